@@ -61,7 +61,7 @@ mod compile {
     };
 
     fn ruby() -> CompileOptions {
-        CompileOptions { flavor: RegexFlavor::Ruby, ..Default::default() }
+        CompileOptions::default().with_flavor(RegexFlavor::Ruby)
     }
 
     macro_rules! group_item {
@@ -107,7 +107,7 @@ pub fn range(bencher: divan::Bencher, n: usize) {
             expr.unwrap()
         })
         .bench_refs(|expr| {
-            let options = CompileOptions { max_range_size: 100, ..Default::default() };
+            let options = CompileOptions::default().with_max_range_size(100);
             let compiled = expr.compile(&input, options);
             unwrap_compiled(compiled)
         })
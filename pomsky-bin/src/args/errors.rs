@@ -7,9 +7,20 @@ pub(crate) enum ParseArgsError {
     UnexpectedTwice(&'static str),
     NoInput,
     NoPath,
+    NoReplacement,
     InputAndPath,
+    FixWithoutPath,
+    UnsafeFixesWithoutFix,
+    WatchWithoutPath,
     UnknownFlavor(String),
+    InvalidFlavorVersion(String),
     UnknownEngine(String),
+    UnknownReporter(String),
+    UnknownFormat(String),
+    JsonAndReporter,
+    JsonAndFormat,
+    FormatAndReporter,
+    SarifUnsupported,
     UnknownList(String),
     WarningsNoEquals(String),
     WarningsNoZero(String),
@@ -36,19 +47,58 @@ impl fmt::Display for ParseArgsError {
             ParseArgsError::UnknownFlavor(flavor) => write!(
                 f,
                 "`{flavor}` isn't a valid flavor\n\
-                    possible values: pcre, python, java, javascript, dotnet, ruby, rust"
+                    possible values: pcre, python, python-regex, java, javascript, dotnet, ruby, rust, \
+                    re2, postgres"
+            ),
+            ParseArgsError::InvalidFlavorVersion(version) => write!(
+                f,
+                "`{version}` isn't a valid flavor version\n\
+                    try a version like `--flavor=python@3.11`"
             ),
             ParseArgsError::UnknownEngine(engine) => {
                 write!(f, "`{engine}` isn't a valid regex engine\npossible values: pcre2, rust")
             }
+            ParseArgsError::UnknownReporter(reporter) => write!(
+                f,
+                "`{reporter}` isn't a valid reporter\npossible values: pretty, json, junit, tap, sarif"
+            ),
+            ParseArgsError::UnknownFormat(format) => {
+                write!(f, "`{format}` isn't a valid format\npossible values: pretty, json, sarif")
+            }
+            ParseArgsError::JsonAndFormat => {
+                write!(f, "`--json` can't be combined with another `--format` than `json`")
+            }
+            ParseArgsError::FormatAndReporter => {
+                write!(f, "`--format` and `--reporter` can't select different formats")
+            }
+            ParseArgsError::SarifUnsupported => write!(
+                f,
+                "`--format sarif` is only supported when compiling or testing pomsky expressions"
+            ),
+            ParseArgsError::JsonAndReporter => {
+                write!(f, "`--json` can't be combined with another `--reporter` than `json`")
+            }
             ParseArgsError::UnknownList(list) => {
                 write!(f, "`{list}` isn't a valid list\npossible values: shorthands")
             }
             ParseArgsError::NoInput => write!(f, "No input provided"),
             ParseArgsError::NoPath => write!(f, "No file path provided"),
+            ParseArgsError::NoReplacement => {
+                write!(f, "No replacement provided\ntry `--with '<TEMPLATE>'`")
+            }
             ParseArgsError::InputAndPath => {
                 write!(f, "You can only provide an input or a path, but not both")
             }
+            ParseArgsError::FixWithoutPath => {
+                write!(f, "`--fix` can only be used with a file, e.g. `--fix --path file.pomsky`")
+            }
+            ParseArgsError::UnsafeFixesWithoutFix => {
+                write!(f, "`--unsafe-fixes` can only be used together with `--fix`")
+            }
+            ParseArgsError::WatchWithoutPath => write!(
+                f,
+                "`--watch` can only be used with a file, e.g. `--watch --path file.pomsky`"
+            ),
             ParseArgsError::WarningsNoEquals(warning) => write!(
                 f,
                 "`{warning}` contains no `=`\n\
@@ -1,20 +1,60 @@
 use std::ffi::OsString;
 
-use pomsky::options::RegexFlavor;
+use pomsky::options::{FlavorSet, FlavorVersion, RegexFlavor};
 
 use super::ParseArgsError;
 
-pub(super) fn parse_flavor(value: OsString) -> Result<RegexFlavor, ParseArgsError> {
+pub(super) type Flavors = (RegexFlavor, Option<FlavorVersion>, FlavorSet);
+
+/// Parses a comma-separated list of flavors, e.g. `rust,js@2018,java`.
+///
+/// The first flavor is the primary one; the others are returned as a set of
+/// additional flavors the regex must be compatible with.
+pub(super) fn parse_flavor(value: OsString) -> Result<Flavors, ParseArgsError> {
     let lower = value.to_string_lossy().to_ascii_lowercase();
-    Ok(match lower.as_str() {
+    let mut parts = lower.split(',').map(str::trim);
+
+    let (flavor, version) = parse_flavor_with_version(parts.next().unwrap_or_default())?;
+    let mut additional = FlavorSet::new();
+    for part in parts {
+        let (flavor, version) = parse_flavor_with_version(part)?;
+        additional = additional.add(flavor, version);
+    }
+    Ok((flavor, version, additional))
+}
+
+/// Parses a flavor with an optional version, e.g. `python@3.11`
+fn parse_flavor_with_version(
+    lower: &str,
+) -> Result<(RegexFlavor, Option<FlavorVersion>), ParseArgsError> {
+    let (flavor, version) = match lower.split_once('@') {
+        Some((flavor, version)) => (flavor, Some(version)),
+        None => (lower, None),
+    };
+
+    let flavor = parse_flavor_name(flavor)?;
+    let version = version
+        .map(|v| {
+            // ECMAScript editions may be written as `es2018`
+            let v = if flavor == RegexFlavor::JavaScript { v.trim_start_matches("es") } else { v };
+            v.parse().map_err(|_| ParseArgsError::InvalidFlavorVersion(v.to_string()))
+        })
+        .transpose()?;
+    Ok((flavor, version))
+}
+
+fn parse_flavor_name(lower: &str) -> Result<RegexFlavor, ParseArgsError> {
+    Ok(match lower {
         "pcre" => RegexFlavor::Pcre,
         "python" => RegexFlavor::Python,
+        "python-regex" => RegexFlavor::PythonRegex,
         "java" => RegexFlavor::Java,
         "js" | "javascript" => RegexFlavor::JavaScript,
         "dotnet" | ".net" => RegexFlavor::DotNet,
         "ruby" => RegexFlavor::Ruby,
         "rust" => RegexFlavor::Rust,
         "re2" => RegexFlavor::RE2,
-        _ => return Err(ParseArgsError::UnknownFlavor(lower)),
+        "postgres" | "postgresql" => RegexFlavor::Postgres,
+        _ => return Err(ParseArgsError::UnknownFlavor(lower.to_string())),
     })
 }
@@ -0,0 +1,23 @@
+use std::ffi::OsString;
+
+use super::ParseArgsError;
+
+/// Output format selected with `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Pretty,
+    Json,
+    Sarif,
+}
+
+impl OutputFormat {
+    pub(crate) fn parse(value: OsString) -> Result<Self, ParseArgsError> {
+        let lower = value.to_string_lossy().to_ascii_lowercase();
+        Ok(match lower.as_str() {
+            "pretty" => OutputFormat::Pretty,
+            "json" => OutputFormat::Json,
+            "sarif" => OutputFormat::Sarif,
+            _ => return Err(ParseArgsError::UnknownFormat(lower)),
+        })
+    }
+}
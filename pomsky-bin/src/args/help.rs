@@ -17,18 +17,21 @@ const FLAVORS: &[HelpSection] = sections![
     table Compact {
         "pcre"       => { ["PCRE2 regex engines, compatible with Perl, PHP and R"] }
         "python"     => { ["Python's " c!"re" " module"] }
+        "python-regex" => { ["Python's third-party " c!"regex" " module"] }
         "java"       => { ["Java's " c!"Pattern" " class, compatible with Kotlin and Scala"] }
         "javascript" => { ["ECMAScript regular expressions"] }
         "dotnet"     => { [c!"Regex" " class in .NET languages such C# and F#"] }
         "ruby"       => { ["Ruby's built-in regular expressions"] }
         "rust"       => { ["Rust's " c!"regex" " crate"] }
         "re2"        => { ["The RE2 engine, compatible with Go's " c!"regexp" " package"] }
+        "postgres"   => { ["PostgreSQL's advanced regular expressions"] }
     }
 ];
 
 const ENGINES: &[HelpSection] = sections![
     table Compact {
         "pcre2"       => { ["PCRE2 regex engine, using the " c:"pcre" " flavor"] }
+        "rust"        => { ["Rust's " c!"regex" " crate, using the " c:"rust" " flavor"] }
     }
 ];
 
@@ -59,9 +62,10 @@ const FEATURES: &[HelpSection] = sections! [
 const WARNINGS: &[HelpSection] = sections![
     Short ["Disable certain warnings (disable all with " c:"-W0" ")"]
     Long ["Disable some or all warnings. A single warning can be disabled by specifying
-the name followed by " c:"=0" ", for example:
+the name or code followed by " c:"=0" ", for example:
 
     " c!"-Wcompat=0" "
+    " c!"-WP0105=0" "
 
 Multiple warnings can be disabled by setting this option multiple times, or
 using a comma-separated list:
@@ -69,9 +73,10 @@ using a comma-separated list:
     " c!"-Wcompat=0 -Wdeprecated=0
     -Wcompat=0,deprecated=0" "
 
-To disable all warnings, use " c:"-W0" ".
+To disable all warnings, use " c:"-W0" ". Warnings can also be disabled in the
+expression with an " c:"allow" " statement, e.g. " c:"allow deprecated;" ".
 
-Currently, the following warnings can be disabled:"]
+Currently, the following kinds of warnings can be disabled:"]
     Long table Compact {
         "compat"     => { ["Compatibility warnings"] }
         "deprecated" => { ["A used feature will be removed in the future"] }
@@ -101,6 +106,36 @@ pub(super) const HELP: Help = Help(sections![
                 Long ["\n\
                 Run " c:"pomsky test --help" " for more information"]
             }
+            "pomsky import" => {
+                ["Convert a regex to a pomsky expression"]
+                Long ["\n\
+                Run " c:"pomsky import --help" " for more information"]
+            }
+            "pomsky fmt" => {
+                ["Format pomsky expressions"]
+                Long ["\n\
+                Run " c:"pomsky fmt --help" " for more information"]
+            }
+            "pomsky parse" => {
+                ["Print the syntax tree of a pomsky expression"]
+                Long ["\n\
+                Run " c:"pomsky parse --help" " for more information"]
+            }
+            "pomsky explain" => {
+                ["Explain a pomsky expression in plain English"]
+                Long ["\n\
+                Run " c:"pomsky explain --help" " for more information"]
+            }
+            "pomsky match" => {
+                ["Search files for lines matching a pomsky expression"]
+                Long ["\n\
+                Run " c:"pomsky match --help" " for more information"]
+            }
+            "pomsky replace" => {
+                ["Replace matches of a pomsky expression in files"]
+                Long ["\n\
+                Run " c:"pomsky replace --help" " for more information"]
+            }
         }
     }
 
@@ -121,9 +156,20 @@ pub(super) const HELP: Help = Help(sections![
                 ["Comma-separated list of allowed features [default: all enabled]"]
                 Long ["Supported features are listed below."]
             }
+            "    --deny-warnings" => {
+                ["Turn all warnings into errors"]
+                Long ["Warnings disabled with " c:"-W" " are not affected."]
+            }
             "-f, --flavor <FLAVOR>" => {
                 ["Regex flavor [default: " c:"pcre" "]"]
                 Long ["Supported flavors are listed below."]
+                Long ["A minimum version can be appended, e.g. " c:"python@3.11" "."]
+                Long ["Multiple comma-separated flavors produce a regex that works in all of them, e.g. " c:"rust,js,java" "."]
+            }
+            "    --fix" => {
+                ["Fix errors and warnings automatically in the file given with " c:"--path"]
+                Long ["The file is modified in place. Problems that can't be fixed automatically are reported as usual.
+Only fixes that don't change what the expression matches are applied, unless " c:"--unsafe-fixes" " is given."]
             }
             "-h, --help" => {
                 ["Print help information"]
@@ -138,16 +184,29 @@ pub(super) const HELP: Help = Help(sections![
             "-p, --path <FILE>" => {
                 ["File containing the pomsky expression to compile"]
             }
+            "    --unsafe-fixes" => {
+                ["Also apply fixes that may change what the expression matches, with " c:"--fix"]
+                Long ["For example, typos in variable names are replaced with the most similar name."]
+            }
             "-V, --version" => {
                 ["Print version information"]
             }
             "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --watch" => {
+                ["Compile the file given with " c:"--path" " again whenever it changes"]
+                Long ["The screen is cleared before every compilation. Press Ctrl+C to stop."]
+            }
             "-d, --debug" => {
                 Long ["Show debug information"]
             }
             "    --json" => {
                 Long ["Return output as JSON"]
             }
+            "    --format <FORMAT>" => {
+                Long ["Output format, either " c:"pretty" ", " c:"json" " (same as " c:"--json" ") or \
+" c:"sarif" ". SARIF only contains the diagnostics, including their quick fixes, and can be uploaded \
+to code scanning tools."]
+            }
         }
     }
 
@@ -184,6 +243,10 @@ pub(super) const TEST_HELP: Help = Help(sections![
                 ["Comma-separated list of allowed features [default: all enabled]"]
                 Long ["Supported features are listed below."]
             }
+            "    --deny-warnings" => {
+                ["Turn all warnings into errors"]
+                Long ["Warnings disabled with " c:"-W" " are not affected."]
+            }
             "-f, --flavor <FLAVOR>" => {
                 ["Regex flavor"]
                 Long ["Supported flavors are listed below."]
@@ -205,9 +268,25 @@ Note that pomsky respects " c:".gitignore" " files."]
             "    --pass-with-no-tests" => {
                 ["Don't error if the specified directory contains no " c:"*.pomsky" " files"]
             }
+            "    --reporter <REPORTER>" => {
+                ["How to report test results [default: " c:"pretty" "]"]
+                Long ["Supported reporters are " c:"pretty" ", " c:"json" ", " c:"junit" " (JUnit XML), \
+" c:"tap" " (Test Anything Protocol) and " c:"sarif" ". All of them except " c:"pretty" " print \
+their report on stdout after all tests ran. " c:"json" ", " c:"junit" " and " c:"tap" " report each \
+test case individually, " c:"sarif" " reports the failures and other diagnostics."]
+            }
             "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --watch" => {
+                ["Run the tests again whenever a " c:"*.pomsky" " file changes"]
+                Long ["Only the files that changed are tested again. The screen is cleared before \
+every run. Press Ctrl+C to stop."]
+            }
             "    --json" => {
-                Long ["Report test results as JSON"]
+                Long ["Report test results as JSON, same as " c:"--reporter json"]
+            }
+            "    --format <FORMAT>" => {
+                Long ["Output format, either " c:"pretty" ", " c:"json" " or " c:"sarif" ". This is a \
+shorthand for the " c:"--reporter" " with the same name."]
             }
         }
     }
@@ -217,6 +296,336 @@ Note that pomsky respects " c:".gitignore" " files."]
     Long "Features" FEATURES
 ]);
 
+pub(super) const IMPORT_HELP: Help = Help(sections![
+    [g!"pomsky import " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Convert a regex to a pomsky expression\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky import [OPTIONS] <INPUT>\n\
+        pomsky import [OPTIONS] --path <PATH>\n\
+        command | pomsky import [OPTIONS]"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Regex to convert"]
+                Long ["\n\
+                Constructs that can't be expressed in pomsky are embedded with the " c:"regex" " keyword,\n\
+                and a warning is shown for them."]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "    --deny-warnings" => {
+                ["Turn all warnings into errors"]
+                Long ["Warnings disabled with " c:"-W" " are not affected."]
+            }
+            "-f, --flavor <FLAVOR>" => {
+                ["Regex flavor of the input [default: " c:"pcre" "]"]
+                Long ["Supported flavors are listed below."]
+            }
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "-p, --path <FILE>" => {
+                ["File containing the regex to convert"]
+            }
+            "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --json" => {
+                Long ["Return output as JSON"]
+            }
+        }
+    }
+
+    Long "Flavors" FLAVORS
+]);
+
+pub(super) const FMT_HELP: Help = Help(sections![
+    [g!"pomsky fmt " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Format pomsky expressions in the canonical style\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky fmt [OPTIONS] <INPUT>\n\
+        pomsky fmt [OPTIONS] --path <PATH>\n\
+        command | pomsky fmt [OPTIONS]"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Pomsky expression to format"]
+                Long ["\n\
+                The formatted expression is printed to stdout."]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "    --check" => {
+                ["Don't change anything, but fail if the input is not formatted"]
+                Long ["Files that are not formatted are listed."]
+            }
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "    --indent <N>" => {
+                ["Number of spaces per indentation level [default: 2]"]
+            }
+            "-p, --path <PATH>" => {
+                ["File or directory containing the pomsky expressions to format"]
+                Long ["If a directory is specified, all contained " c:"*.pomsky" " files are formatted.
+Files are formatted in place. Note that pomsky respects " c:".gitignore" " files."]
+            }
+            "    --width <N>" => {
+                ["Preferred maximum line width [default: 80]"]
+            }
+            "    --json" => {
+                Long ["Report syntax errors as JSON"]
+            }
+        }
+    }
+]);
+
+pub(super) const PARSE_HELP: Help = Help(sections![
+    [g!"pomsky parse " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Print the syntax tree of a pomsky expression\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky parse [OPTIONS] <INPUT>\n\
+        pomsky parse [OPTIONS] --path <PATH>\n\
+        command | pomsky parse [OPTIONS]"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Pomsky expression to parse"]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "-p, --path <FILE>" => {
+                ["File containing the pomsky expression to parse"]
+            }
+            "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --json" => {
+                ["Print the syntax tree as JSON"]
+                Long ["The JSON object has a " c:"version" " field, which changes when the format
+of the syntax tree changes. Each node includes its span, i.e. its start and end
+byte offset in the input."]
+            }
+        }
+    }
+]);
+
+pub(super) const EXPLAIN_HELP: Help = Help(sections![
+    [g!"pomsky explain " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Explain a pomsky expression in plain English\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky explain [OPTIONS] <INPUT>\n\
+        pomsky explain [OPTIONS] --path <PATH>\n\
+        command | pomsky explain [OPTIONS]"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Pomsky expression to explain"]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "-f, --flavor <FLAVOR>" => {
+                ["Regex flavor [default: " c:"pcre" "]"]
+                Long ["The explanation includes notes about behaviour that is specific to this flavor.
+Supported flavors are listed below."]
+            }
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "-p, --path <FILE>" => {
+                ["File containing the pomsky expression to explain"]
+            }
+            "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --json" => {
+                ["Print the explanation as JSON"]
+                Long ["Each part of the explanation includes its span, i.e. its start and end byte
+offset in the input, and the variable it was inserted from."]
+            }
+        }
+    }
+
+    Long "Flavors" FLAVORS
+]);
+
+pub(super) const MATCH_HELP: Help = Help(sections![
+    [g!"pomsky match " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Search files for lines matching a pomsky expression\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky match [OPTIONS] <INPUT> [PATH]...\n\
+        pomsky match [OPTIONS] --path <FILE> [PATH]...\n\
+        command | pomsky match [OPTIONS] <INPUT>"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Pomsky expression to search for"]
+            }
+            "[PATH]..." => {
+                ["Files or directories to search [default: stdin]"]
+                Long ["Directories are searched recursively. Note that pomsky respects " c:".gitignore" " files,
+and skips hidden files and files that aren't valid UTF-8."]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "    --allowed-features <FEATURE>..." => {
+                ["Comma-separated list of allowed features [default: all enabled]"]
+                Long ["Supported features are listed below."]
+            }
+            "-c, --count" => {
+                ["Only print the number of matching lines of each file"]
+            }
+            "-e, --engine <ENGINE>" => {
+                ["Regex engine used for searching [default: " c:"pcre2" "]"]
+                Long ["If " c:"--flavor" " is specified, the engine can be omitted."]
+            }
+            "-f, --flavor <FLAVOR>" => {
+                ["Regex flavor"]
+                Long ["Supported flavors are listed below."]
+                Long ["If " c:"--engine" " is specified, the flavor can be omitted."]
+            }
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "-o, --only-matching" => {
+                ["Print only the matched parts of each line"]
+            }
+            "-p, --path <FILE>" => {
+                ["File containing the pomsky expression to search for"]
+            }
+            "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+            "    --json" => {
+                Long ["Print the matches as JSON, including the offsets of all matches and named
+capturing groups"]
+            }
+        }
+    }
+
+    Long "Flavors" FLAVORS
+    Long "Engines" ENGINES
+    Long "Features" FEATURES
+]);
+
+pub(super) const REPLACE_HELP: Help = Help(sections![
+    [g!"pomsky replace " {env!("CARGO_PKG_VERSION")}]
+    Long ["Home page: https://pomsky-lang.org"]
+    ["\n\
+    Replace matches of a pomsky expression in files\n\
+    \n\
+    Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+
+    "Usage" {
+        ["pomsky replace [OPTIONS] <INPUT> --with <TEMPLATE> [PATH]...\n\
+        pomsky replace [OPTIONS] --path <FILE> --with <TEMPLATE> [PATH]...\n\
+        command | pomsky replace [OPTIONS] <INPUT> --with <TEMPLATE>"]
+    }
+
+    "Args" {
+        table Auto {
+            "<INPUT>" => {
+                ["Pomsky expression to search for"]
+            }
+            "[PATH]..." => {
+                ["Files or directories to edit in place [default: stdin]"]
+                Long ["When reading from stdin, the result is printed to stdout. Directories are
+searched recursively. Note that pomsky respects " c:".gitignore" " files, and skips hidden files
+and files that aren't valid UTF-8."]
+            }
+        }
+    }
+
+    "Options" {
+        table Auto {
+            "    --allowed-features <FEATURE>..." => {
+                ["Comma-separated list of allowed features [default: all enabled]"]
+                Long ["Supported features are listed below."]
+            }
+            "    --dry-run" => {
+                ["Print a diff of the changes instead of editing the files"]
+            }
+            "-e, --engine <ENGINE>" => {
+                ["Regex engine used for searching [default: " c:"pcre2" "]"]
+                Long ["If " c:"--flavor" " is specified, the engine can be omitted."]
+            }
+            "-f, --flavor <FLAVOR>" => {
+                ["Regex flavor"]
+                Long ["Supported flavors are listed below."]
+                Long ["If " c:"--engine" " is specified, the flavor can be omitted."]
+            }
+            "-h, --help" => {
+                ["Print help information"]
+                Long ["Use " c:"-h" " for short descriptions and " c:"--help" " for more details."]
+            }
+            "-p, --path <FILE>" => {
+                ["File containing the pomsky expression to search for"]
+            }
+            "-w, --with <TEMPLATE>" => {
+                ["Replacement for each match"]
+                Long ["The template can insert capturing groups by name or index, e.g. "
+c:"${year}-${month}" " or " c:"${0}" " for the whole match. Use " c:"$$" " for a literal " c:"$" ".
+Referring to a group that doesn't exist in the expression is an error."]
+            }
+            "-W, --warnings <DIAGNOSTICS>" => WARNINGS
+        }
+    }
+
+    Long "Flavors" FLAVORS
+    Long "Engines" ENGINES
+    Long "Features" FEATURES
+]);
+
 pub(crate) fn print_usage_and_help() {
     let _ = USAGE.write(&mut std::io::stderr().lock(), false, is_colored(Stream::Stderr));
 }
@@ -237,6 +646,54 @@ pub(crate) fn print_test_long_help() {
     let _ = TEST_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
 }
 
+pub(crate) fn print_import_short_help() {
+    let _ = IMPORT_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_import_long_help() {
+    let _ = IMPORT_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_fmt_short_help() {
+    let _ = FMT_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_fmt_long_help() {
+    let _ = FMT_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_parse_short_help() {
+    let _ = PARSE_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_parse_long_help() {
+    let _ = PARSE_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_match_short_help() {
+    let _ = MATCH_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_match_long_help() {
+    let _ = MATCH_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_explain_short_help() {
+    let _ = EXPLAIN_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_explain_long_help() {
+    let _ = EXPLAIN_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_replace_short_help() {
+    let _ = REPLACE_HELP.write(&mut std::io::stdout().lock(), false, is_colored(Stream::Stdout));
+}
+
+pub(crate) fn print_replace_long_help() {
+    let _ = REPLACE_HELP.write(&mut std::io::stdout().lock(), true, is_colored(Stream::Stdout));
+}
+
 fn is_colored(stream: Stream) -> bool {
     matches!(
         supports_color::on_cached(stream),
@@ -1,12 +1,15 @@
 use std::path::PathBuf;
 
-use pomsky::{features::PomskyFeatures, options::RegexFlavor};
+use pomsky::{
+    features::PomskyFeatures,
+    options::{FlavorSet, FlavorVersion, Lints, RegexFlavor},
+};
 
 pub(crate) use self::engines::RegexEngine;
+pub(crate) use self::reporters::Reporter;
 pub(crate) use errors::ParseArgsError;
 pub(crate) use help::print_usage_and_help;
 pub(crate) use input::Input;
-pub(crate) use warnings::DiagnosticSet;
 
 use crate::format::Logger;
 
@@ -16,9 +19,11 @@ mod engines;
 mod errors;
 mod features;
 mod flavors;
+mod formats;
 mod help;
 mod input;
 mod parse;
+mod reporters;
 mod warnings;
 
 #[derive(PartialEq)]
@@ -27,18 +32,31 @@ pub(crate) struct GlobalOptions {
     pub(crate) debug: bool,
     /// Whether output should be provided as JSON
     pub(crate) json: bool,
+    /// Whether diagnostics should be reported in the SARIF format, which is
+    /// based on JSON. If this is set, `json` is set as well
+    pub(crate) sarif: bool,
     /// Regex flavor
     pub(crate) flavor: Option<RegexFlavor>,
+    /// Oldest supported version of the regex flavor
+    pub(crate) flavor_version: Option<FlavorVersion>,
+    /// Additional regex flavors the output must be compatible with
+    pub(crate) additional_flavors: FlavorSet,
     /// Set of allowed pomsky features
     pub(crate) allowed_features: PomskyFeatures,
-    /// Set of warnings that should be emitted
-    pub(crate) warnings: DiagnosticSet,
+    /// Which warnings should be emitted or turned into errors
+    pub(crate) lints: Lints,
 }
 
 #[derive(PartialEq)]
 pub(crate) enum Subcommand {
     Compile(CompileOptions),
     Test(TestOptions),
+    Import(ImportOptions),
+    Fmt(FmtOptions),
+    Parse(ParseOptions),
+    Explain(ExplainOptions),
+    Match(MatchOptions),
+    Replace(ReplaceOptions),
 }
 
 #[derive(PartialEq)]
@@ -51,6 +69,13 @@ pub(crate) struct CompileOptions {
     pub(crate) test: Option<RegexEngine>,
     /// Whether to output the compiled expression. If false, a test report is printed instead
     pub(crate) in_test_suite: bool,
+    /// Whether to apply quick fixes to the input file before compiling it
+    pub(crate) fix: bool,
+    /// Whether to also apply quick fixes that may change what the expression
+    /// matches
+    pub(crate) unsafe_fixes: bool,
+    /// Whether to recompile the input file whenever it changes
+    pub(crate) watch: bool,
 }
 
 /// Test Pomsky expressions
@@ -62,17 +87,96 @@ pub(crate) struct TestOptions {
     pub(crate) engine: Option<RegexEngine>,
     /// Whether to pass even if no expressions were compiled
     pub(crate) pass_with_no_tests: bool,
+    /// Whether to re-run the tests of files that changed
+    pub(crate) watch: bool,
+    /// How the test results are reported
+    pub(crate) reporter: Reporter,
+}
+
+/// Convert a regex to a Pomsky expression
+#[derive(PartialEq)]
+pub(crate) struct ImportOptions {
+    /// Regex to convert
+    pub(crate) input: Input,
+}
+
+/// Format Pomsky expressions
+#[derive(PartialEq)]
+pub(crate) struct FmtOptions {
+    /// Pomsky expression, or file or directory containing expressions to format
+    pub(crate) input: Input,
+    /// Whether to only check if the input is formatted, without changing it
+    pub(crate) check: bool,
+    /// Number of spaces per indentation level
+    pub(crate) indent: Option<usize>,
+    /// Preferred maximum line width
+    pub(crate) width: Option<usize>,
+}
+
+/// Print the syntax tree of a Pomsky expression
+#[derive(PartialEq)]
+pub(crate) struct ParseOptions {
+    /// Pomsky expression to parse
+    pub(crate) input: Input,
+}
+
+#[derive(PartialEq)]
+pub(crate) struct ExplainOptions {
+    /// Pomsky expression to explain
+    pub(crate) input: Input,
+}
+
+/// Search files with a Pomsky expression
+#[derive(PartialEq)]
+pub(crate) struct MatchOptions {
+    /// Pomsky expression to search for
+    pub(crate) input: Input,
+    /// Files or directories to search. If empty, stdin is searched
+    pub(crate) paths: Vec<PathBuf>,
+    /// Regex engine used for searching
+    pub(crate) engine: Option<RegexEngine>,
+    /// Whether to only print the number of matching lines
+    pub(crate) count: bool,
+    /// Whether to print only the matched parts of each line
+    pub(crate) only_matching: bool,
+}
+
+/// Replace matches of a Pomsky expression in files
+#[derive(PartialEq)]
+pub(crate) struct ReplaceOptions {
+    /// Pomsky expression to search for
+    pub(crate) input: Input,
+    /// Files or directories to edit. If empty, stdin is read and the result is printed
+    pub(crate) paths: Vec<PathBuf>,
+    /// Replacement template, which may refer to capturing groups
+    pub(crate) replacement: String,
+    /// Regex engine used for searching
+    pub(crate) engine: Option<RegexEngine>,
+    /// Whether to print a diff instead of editing the files
+    pub(crate) dry_run: bool,
 }
 
 pub(super) fn parse_args(logger: &Logger) -> Result<(Subcommand, GlobalOptions), ParseArgsError> {
     match parse::parse_args_inner(logger, lexopt::Parser::from_env())? {
-        Parsed::Options(subcommand, opts) => Ok((subcommand, opts)),
+        Parsed::Options(subcommand, opts) => Ok((subcommand, *opts)),
         Parsed::Help(help) => {
             match help {
                 parse::Help::Short => help::print_short_help(),
                 parse::Help::Long => help::print_long_help(),
                 parse::Help::TestShort => help::print_test_short_help(),
                 parse::Help::TestLong => help::print_test_long_help(),
+                parse::Help::ImportShort => help::print_import_short_help(),
+                parse::Help::ImportLong => help::print_import_long_help(),
+                parse::Help::FmtShort => help::print_fmt_short_help(),
+                parse::Help::FmtLong => help::print_fmt_long_help(),
+                parse::Help::ParseShort => help::print_parse_short_help(),
+                parse::Help::ParseLong => help::print_parse_long_help(),
+                parse::Help::ExplainShort => help::print_explain_short_help(),
+                parse::Help::ExplainLong => help::print_explain_long_help(),
+                parse::Help::MatchShort => help::print_match_short_help(),
+                parse::Help::MatchLong => help::print_match_long_help(),
+                parse::Help::ReplaceShort => help::print_replace_short_help(),
+                parse::Help::ReplaceLong => help::print_replace_long_help(),
             }
             std::process::exit(0)
         }
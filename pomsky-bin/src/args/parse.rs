@@ -1,18 +1,24 @@
 use std::io::{IsTerminal, stdin, stdout};
 use std::path::PathBuf;
 
-use pomsky::{features::PomskyFeatures, options::RegexFlavor};
+use pomsky::{
+    features::PomskyFeatures,
+    options::{LintLevel, Lints},
+};
 
-use crate::args::RegexEngine;
+use crate::args::{RegexEngine, Reporter};
 use crate::format::Logger;
 
+use super::flavors::Flavors;
+use super::formats::OutputFormat;
 use super::{
-    CompileOptions, DiagnosticSet, GlobalOptions, Input, ParseArgsError, Subcommand, TestOptions,
+    CompileOptions, ExplainOptions, FmtOptions, GlobalOptions, ImportOptions, Input, MatchOptions,
+    ParseArgsError, ParseOptions, ReplaceOptions, Subcommand, TestOptions,
 };
 
 #[derive(PartialEq)]
 pub(super) enum Parsed {
-    Options(Subcommand, GlobalOptions),
+    Options(Subcommand, Box<GlobalOptions>),
     Help(Help),
     Version,
     List(ListKind),
@@ -31,6 +37,18 @@ pub(super) enum Help {
     Long,
     TestShort,
     TestLong,
+    ImportShort,
+    ImportLong,
+    FmtShort,
+    FmtLong,
+    ParseShort,
+    ParseLong,
+    ExplainShort,
+    ExplainLong,
+    MatchShort,
+    MatchLong,
+    ReplaceShort,
+    ReplaceLong,
 }
 
 #[derive(PartialEq)]
@@ -40,10 +58,11 @@ pub(super) enum ListKind {
 
 struct RootParser {
     debug: bool,
-    flavor: Option<RegexFlavor>,
+    flavor: Option<Flavors>,
     allowed_features: Option<PomskyFeatures>,
-    warnings: DiagnosticSet,
+    lints: Lints,
     json: Option<bool>,
+    format: Option<OutputFormat>,
 }
 
 // we have to do this to deduplicate code without creating borrowcheck issues, because `parser` is a lending iterator
@@ -56,12 +75,18 @@ macro_rules! parse_root_arg {
                     .flavor
                     .set_arg(super::flavors::parse_flavor($parser.value()?)?, "--flavor")?;
             }
-            Short('W') | Long("warnings") => $root.warnings.parse($parser.value()?)?,
+            Short('W') | Long("warnings") => {
+                $root.lints = super::warnings::parse_warnings($root.lints, $parser.value()?)?;
+            }
+            Long("deny-warnings") => $root.lints = $root.lints.set_default(LintLevel::Deny),
             Long("allowed-features") => $root.allowed_features.set_arg(
                 super::features::parse_features($logger, $parser.value()?)?,
                 "--allowed-features",
             )?,
             Long("json") => $root.json.set_arg(true, "--json")?,
+            Long("format") => {
+                $root.format.set_arg(OutputFormat::parse($parser.value()?)?, "--format")?
+            }
             Short('h') => return Ok(Parsed::Help(Help::Short)),
             Long("help") => return Ok(Parsed::Help(Help::Long)),
             Short('V') | Long("version") => return Ok(Parsed::Version),
@@ -78,6 +103,9 @@ macro_rules! parse_compile_options {
                 $self.test.set_arg(RegexEngine::parse($parser.value()?)?, "--test")?;
             }
             Short('n') | Long("no-new-line") => $self.no_new_line.set_arg(true, "--no-new-line")?,
+            Long("fix") => $self.fix.set_arg(true, "--fix")?,
+            Long("unsafe-fixes") => $self.unsafe_fixes.set_arg(true, "--unsafe-fixes")?,
+            Long("watch") => $self.watch.set_arg(true, "--watch")?,
             Value(val) if $self.input_value.is_none() => {
                 $self.input_value = Some(val.into_string().map_err(lexopt::Error::from)?);
             }
@@ -92,8 +120,9 @@ impl RootParser {
             debug: false,
             flavor: None,
             allowed_features: None,
-            warnings: DiagnosticSet::default(),
+            lints: Lints::default(),
             json: None,
+            format: None,
         }
     }
 
@@ -116,6 +145,12 @@ impl RootParser {
                 Ok(Parsed::List(ListKind::Shorthands))
             }
             Value(val) if val == "test" => TestParser::new(self).parse(logger, parser),
+            Value(val) if val == "import" => ImportParser::new(self).parse(logger, parser),
+            Value(val) if val == "fmt" => FmtParser::new(self).parse(logger, parser),
+            Value(val) if val == "parse" => AstParser::new(self).parse(logger, parser),
+            Value(val) if val == "explain" => ExplainParser::new(self).parse(logger, parser),
+            Value(val) if val == "match" => MatchParser::new(self).parse(logger, parser),
+            Value(val) if val == "replace" => ReplaceParser::new(self).parse(logger, parser),
             arg => {
                 let mut compile_parser = CompileParser::new(self);
                 parse_compile_options!(logger, arg, parser, compile_parser);
@@ -125,15 +160,30 @@ impl RootParser {
     }
 
     fn finish(self, subcommand: Subcommand) -> Result<Parsed, ParseArgsError> {
+        // `--json` is a shorthand for `--format json`
+        let format = match (self.format, self.json.unwrap_or_default()) {
+            (None, true) | (Some(OutputFormat::Json), _) => OutputFormat::Json,
+            (Some(_), true) => return Err(ParseArgsError::JsonAndFormat),
+            (None, false) => OutputFormat::Pretty,
+            (Some(format), false) => format,
+        };
+        let sarif = format == OutputFormat::Sarif;
+        if sarif && !matches!(subcommand, Subcommand::Compile(_) | Subcommand::Test(_)) {
+            return Err(ParseArgsError::SarifUnsupported);
+        }
+
         Ok(Parsed::Options(
             subcommand,
-            GlobalOptions {
-                flavor: self.flavor,
+            Box::new(GlobalOptions {
+                flavor: self.flavor.map(|(flavor, ..)| flavor),
+                flavor_version: self.flavor.and_then(|(_, version, _)| version),
+                additional_flavors: self.flavor.map(|(.., set)| set).unwrap_or_default(),
                 debug: self.debug,
-                json: self.json.unwrap_or_default(),
+                json: format != OutputFormat::Pretty,
+                sarif,
                 allowed_features: self.allowed_features.unwrap_or_default(),
-                warnings: self.warnings,
-            },
+                lints: self.lints,
+            }),
         ))
     }
 }
@@ -144,11 +194,23 @@ struct CompileParser {
     path: Option<PathBuf>,
     no_new_line: bool,
     test: Option<RegexEngine>,
+    fix: bool,
+    unsafe_fixes: bool,
+    watch: bool,
 }
 
 impl CompileParser {
     fn new(root: RootParser) -> Self {
-        Self { root, input_value: None, path: None, no_new_line: false, test: None }
+        Self {
+            root,
+            input_value: None,
+            path: None,
+            no_new_line: false,
+            test: None,
+            fix: false,
+            unsafe_fixes: false,
+            watch: false,
+        }
     }
 
     fn parse(
@@ -171,12 +233,24 @@ impl CompileParser {
             (Some(_), Some(_)) => return Err(ParseArgsError::InputAndPath),
             (None, None) => Input::read_stdin()?,
         };
+        if self.fix && !matches!(input, Input::File(_)) {
+            return Err(ParseArgsError::FixWithoutPath);
+        }
+        if self.unsafe_fixes && !self.fix {
+            return Err(ParseArgsError::UnsafeFixesWithoutFix);
+        }
+        if self.watch && !matches!(input, Input::File(_)) {
+            return Err(ParseArgsError::WatchWithoutPath);
+        }
 
         self.root.finish(Subcommand::Compile(CompileOptions {
             input,
             no_new_line: self.no_new_line,
             test: self.test,
             in_test_suite: false,
+            fix: self.fix,
+            unsafe_fixes: self.unsafe_fixes,
+            watch: self.watch,
         }))
     }
 }
@@ -186,11 +260,20 @@ struct TestParser {
     path: Option<PathBuf>,
     engine: Option<RegexEngine>,
     pass_with_no_tests: Option<bool>,
+    watch: bool,
+    reporter: Option<Reporter>,
 }
 
 impl TestParser {
     fn new(root: RootParser) -> Self {
-        Self { root, path: None, engine: None, pass_with_no_tests: None }
+        Self {
+            root,
+            path: None,
+            engine: None,
+            pass_with_no_tests: None,
+            watch: false,
+            reporter: None,
+        }
     }
 
     fn parse(
@@ -211,6 +294,10 @@ impl TestParser {
                 Long("pass-with-no-tests") => {
                     self.pass_with_no_tests.set_arg(true, "--pass-with-no-tests")?
                 }
+                Long("watch") => self.watch.set_arg(true, "--watch")?,
+                Long("reporter") => {
+                    self.reporter.set_arg(Reporter::parse(parser.value()?)?, "--reporter")?
+                }
                 Short('h') => return Ok(Parsed::Help(Help::TestShort)),
                 Long("help") => return Ok(Parsed::Help(Help::TestLong)),
                 _ => parse_root_arg!(logger, arg, parser, self.root),
@@ -219,13 +306,364 @@ impl TestParser {
         self.finish()
     }
 
-    fn finish(self) -> Result<Parsed, ParseArgsError> {
+    fn finish(mut self) -> Result<Parsed, ParseArgsError> {
         let path = self.path.ok_or(ParseArgsError::NoPath)?;
 
+        // `--json` is a shorthand for `--reporter json`, and `--format sarif`
+        // for `--reporter sarif`
+        let json = self.root.json.unwrap_or_default();
+        let implied = match (json, self.root.format) {
+            (true, None | Some(OutputFormat::Json)) => Some(Reporter::Json),
+            (true, Some(_)) => return Err(ParseArgsError::JsonAndFormat),
+            (false, Some(OutputFormat::Json)) => Some(Reporter::Json),
+            (false, Some(OutputFormat::Sarif)) => Some(Reporter::Sarif),
+            (false, Some(OutputFormat::Pretty) | None) => None,
+        };
+        let reporter = match (self.reporter, implied) {
+            (Some(reporter), Some(implied)) if reporter != implied => {
+                return Err(if json {
+                    ParseArgsError::JsonAndReporter
+                } else {
+                    ParseArgsError::FormatAndReporter
+                });
+            }
+            (Some(reporter), _) | (None, Some(reporter)) => reporter,
+            (None, None) => Reporter::Pretty,
+        };
+        self.root.json = None;
+        self.root.format = Some(match reporter {
+            Reporter::Json => OutputFormat::Json,
+            Reporter::Sarif => OutputFormat::Sarif,
+            Reporter::Pretty | Reporter::Junit | Reporter::Tap => OutputFormat::Pretty,
+        });
+
         self.root.finish(Subcommand::Test(TestOptions {
             path,
             engine: self.engine,
             pass_with_no_tests: self.pass_with_no_tests.unwrap_or_default(),
+            watch: self.watch,
+            reporter,
+        }))
+    }
+}
+
+struct ImportParser {
+    root: RootParser,
+    input_value: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl ImportParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, input_value: None, path: None }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Value(val) if self.input_value.is_none() => {
+                    self.input_value = Some(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::ImportShort)),
+                Long("help") => return Ok(Parsed::Help(Help::ImportLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        let input = match (self.input_value, self.path) {
+            (Some(input), None) => Input::Value(input),
+            (None, Some(path)) => Input::File(path),
+            (Some(_), Some(_)) => return Err(ParseArgsError::InputAndPath),
+            (None, None) => Input::read_stdin()?,
+        };
+
+        self.root.finish(Subcommand::Import(ImportOptions { input }))
+    }
+}
+
+struct FmtParser {
+    root: RootParser,
+    input_value: Option<String>,
+    path: Option<PathBuf>,
+    check: bool,
+    indent: Option<usize>,
+    width: Option<usize>,
+}
+
+impl FmtParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, input_value: None, path: None, check: false, indent: None, width: None }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Long("check") => self.check.set_arg(true, "--check")?,
+                Long("indent") => self.indent.set_arg(parser.value()?.parse()?, "--indent")?,
+                Long("width") => self.width.set_arg(parser.value()?.parse()?, "--width")?,
+                Value(val) if self.input_value.is_none() => {
+                    self.input_value = Some(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::FmtShort)),
+                Long("help") => return Ok(Parsed::Help(Help::FmtLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        let input = match (self.input_value, self.path) {
+            (Some(input), None) => Input::Value(input),
+            (None, Some(path)) => Input::File(path),
+            (Some(_), Some(_)) => return Err(ParseArgsError::InputAndPath),
+            (None, None) => Input::read_stdin()?,
+        };
+
+        self.root.finish(Subcommand::Fmt(FmtOptions {
+            input,
+            check: self.check,
+            indent: self.indent,
+            width: self.width,
+        }))
+    }
+}
+
+/// Parser for the arguments of `pomsky parse`
+struct AstParser {
+    root: RootParser,
+    input_value: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl AstParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, input_value: None, path: None }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Value(val) if self.input_value.is_none() => {
+                    self.input_value = Some(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::ParseShort)),
+                Long("help") => return Ok(Parsed::Help(Help::ParseLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        let input = match (self.input_value, self.path) {
+            (Some(input), None) => Input::Value(input),
+            (None, Some(path)) => Input::File(path),
+            (Some(_), Some(_)) => return Err(ParseArgsError::InputAndPath),
+            (None, None) => Input::read_stdin()?,
+        };
+
+        self.root.finish(Subcommand::Parse(ParseOptions { input }))
+    }
+}
+
+/// Parser for the arguments of `pomsky explain`
+struct ExplainParser {
+    root: RootParser,
+    input_value: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl ExplainParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, input_value: None, path: None }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Value(val) if self.input_value.is_none() => {
+                    self.input_value = Some(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::ExplainShort)),
+                Long("help") => return Ok(Parsed::Help(Help::ExplainLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        let input = match (self.input_value, self.path) {
+            (Some(input), None) => Input::Value(input),
+            (None, Some(path)) => Input::File(path),
+            (Some(_), Some(_)) => return Err(ParseArgsError::InputAndPath),
+            (None, None) => Input::read_stdin()?,
+        };
+
+        self.root.finish(Subcommand::Explain(ExplainOptions { input }))
+    }
+}
+
+/// Parser for the arguments of `pomsky match`
+struct MatchParser {
+    root: RootParser,
+    values: Vec<String>,
+    path: Option<PathBuf>,
+    engine: Option<RegexEngine>,
+    count: bool,
+    only_matching: bool,
+}
+
+impl MatchParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, values: vec![], path: None, engine: None, count: false, only_matching: false }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Short('e') | Long("engine") => {
+                    self.engine.set_arg(RegexEngine::parse(parser.value()?)?, "--engine")?
+                }
+                Short('c') | Long("count") => self.count.set_arg(true, "--count")?,
+                Short('o') | Long("only-matching") => {
+                    self.only_matching.set_arg(true, "--only-matching")?
+                }
+                Value(val) => {
+                    self.values.push(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::MatchShort)),
+                Long("help") => return Ok(Parsed::Help(Help::MatchLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        // stdin is searched when no files are given, so the expression can't be read from it
+        let mut values = self.values.into_iter();
+        let input = match self.path {
+            Some(path) => Input::File(path),
+            None => Input::Value(values.next().ok_or(ParseArgsError::NoInput)?),
+        };
+
+        self.root.finish(Subcommand::Match(MatchOptions {
+            input,
+            paths: values.map(PathBuf::from).collect(),
+            engine: self.engine,
+            count: self.count,
+            only_matching: self.only_matching,
+        }))
+    }
+}
+
+struct ReplaceParser {
+    root: RootParser,
+    values: Vec<String>,
+    path: Option<PathBuf>,
+    replacement: Option<String>,
+    engine: Option<RegexEngine>,
+    dry_run: bool,
+}
+
+impl ReplaceParser {
+    fn new(root: RootParser) -> Self {
+        Self { root, values: vec![], path: None, replacement: None, engine: None, dry_run: false }
+    }
+
+    fn parse(
+        mut self,
+        logger: &Logger,
+        parser: &mut lexopt::Parser,
+    ) -> Result<Parsed, ParseArgsError> {
+        use lexopt::prelude::*;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Short('p') | Long("path") => {
+                    self.path.set_arg(parser.value()?.parse()?, "--path")?
+                }
+                Short('w') | Long("with") => {
+                    self.replacement.set_arg(parser.value()?.string()?, "--with")?
+                }
+                Short('e') | Long("engine") => {
+                    self.engine.set_arg(RegexEngine::parse(parser.value()?)?, "--engine")?
+                }
+                Long("dry-run") => self.dry_run.set_arg(true, "--dry-run")?,
+                Value(val) => {
+                    self.values.push(val.into_string().map_err(lexopt::Error::from)?);
+                }
+                Short('h') => return Ok(Parsed::Help(Help::ReplaceShort)),
+                Long("help") => return Ok(Parsed::Help(Help::ReplaceLong)),
+                _ => parse_root_arg!(logger, arg, parser, self.root),
+            }
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> Result<Parsed, ParseArgsError> {
+        let mut values = self.values.into_iter();
+        let input = match self.path {
+            Some(path) => Input::File(path),
+            None => Input::Value(values.next().ok_or(ParseArgsError::NoInput)?),
+        };
+
+        self.root.finish(Subcommand::Replace(ReplaceOptions {
+            input,
+            paths: values.map(PathBuf::from).collect(),
+            replacement: self.replacement.ok_or(ParseArgsError::NoReplacement)?,
+            engine: self.engine,
+            dry_run: self.dry_run,
         }))
     }
 }
@@ -0,0 +1,27 @@
+use std::ffi::OsString;
+
+use super::ParseArgsError;
+
+/// Output format of `pomsky test`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Reporter {
+    Pretty,
+    Json,
+    Junit,
+    Tap,
+    Sarif,
+}
+
+impl Reporter {
+    pub(crate) fn parse(value: OsString) -> Result<Self, ParseArgsError> {
+        let lower = value.to_string_lossy().to_ascii_lowercase();
+        Ok(match lower.as_str() {
+            "pretty" => Reporter::Pretty,
+            "json" => Reporter::Json,
+            "junit" => Reporter::Junit,
+            "tap" => Reporter::Tap,
+            "sarif" => Reporter::Sarif,
+            _ => return Err(ParseArgsError::UnknownReporter(lower)),
+        })
+    }
+}
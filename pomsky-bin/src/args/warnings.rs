@@ -1,65 +1,44 @@
-use std::{ffi::OsString, str::FromStr};
+use std::ffi::OsString;
 
-use pomsky::diagnose::DiagnosticKind;
+use pomsky::{
+    diagnose::DiagnosticKind,
+    options::{Lint, LintLevel, Lints},
+};
 
 use super::ParseArgsError;
 
-#[derive(Debug, PartialEq, Default)]
-pub(crate) enum DiagnosticSet {
-    #[default]
-    All,
-    Disabled(Vec<DiagnosticKind>),
-    // the list is currently unused -- `-W0` disables all warnings, so the set of warnings is empty
-    Enabled(Vec<DiagnosticKind>),
-}
-
-impl DiagnosticSet {
-    pub(crate) fn is_enabled(&self, kind: DiagnosticKind) -> bool {
-        match self {
-            DiagnosticSet::All => true,
-            DiagnosticSet::Disabled(set) => !set.contains(&kind),
-            DiagnosticSet::Enabled(set) => set.contains(&kind),
-        }
+/// Parses the value of `-W`, and adds it to the lint configuration
+pub(super) fn parse_warnings(lints: Lints, value: OsString) -> Result<Lints, ParseArgsError> {
+    let value = value.to_string_lossy();
+    if value.as_ref() == "0" {
+        return Ok(lints.set_default(LintLevel::Allow));
     }
 
-    pub(super) fn parse(&mut self, value: OsString) -> Result<(), ParseArgsError> {
-        let value = value.to_string_lossy();
-        if value.as_ref() == "0" {
-            *self = DiagnosticSet::Enabled(vec![]);
-            return Ok(());
-        }
-
-        let warning_list = match self {
-            DiagnosticSet::Disabled(set) => set,
-            DiagnosticSet::Enabled(_) => return Ok(()),
-            DiagnosticSet::All => {
-                *self = DiagnosticSet::Disabled(vec![]);
-                let DiagnosticSet::Disabled(warning_list) = self else { unreachable!() };
-                warning_list
-            }
-        };
+    let mut lints = lints;
+    for warning in value.split(',') {
+        let (lint_str, val) = warning
+            .trim_start()
+            .rsplit_once('=')
+            .ok_or_else(|| ParseArgsError::WarningsNoEquals(warning.to_string()))?;
 
-        for warning in value.split(',') {
-            let (kind_str, val) = warning
-                .trim_start()
-                .rsplit_once('=')
-                .ok_or_else(|| ParseArgsError::WarningsNoEquals(warning.to_string()))?;
-
-            if val != "0" {
-                return Err(ParseArgsError::WarningsNoZero(kind_str.to_string()));
-            }
-
-            let kind = DiagnosticKind::from_str(kind_str).map_err(|_| {
-                ParseArgsError::Other(format!("`{kind_str}` is not a recognized diagnostic kind"))
-            })?;
+        if val != "0" {
+            return Err(ParseArgsError::WarningsNoZero(lint_str.to_string()));
+        }
 
-            let (DiagnosticKind::Compat | DiagnosticKind::Deprecated) = kind else {
-                return Err(ParseArgsError::WarningsNotAllowed(kind_str.to_string()));
-            };
+        let lint = lint_str.parse::<Lint>().map_err(|_| {
+            ParseArgsError::Other(format!(
+                "`{lint_str}` is not a recognized diagnostic kind or code"
+            ))
+        })?;
 
-            warning_list.push(kind);
+        if let Lint::Kind(kind) = lint
+            && !matches!(kind, DiagnosticKind::Compat | DiagnosticKind::Deprecated)
+        {
+            return Err(ParseArgsError::WarningsNotAllowed(lint_str.to_string()));
         }
 
-        Ok(())
+        lints = lints.set(lint, LintLevel::Allow);
     }
+
+    Ok(lints)
 }
@@ -0,0 +1,145 @@
+//! Wrappers around the regex engines used for testing and searching
+
+use std::ops::Index;
+
+use pcre2::bytes::Regex as PcreRegex;
+use regex::Regex as RustRegex;
+
+use crate::args::RegexEngine;
+
+pub(crate) enum Regex {
+    Pcre(PcreRegex),
+    Rust(RustRegex),
+}
+
+impl Regex {
+    /// Compiles the regex with the given engine. PCRE2 is used in UTF mode with
+    /// Unicode properties enabled.
+    pub(crate) fn new(engine: RegexEngine, pattern: &str) -> Result<Self, String> {
+        match engine {
+            RegexEngine::Pcre2 => pcre2::bytes::RegexBuilder::new()
+                .jit_if_available(true)
+                .ucp(true)
+                .utf(true)
+                .build(pattern)
+                .map(Regex::Pcre)
+                .map_err(|e| e.to_string()),
+            RegexEngine::Rust => {
+                RustRegex::new(pattern).map(Regex::Rust).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            Regex::Pcre(regex) => regex.as_str(),
+            Regex::Rust(regex) => regex.as_str(),
+        }
+    }
+
+    pub(crate) fn captures<'a>(
+        &'a self,
+        subject: &'a str,
+    ) -> Result<Option<Captures<'a>>, pcre2::Error> {
+        match self {
+            Regex::Pcre(regex) => regex.captures(subject.as_bytes()).map(|o| o.map(Captures::Pcre)),
+            Regex::Rust(regex) => Ok(regex.captures(subject).map(Captures::Rust)),
+        }
+    }
+
+    pub(crate) fn captures_iter<'r, 's>(&'r self, subject: &'s str) -> CaptureMatches<'r, 's> {
+        match self {
+            Regex::Pcre(regex) => CaptureMatches::Pcre(regex.captures_iter(subject.as_bytes())),
+            Regex::Rust(regex) => CaptureMatches::Rust(regex.captures_iter(subject)),
+        }
+    }
+
+    /// Returns the names of all capturing groups. The first element belongs to
+    /// the whole match, and is always `None`.
+    pub(crate) fn capture_names(&self) -> Vec<Option<&str>> {
+        match self {
+            Regex::Pcre(regex) => regex.capture_names().iter().map(Option::as_deref).collect(),
+            Regex::Rust(regex) => regex.capture_names().collect(),
+        }
+    }
+}
+
+pub(crate) enum Captures<'a> {
+    Pcre(pcre2::bytes::Captures<'a>),
+    Rust(regex::Captures<'a>),
+}
+
+impl Captures<'_> {
+    pub(crate) fn name(&self, name: &str) -> Option<Match<'_>> {
+        match self {
+            Captures::Pcre(captures) => captures.name(name).map(Match::Pcre),
+            Captures::Rust(captures) => captures.name(name).map(Match::Rust),
+        }
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<Match<'_>> {
+        match self {
+            Captures::Pcre(captures) => captures.get(index).map(Match::Pcre),
+            Captures::Rust(captures) => captures.get(index).map(Match::Rust),
+        }
+    }
+}
+
+impl Index<usize> for Captures<'_> {
+    type Output = [u8];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Captures::Pcre(captures) => &captures[index],
+            Captures::Rust(captures) => captures[index].as_bytes(),
+        }
+    }
+}
+
+pub(crate) enum Match<'s> {
+    Pcre(pcre2::bytes::Match<'s>),
+    Rust(regex::Match<'s>),
+}
+
+impl Match<'_> {
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            Match::Pcre(mat) => mat.as_bytes(),
+            Match::Rust(mat) => mat.as_str().as_bytes(),
+        }
+    }
+
+    pub(crate) fn start(&self) -> usize {
+        match self {
+            Match::Pcre(mat) => mat.start(),
+            Match::Rust(mat) => mat.start(),
+        }
+    }
+
+    pub(crate) fn end(&self) -> usize {
+        match self {
+            Match::Pcre(mat) => mat.end(),
+            Match::Rust(mat) => mat.end(),
+        }
+    }
+}
+
+pub(crate) enum CaptureMatches<'r, 's> {
+    Pcre(pcre2::bytes::CaptureMatches<'r, 's>),
+    Rust(regex::CaptureMatches<'r, 's>),
+}
+
+impl<'s> Iterator for CaptureMatches<'_, 's> {
+    type Item = Result<Captures<'s>, pcre2::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            CaptureMatches::Pcre(capture_matches) => {
+                capture_matches.next().map(|r| r.map(Captures::Pcre))
+            }
+            CaptureMatches::Rust(capture_matches) => {
+                capture_matches.next().map(|c| Ok(Captures::Rust(c)))
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Finding pomsky files in a directory
+
+use std::path::{Path, PathBuf};
+
+use helptext::text;
+
+use crate::format::Logger;
+
+/// Returns all `*.pomsky` files in a directory, respecting `.gitignore` files
+pub(crate) fn pomsky_files(path: &Path, mut on_error: impl FnMut(ignore::Error)) -> Vec<PathBuf> {
+    ignore::WalkBuilder::new(path)
+        .follow_links(true)
+        .filter_entry(is_dir_or_pomsky_file)
+        .build()
+        .filter_map(|entry| entry.map_err(&mut on_error).ok())
+        .filter(|entry| entry.file_type().is_some_and(|ty| ty.is_file()))
+        .map(ignore::DirEntry::into_path)
+        .collect()
+}
+
+fn is_dir_or_pomsky_file(entry: &ignore::DirEntry) -> bool {
+    let Some(ty) = entry.file_type() else { return false };
+    if ty.is_dir() {
+        return true;
+    }
+    let Some(ext) = entry.path().extension() else { return false };
+    ext == "pomsky"
+}
+
+pub(crate) fn handle_walk_error(error: ignore::Error, logger: &Logger, current_dir: &Path) {
+    match error {
+        ignore::Error::Partial(errors) => {
+            for error in errors {
+                handle_walk_error(error, logger, current_dir);
+            }
+        }
+        ignore::Error::WithLineNumber { line, err } => {
+            handle_walk_error(*err, logger, current_dir);
+            logger.basic().fmtln(text!["    at line " C!{&line.to_string()}]);
+        }
+        ignore::Error::WithPath { path, err } => {
+            handle_walk_error(*err, logger, current_dir);
+            logger.basic().fmtln(text!["    at path " C!{&path.display().to_string()}]);
+        }
+        ignore::Error::WithDepth { depth, err } => {
+            handle_walk_error(*err, logger, current_dir);
+            logger.basic().fmtln(text!["    at depth " C!{&depth.to_string()}]);
+        }
+        ignore::Error::Loop { ancestor, child } => {
+            let ancestor = ancestor.strip_prefix(current_dir).unwrap_or(&ancestor);
+            let child = child.canonicalize().unwrap_or(child);
+
+            logger.error().println("file system loop detected!");
+            logger.basic().fmtln(text!["    ancestor: " C!{&ancestor.display().to_string()}]);
+            logger.basic().fmtln(text!["    child: " C!{&child.display().to_string()}]);
+        }
+        ignore::Error::Io(error) => {
+            logger.error().println(error);
+        }
+        ignore::Error::Glob { glob, err } => {
+            logger.error().println(err);
+            if let Some(glob) = glob {
+                logger.basic().fmtln(text!["    glob: " C!{&glob}]);
+            }
+        }
+        ignore::Error::UnrecognizedFileType(file_type) => {
+            logger.error().println(format_args!("file type `{file_type}` not recognized"));
+        }
+        ignore::Error::InvalidDefinition => {
+            logger.error().println("file type definition could not be parsed");
+        }
+    }
+}
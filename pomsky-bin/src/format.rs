@@ -1,19 +1,26 @@
 use std::{
     fmt::Display,
-    io::{StderrLock, Write},
+    io::{IsTerminal, StderrLock, Write},
 };
 
 use helptext::{Segment, Style};
 
 use crate::Severity;
 
-pub(crate) fn supports_color() -> bool {
+pub(crate) fn supports_color(stream: ::supports_color::Stream) -> bool {
     matches!(
-        ::supports_color::on_cached(::supports_color::Stream::Stderr),
+        ::supports_color::on_cached(stream),
         Some(::supports_color::ColorLevel { has_basic: true, .. })
     )
 }
 
+/// Discards the result of writing to stdout. Writing fails e.g. when the output
+/// is piped into `head`, which exits before reading everything.
+#[cfg(feature = "test")]
+pub(crate) fn ignore_write_error(result: std::io::Result<()>) {
+    let _ = result;
+}
+
 pub(crate) struct Logger {
     colored: bool,
     enabled: bool,
@@ -25,7 +32,7 @@ impl Logger {
     }
 
     pub(crate) fn new() -> Self {
-        Logger { colored: supports_color(), enabled: true }
+        Logger { colored: supports_color(::supports_color::Stream::Stderr), enabled: true }
     }
 
     pub(crate) fn color(&self, colored: bool) -> Self {
@@ -47,6 +54,16 @@ impl Logger {
         }
     }
 
+    /// Clears the terminal, if stderr is a terminal
+    pub(crate) fn clear_screen(&self) {
+        let mut buf = std::io::stderr().lock();
+        if self.enabled && buf.is_terminal() {
+            // clear the screen and the scrollback buffer, then move the cursor to the top left
+            let _ = buf.write_all(b"\x1b[2J\x1b[3J\x1b[H");
+            let _ = buf.flush();
+        }
+    }
+
     pub(crate) fn basic(&self) -> Formatted<'_, FormatBasic> {
         Formatted { format: FormatBasic, logger: self }
     }
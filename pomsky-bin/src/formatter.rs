@@ -0,0 +1,124 @@
+use std::{path::Path, process::exit, time::Instant};
+
+use helptext::text;
+use pomsky::format::{FormatOptions, format};
+
+use crate::{
+    CompilationResult,
+    args::{FmtOptions, GlobalOptions, Input},
+    files::{handle_walk_error, pomsky_files},
+    format::Logger,
+};
+
+pub(crate) fn fmt(logger: &Logger, args: &GlobalOptions, fmt_args: FmtOptions) {
+    let options = FormatOptions {
+        indent: fmt_args.indent.unwrap_or(FormatOptions::default().indent),
+        max_width: fmt_args.width.unwrap_or(FormatOptions::default().max_width),
+    };
+
+    match fmt_args.input {
+        Input::Value(input) => {
+            let formatted = format_or_report(logger, args, &input, &options);
+            if fmt_args.check {
+                // an input passed as argument usually doesn't end with a line break
+                if formatted.trim_end() != input.trim_end() {
+                    logger.error().println("The input is not formatted");
+                    exit(1);
+                }
+            } else {
+                print!("{formatted}");
+            }
+        }
+        Input::File(path) => {
+            let files = if path.is_dir() {
+                let current_dir = std::env::current_dir().unwrap_or_default();
+                let mut files =
+                    pomsky_files(&path, |error| handle_walk_error(error, logger, &current_dir));
+                files.sort();
+                files
+            } else {
+                vec![path]
+            };
+
+            let mut failed = false;
+            for path in &files {
+                let input = std::fs::read_to_string(path).unwrap_or_else(|error| {
+                    logger.error().println(format_args!("{}: {error}", path.display()));
+                    exit(3);
+                });
+                if let Some(formatted) = format_file(logger, args, path, &input, &options) {
+                    if formatted == input {
+                        continue;
+                    }
+                    if fmt_args.check {
+                        logger
+                            .error()
+                            .println(format_args!("`{}` is not formatted", path.display()));
+                        failed = true;
+                    } else if let Err(error) = std::fs::write(path, formatted) {
+                        logger.error().println(format_args!("{}: {error}", path.display()));
+                        exit(3);
+                    }
+                } else {
+                    failed = true;
+                }
+            }
+            if failed {
+                exit(1);
+            }
+        }
+    }
+}
+
+fn format_file(
+    logger: &Logger,
+    args: &GlobalOptions,
+    path: &Path,
+    input: &str,
+    options: &FormatOptions,
+) -> Option<String> {
+    let start = Instant::now();
+    match format(input, options) {
+        (Some(formatted), _) => Some(formatted),
+        (None, diagnostics) => {
+            logger.basic().fmt(text![C!"formatting " {&path.display().to_string()} " ... "]);
+            let result = CompilationResult::error(
+                Some(path),
+                start.elapsed().as_micros(),
+                0,
+                diagnostics,
+                input,
+                &args.lints,
+                args.json,
+            );
+            result.output(logger, args.json, true, true, input);
+            None
+        }
+    }
+}
+
+/// Formats the input, or prints the syntax errors and exits
+fn format_or_report(
+    logger: &Logger,
+    args: &GlobalOptions,
+    input: &str,
+    options: &FormatOptions,
+) -> String {
+    let start = Instant::now();
+    match format(input, options) {
+        (Some(formatted), _) => formatted,
+        (None, diagnostics) => {
+            let result = CompilationResult::error(
+                None,
+                start.elapsed().as_micros(),
+                0,
+                diagnostics,
+                input,
+                &args.lints,
+                args.json,
+            );
+            result.output(logger, args.json, true, false, input);
+            exit(1);
+        }
+    }
+}
@@ -1,22 +1,36 @@
 #[macro_use]
 mod format;
 mod args;
+#[cfg(feature = "test")]
+mod engine;
+mod files;
+mod formatter;
+#[cfg(feature = "test")]
+mod matching;
+#[cfg(feature = "test")]
+mod replacing;
+#[cfg(feature = "test")]
+mod reporters;
 mod result;
+mod sarif;
 #[cfg(feature = "test")]
 mod test_runner;
 #[cfg(feature = "test")]
 mod testing;
+mod watch;
 
 use format::Logger;
 pub use result::{
-    CompilationResult, Diagnostic, Kind, QuickFix, Replacement, Severity, Span, Timings, Version,
+    CompilationResult, Diagnostic, ExplainResult, FileMatches, Kind, LineMatch, Match, MatchResult,
+    NamedCapture, ParseResult, Position, QuickFix, Replacement, Severity, Span, TestCaseKind,
+    TestCaseResult, TestFailure, Timings, Version,
 };
 
 use std::{path::Path, process::exit, time::Instant};
 
 use pomsky::{
     Expr,
-    options::{CompileOptions as PomskyCompileOptions, RegexFlavor},
+    options::{CompileOptions as PomskyCompileOptions, Limits, RegexFlavor},
 };
 
 use args::{CompileOptions, GlobalOptions, Input};
@@ -43,23 +57,27 @@ pub fn main() {
 
             match &compile_args.input {
                 Input::Value(input) => {
-                    compile(None, input, &compile_args, &args).output(
-                        &logger,
-                        args.json,
-                        !compile_args.no_new_line,
-                        compile_args.in_test_suite,
-                        input,
-                    );
+                    let result = compile(None, input, &compile_args, &args);
+                    if !print_result(result, &logger, &compile_args, &args, input)
+                        && !compile_args.in_test_suite
+                    {
+                        exit(1);
+                    }
+                }
+                Input::File(path) if compile_args.watch => {
+                    watch::watch_compile(&logger, path, &compile_args, &args)
                 }
                 Input::File(path) => match std::fs::read_to_string(path) {
-                    Ok(input) => {
-                        compile(Some(path), &input, &compile_args, &args).output(
-                            &logger,
-                            args.json,
-                            !compile_args.no_new_line,
-                            compile_args.in_test_suite,
-                            &input,
-                        );
+                    Ok(mut input) => {
+                        if compile_args.fix {
+                            input = fix(&logger, path, input, compile_args.unsafe_fixes, &args);
+                        }
+                        let result = compile(Some(path), &input, &compile_args, &args);
+                        if !print_result(result, &logger, &compile_args, &args, &input)
+                            && !compile_args.in_test_suite
+                        {
+                            exit(1);
+                        }
                     }
                     Err(error) => {
                         logger.error().println(error);
@@ -68,6 +86,76 @@ pub fn main() {
                 },
             }
         }
+        args::Subcommand::Import(import_args) => {
+            let input = match import_args.input {
+                Input::Value(input) => input,
+                Input::File(path) => match std::fs::read_to_string(path) {
+                    Ok(input) => input,
+                    Err(error) => {
+                        logger.error().println(error);
+                        exit(3);
+                    }
+                },
+            };
+            // a regex read from a file or stdin usually ends with a line break
+            let input = input.strip_suffix('\n').unwrap_or(&input);
+            let input = input.strip_suffix('\r').unwrap_or(input);
+
+            import(input, &args).output(&logger, args.json, true, false, input);
+        }
+        args::Subcommand::Parse(parse_args) => {
+            let (path, input) = match parse_args.input {
+                Input::Value(input) => (None, input),
+                Input::File(path) => match std::fs::read_to_string(&path) {
+                    Ok(input) => (Some(path), input),
+                    Err(error) => {
+                        logger.error().println(error);
+                        exit(3);
+                    }
+                },
+            };
+            parse(&logger, path.as_deref(), &input, &args);
+        }
+        args::Subcommand::Explain(explain_args) => {
+            let (path, input) = match explain_args.input {
+                Input::Value(input) => (None, input),
+                Input::File(path) => match std::fs::read_to_string(&path) {
+                    Ok(input) => (Some(path), input),
+                    Err(error) => {
+                        logger.error().println(error);
+                        exit(3);
+                    }
+                },
+            };
+            explain(&logger, path.as_deref(), &input, &args);
+        }
+        args::Subcommand::Fmt(fmt_args) => formatter::fmt(&logger, &args, fmt_args),
+        args::Subcommand::Match(_match_args) => {
+            #[cfg(not(feature = "test"))]
+            {
+                logger.error().println(
+                    "Searching is not supported, because this pomsky binary \
+                    was compiled with the `test` feature disabled!",
+                );
+                exit(4);
+            }
+
+            #[cfg(feature = "test")]
+            matching::match_(&logger, args, _match_args);
+        }
+        args::Subcommand::Replace(_replace_args) => {
+            #[cfg(not(feature = "test"))]
+            {
+                logger.error().println(
+                    "Replacing is not supported, because this pomsky binary \
+                    was compiled with the `test` feature disabled!",
+                );
+                exit(4);
+            }
+
+            #[cfg(feature = "test")]
+            replacing::replace(&logger, args, _replace_args);
+        }
         args::Subcommand::Test(_test_args) => {
             handle_disabled_tests(&logger);
 
@@ -80,7 +168,7 @@ pub fn main() {
 fn handle_disabled_tests(_logger: &Logger) {
     #[cfg(not(feature = "test"))]
     {
-        _logger.error_plain(
+        _logger.error().println(
             "Testing is not supported, because this pomsky binary \
             was compiled with the `test` feature disabled!",
         );
@@ -94,6 +182,80 @@ fn show_tests_deprecated_warning(logger: &Logger) {
         .println("The `--test` argument is deprecated, use the `pomsky test` subcommand instead");
 }
 
+/// Prints the result of compiling an expression in the requested format, and
+/// returns whether compilation succeeded
+fn print_result(
+    result: CompilationResult,
+    logger: &Logger,
+    compile_args: &CompileOptions,
+    args: &GlobalOptions,
+    input: &str,
+) -> bool {
+    if args.sarif {
+        let success = result.success;
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        sarif::print(&[result], &current_dir);
+        success
+    } else {
+        result.print(
+            logger,
+            args.json,
+            !compile_args.no_new_line,
+            compile_args.in_test_suite,
+            input,
+        )
+    }
+}
+
+fn compile_options(args: &GlobalOptions) -> PomskyCompileOptions {
+    PomskyCompileOptions::default()
+        .with_flavor(args.flavor.unwrap_or(RegexFlavor::Pcre))
+        .with_flavor_version(args.flavor_version)
+        .with_additional_flavors(args.additional_flavors)
+        .with_max_range_size(12)
+        .with_limits(Limits::default())
+        .with_allowed_features(args.allowed_features)
+        .with_lints(args.lints)
+}
+
+/// Applies quick fixes to the file until no more fixes are available, and
+/// returns the fixed source code. Fixes that may change the meaning of the
+/// expression are only applied if `unsafe_fixes` is set.
+fn fix(
+    logger: &Logger,
+    path: &Path,
+    mut input: String,
+    unsafe_fixes: bool,
+    args: &GlobalOptions,
+) -> String {
+    // applying a fix can uncover diagnostics that were hidden by an error
+    // before, but this shouldn't loop forever if fixes are conflicting
+    const MAX_PASSES: usize = 10;
+
+    let original = input.clone();
+    for _ in 0..MAX_PASSES {
+        // allowed warnings are already removed here
+        let (_, diagnostics, _) = Expr::parse_and_compile(&input, compile_options(args));
+        let fixes = diagnostics
+            .iter()
+            .filter_map(|d| d.fixes.iter().find(|fix| unsafe_fixes || fix.preserves_meaning));
+        let fixed = pomsky::diagnose::apply_fixes(&input, fixes);
+        if fixed == input {
+            break;
+        }
+        input = fixed;
+    }
+
+    if input != original {
+        if let Err(error) = std::fs::write(path, &input) {
+            logger.error().println(format_args!("{}: {error}", path.display()));
+            exit(3);
+        }
+        logger.note().println(format_args!("applied fixes to `{}`", path.display()));
+    }
+    input
+}
+
 // TODO: refactor this
 fn compile(
     path: Option<&Path>,
@@ -103,13 +265,9 @@ fn compile(
 ) -> CompilationResult {
     let start = Instant::now();
 
-    let options = PomskyCompileOptions {
-        flavor: args.flavor.unwrap_or(RegexFlavor::Pcre),
-        max_range_size: 12,
-        allowed_features: args.allowed_features,
-    };
+    let options = compile_options(args);
 
-    let (parsed, warnings) = match Expr::parse(input) {
+    let (parsed, warnings) = match Expr::parse_with_limits(input, options.limits) {
         (Some(res), warnings) => (res, warnings),
         (None, err) => {
             return CompilationResult::error(
@@ -118,7 +276,7 @@ fn compile(
                 0,
                 err,
                 input,
-                &args.warnings,
+                &args.lints,
                 args.json,
             );
         }
@@ -131,19 +289,24 @@ fn compile(
 
     let mut diagnostics = warnings.collect::<Vec<_>>();
 
-    let (output, compile_diagnostics) = parsed.compile(input, options);
+    let (mut output, compile_diagnostics) = parsed.compile(input, options);
     diagnostics.extend(compile_diagnostics);
+    if options.lints.apply(&mut diagnostics) {
+        output = None;
+    }
 
     if let Some(output) = output {
         #[allow(unused_mut)] // the `mut` is only needed when cfg(feature = "test")
         let mut time_test = 0;
+        #[allow(unused_mut)]
+        let mut tests = Vec::new();
 
         #[cfg(feature = "test")]
         if let Some(test_engine) = compile_args.test {
             let mut test_errors = Vec::new();
 
             let start = Instant::now();
-            test_runner::run_tests(&parsed, input, options, test_engine, &mut test_errors);
+            tests = test_runner::run_tests(&parsed, input, options, test_engine, &mut test_errors);
             time_test = start.elapsed().as_micros();
 
             if !test_errors.is_empty() {
@@ -166,28 +329,32 @@ fn compile(
                     last.help = Some(prev_help);
                 }
 
-                return CompilationResult::error(
+                let mut result = CompilationResult::error(
                     path,
                     start.elapsed().as_micros(),
                     time_test,
                     diagnostics,
                     input,
-                    &args.warnings,
+                    &args.lints,
                     args.json,
                 );
+                result.tests = tests;
+                return result;
             }
         }
 
-        CompilationResult::success(
+        let mut result = CompilationResult::success(
             path,
             output,
             start.elapsed().as_micros(),
             time_test,
             diagnostics,
             input,
-            &args.warnings,
+            &args.lints,
             args.json,
-        )
+        );
+        result.tests = tests;
+        result
     } else {
         CompilationResult::error(
             path,
@@ -195,8 +362,105 @@ fn compile(
             0,
             diagnostics,
             input,
-            &args.warnings,
+            &args.lints,
             args.json,
         )
     }
 }
+
+fn import(input: &str, args: &GlobalOptions) -> CompilationResult {
+    let start = Instant::now();
+    let flavor = args.flavor.unwrap_or(RegexFlavor::Pcre);
+
+    let (output, mut diagnostics) = pomsky::import::import(input, flavor);
+    let denied = args.lints.apply(&mut diagnostics);
+    match output {
+        Some(output) if !denied => CompilationResult::success(
+            None,
+            output,
+            start.elapsed().as_micros(),
+            0,
+            diagnostics,
+            input,
+            &args.lints,
+            args.json,
+        ),
+        _ => CompilationResult::error(
+            None,
+            start.elapsed().as_micros(),
+            0,
+            diagnostics,
+            input,
+            &args.lints,
+            args.json,
+        ),
+    }
+}
+
+fn explain(logger: &Logger, path: Option<&Path>, input: &str, args: &GlobalOptions) {
+    let start = Instant::now();
+    let flavor = args.flavor.unwrap_or(RegexFlavor::Pcre);
+    let (explanation, diagnostics) = pomsky::explain::explain(input, flavor);
+
+    if args.json {
+        let result =
+            ExplainResult::new(path, explanation.as_ref(), diagnostics, input, &args.lints);
+        match serde_json::to_string(&result) {
+            Ok(string) => println!("{string}"),
+            Err(e) => eprintln!("{e}"),
+        }
+        if !result.success {
+            exit(1);
+        }
+    } else {
+        let time = start.elapsed().as_micros();
+        let result = match explanation {
+            Some(explanation) => CompilationResult::success(
+                path,
+                explanation.to_string(),
+                time,
+                0,
+                diagnostics,
+                input,
+                &args.lints,
+                false,
+            ),
+            None => CompilationResult::error(path, time, 0, diagnostics, input, &args.lints, false),
+        };
+        // the explanation already ends with a line break
+        result.output(logger, false, false, false, input);
+    }
+}
+
+fn parse(logger: &Logger, path: Option<&Path>, input: &str, args: &GlobalOptions) {
+    let start = Instant::now();
+    let (expr, diagnostics) = Expr::parse(input);
+    let diagnostics = diagnostics.collect::<Vec<_>>();
+
+    if args.json {
+        let result = ParseResult::new(path, expr.as_ref(), diagnostics, input, &args.lints);
+        match serde_json::to_string(&result) {
+            Ok(string) => println!("{string}"),
+            Err(e) => eprintln!("{e}"),
+        }
+        if !result.success {
+            exit(1);
+        }
+    } else {
+        let time = start.elapsed().as_micros();
+        let result = match expr {
+            Some(expr) => CompilationResult::success(
+                path,
+                format!("{expr:?}"),
+                time,
+                0,
+                diagnostics,
+                input,
+                &args.lints,
+                false,
+            ),
+            None => CompilationResult::error(path, time, 0, diagnostics, input, &args.lints, false),
+        };
+        result.output(logger, false, true, false, input);
+    }
+}
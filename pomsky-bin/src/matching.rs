@@ -0,0 +1,336 @@
+use std::{
+    io::{Read, StdoutLock, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use helptext::{Segment, Style};
+
+use crate::{
+    CompilationResult, FileMatches, LineMatch, Match, MatchResult, NamedCapture, Version,
+    args::{CompileOptions, GlobalOptions, Input, MatchOptions, RegexEngine},
+    engine::Regex,
+    files::handle_walk_error,
+    format::{Logger, ignore_write_error, supports_color},
+    testing::select_engine,
+};
+
+/// A pomsky expression compiled to a regex for one of the bundled engines
+pub(crate) struct CompiledRegex {
+    pub(crate) regex: Regex,
+    pub(crate) pattern: String,
+    pub(crate) source: String,
+    pub(crate) result: CompilationResult,
+}
+
+/// Compiles the expression and builds a regex for the selected engine. The
+/// flavor in `args` is set to match the engine. If compilation fails, the
+/// errors are printed and the process exits.
+pub(crate) fn compile_regex(
+    logger: &Logger,
+    args: &mut GlobalOptions,
+    input: Input,
+    engine: Option<RegexEngine>,
+) -> CompiledRegex {
+    // unlike `pomsky test`, this uses PCRE2 by default
+    let engine = engine.or(args.flavor.is_none().then_some(RegexEngine::Pcre2));
+    let (engine, flavor) = select_engine(logger, args.flavor, engine);
+    args.flavor = Some(flavor);
+
+    let (path, source) = match input {
+        Input::Value(input) => (None, input),
+        Input::File(path) => match std::fs::read_to_string(&path) {
+            Ok(input) => (Some(path), input),
+            Err(error) => {
+                logger.error().println(error);
+                exit(3);
+            }
+        },
+    };
+
+    let compile_args = CompileOptions {
+        input: Input::Value(String::new()),
+        no_new_line: false,
+        test: None,
+        in_test_suite: false,
+        fix: false,
+        unsafe_fixes: false,
+        watch: false,
+    };
+    let result = super::compile(path.as_deref(), &source, &compile_args, args);
+    let Some(pattern) = result.output.clone() else {
+        // prints the errors and exits
+        result.output(logger, args.json, true, false, &source);
+        exit(1);
+    };
+
+    let regex = match Regex::new(engine, &pattern) {
+        Ok(regex) => regex,
+        Err(error) => {
+            logger.error().println(error);
+            logger.note().println(format_args!("The compiled regex is {pattern:?}"));
+            exit(1);
+        }
+    };
+
+    CompiledRegex { regex, pattern, source, result }
+}
+
+pub(crate) fn match_(logger: &Logger, mut args: GlobalOptions, match_args: MatchOptions) {
+    let CompiledRegex { regex, pattern, source, mut result } =
+        compile_regex(logger, &mut args, match_args.input, match_args.engine);
+
+    // in JSON mode, the diagnostics are part of the match result
+    let (expr_path, diagnostics) = if args.json {
+        (result.path.take(), std::mem::take(&mut result.diagnostics))
+    } else {
+        result.output_diagnostics(logger, &source);
+        (None, vec![])
+    };
+
+    let mut searcher = Searcher {
+        capture_names: regex.capture_names(),
+        regex: &regex,
+        logger,
+        json: args.json,
+        count: match_args.count,
+        only_matching: match_args.only_matching,
+        with_path: match_args.paths.len() > 1 || match_args.paths.iter().any(|p| p.is_dir()),
+        colored: !args.json && supports_color(supports_color::Stream::Stdout),
+        out: std::io::stdout().lock(),
+    };
+
+    let mut files = Vec::new();
+    let had_error = if match_args.paths.is_empty() {
+        let haystack = read_stdin(logger);
+        files.push(searcher.search(None, &haystack));
+        false
+    } else {
+        for_each_file(logger, &match_args.paths, |path, haystack| {
+            files.push(searcher.search(Some(path), &haystack));
+        })
+    };
+
+    let success = files.iter().any(|file| file.count > 0);
+    if args.json {
+        let result = MatchResult {
+            version: Version::V1,
+            success,
+            path: expr_path,
+            regex: pattern,
+            diagnostics,
+            files,
+        };
+        match serde_json::to_string(&result) {
+            Ok(string) => println!("{string}"),
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
+    if had_error {
+        exit(3);
+    } else if !success {
+        exit(1);
+    }
+}
+
+/// Reads stdin, or prints an error and exits if it isn't valid UTF-8
+pub(crate) fn read_stdin(logger: &Logger) -> String {
+    let mut buf = Vec::new();
+    if let Err(error) = std::io::stdin().read_to_end(&mut buf) {
+        logger.error().println(error);
+        exit(3);
+    }
+    String::from_utf8(buf).unwrap_or_else(|error| {
+        logger.error().println(format_args!("Could not parse stdin: {error}"));
+        exit(3);
+    })
+}
+
+/// Calls `f` with the path and content of every file in the given paths.
+/// Directories are searched recursively. Returns `true` if an error occurred.
+pub(crate) fn for_each_file(
+    logger: &Logger,
+    paths: &[PathBuf],
+    mut f: impl FnMut(&Path, String),
+) -> bool {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let mut had_error = false;
+
+    let mut walk = ignore::WalkBuilder::new(&paths[0]);
+    for path in &paths[1..] {
+        walk.add(path);
+    }
+    for entry in walk.follow_links(true).build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                handle_walk_error(error, logger, &current_dir);
+                had_error = true;
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|ty| ty.is_file()) {
+            continue;
+        }
+
+        let path = entry.path();
+        match std::fs::read(path).map(String::from_utf8) {
+            Ok(Ok(content)) => f(path, content),
+            Ok(Err(_)) => {
+                // files in directories are skipped silently, like binary files
+                if entry.depth() == 0 {
+                    logger.warn().println(format_args!("`{}` is not valid UTF-8", path.display()));
+                }
+            }
+            Err(error) => {
+                logger.error().println(format_args!("{}: {error}", path.display()));
+                had_error = true;
+            }
+        }
+    }
+    had_error
+}
+
+struct Searcher<'a> {
+    regex: &'a Regex,
+    capture_names: Vec<Option<&'a str>>,
+    logger: &'a Logger,
+    json: bool,
+    count: bool,
+    only_matching: bool,
+    with_path: bool,
+    colored: bool,
+    out: StdoutLock<'static>,
+}
+
+impl Searcher<'_> {
+    /// Searches the haystack line by line. Unless the output is JSON, the
+    /// results are printed immediately.
+    fn search(&mut self, path: Option<&Path>, haystack: &str) -> FileMatches {
+        let path = path.map(|path| path.display().to_string());
+        let mut count = 0;
+        let mut lines = Vec::new();
+
+        for (i, text) in haystack.lines().enumerate() {
+            let matches = self.find_matches(text);
+            if matches.is_empty() {
+                continue;
+            }
+            count += 1;
+
+            let line = LineMatch { line: i + 1, text: text.to_string(), matches };
+            if !self.json && !self.count {
+                self.print_line(path.as_deref(), &line);
+            }
+            if self.json && !self.count {
+                lines.push(line);
+            }
+        }
+
+        if !self.json && self.count {
+            self.print_prefix(path.as_deref(), None);
+            ignore_write_error(writeln!(self.out, "{count}"));
+        }
+
+        FileMatches { path, count, lines }
+    }
+
+    fn find_matches(&self, line: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        for captures in self.regex.captures_iter(line) {
+            let captures = match captures {
+                Ok(captures) => captures,
+                Err(error) => {
+                    self.logger.error().println(error);
+                    break;
+                }
+            };
+            let Some(whole) = captures.get(0) else { continue };
+
+            let captures = self
+                .capture_names
+                .iter()
+                .enumerate()
+                .filter_map(|(i, name)| {
+                    let name = (*name)?;
+                    let capture = captures.get(i)?;
+                    Some(NamedCapture {
+                        name: name.to_string(),
+                        start: capture.start(),
+                        end: capture.end(),
+                        text: line[capture.start()..capture.end()].to_string(),
+                    })
+                })
+                .collect();
+
+            matches.push(Match {
+                start: whole.start(),
+                end: whole.end(),
+                text: line[whole.start()..whole.end()].to_string(),
+                captures,
+            });
+        }
+        matches
+    }
+
+    fn print_line(&mut self, path: Option<&str>, line: &LineMatch) {
+        if self.only_matching {
+            for m in line.matches.iter().filter(|m| m.start < m.end) {
+                self.print_prefix(path, Some(line.line));
+                self.write(Some(Style::RedBold), &m.text);
+                ignore_write_error(self.out.write_all(b"\n"));
+                self.print_captures(m);
+            }
+        } else {
+            self.print_prefix(path, Some(line.line));
+            let mut offset = 0;
+            for m in &line.matches {
+                self.write(None, &line.text[offset..m.start]);
+                self.write(Some(Style::RedBold), &m.text);
+                offset = m.end;
+            }
+            self.write(None, &line.text[offset..]);
+            ignore_write_error(self.out.write_all(b"\n"));
+
+            for m in &line.matches {
+                self.print_captures(m);
+            }
+        }
+    }
+
+    fn print_prefix(&mut self, path: Option<&str>, line: Option<usize>) {
+        if self.with_path
+            && let Some(path) = path
+        {
+            self.write(Some(Style::Magenta), path);
+            self.write(None, ":");
+        }
+        if let Some(line) = line {
+            self.write(Some(Style::Green), &line.to_string());
+            self.write(None, ":");
+        }
+    }
+
+    /// Prints the named capturing groups of a match in an indented line
+    fn print_captures(&mut self, m: &Match) {
+        if m.captures.is_empty() {
+            return;
+        }
+        self.write(None, "   ");
+        for capture in &m.captures {
+            self.write(None, " ");
+            self.write(Some(Style::Cyan), &capture.name);
+            self.write(None, &format!("={:?}", capture.text));
+        }
+        ignore_write_error(self.out.write_all(b"\n"));
+    }
+
+    fn write(&mut self, style: Option<Style>, text: &str) {
+        ignore_write_error(Segment { style, text, ticks: false }.write(
+            &mut self.out,
+            self.colored,
+            0,
+        ));
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fmt,
+    io::{StdoutLock, Write},
+    process::exit,
+};
+
+use helptext::{Segment, Style};
+use similar::TextDiff;
+
+use crate::{
+    args::{GlobalOptions, ReplaceOptions},
+    engine::Regex,
+    format::{Logger, ignore_write_error, supports_color},
+    matching::{CompiledRegex, compile_regex, for_each_file, read_stdin},
+};
+
+pub(crate) fn replace(logger: &Logger, mut args: GlobalOptions, replace_args: ReplaceOptions) {
+    let CompiledRegex { regex, source, result, .. } =
+        compile_regex(logger, &mut args, replace_args.input, replace_args.engine);
+    result.output_diagnostics(logger, &source);
+
+    // the template is validated before any file is read
+    let template = match Template::parse(&replace_args.replacement, &regex.capture_names()) {
+        Ok(template) => template,
+        Err(error) => {
+            logger.error().println(error);
+            exit(1);
+        }
+    };
+
+    let mut replacer = Replacer {
+        regex: &regex,
+        template: &template,
+        logger,
+        colored: supports_color(supports_color::Stream::Stdout),
+        out: std::io::stdout().lock(),
+    };
+
+    let mut total = 0;
+    let mut changed_files = 0;
+    let had_error = if replace_args.paths.is_empty() {
+        let haystack = read_stdin(logger);
+        let (replaced, count) = replacer.replace_all(&haystack);
+        total += count;
+        if replace_args.dry_run {
+            replacer.print_diff("<stdin>", &haystack, &replaced);
+        } else {
+            ignore_write_error(replacer.out.write_all(replaced.as_bytes()));
+        }
+        false
+    } else {
+        let mut write_error = false;
+        let walk_error = for_each_file(logger, &replace_args.paths, |path, haystack| {
+            let (replaced, count) = replacer.replace_all(&haystack);
+            if count == 0 {
+                return;
+            }
+            total += count;
+            changed_files += 1;
+
+            if replace_args.dry_run {
+                replacer.print_diff(&path.display().to_string(), &haystack, &replaced);
+            } else if let Err(error) = std::fs::write(path, replaced) {
+                logger.error().println(format_args!("{}: {error}", path.display()));
+                write_error = true;
+            }
+        });
+
+        if !replace_args.dry_run && total > 0 {
+            let matches = if total == 1 { "match" } else { "matches" };
+            let files = if changed_files == 1 { "file" } else { "files" };
+            logger
+                .basic()
+                .println(format_args!("replaced {total} {matches} in {changed_files} {files}"));
+        }
+        walk_error || write_error
+    };
+
+    if had_error {
+        exit(3);
+    } else if total == 0 {
+        exit(1);
+    }
+}
+
+struct Replacer<'a> {
+    regex: &'a Regex,
+    template: &'a Template,
+    logger: &'a Logger,
+    colored: bool,
+    out: StdoutLock<'static>,
+}
+
+impl Replacer<'_> {
+    /// Replaces all matches in the haystack, and returns the result and the
+    /// number of replaced matches
+    fn replace_all(&self, haystack: &str) -> (String, usize) {
+        let mut result = String::with_capacity(haystack.len());
+        let mut offset = 0;
+        let mut count = 0;
+
+        for captures in self.regex.captures_iter(haystack) {
+            let captures = match captures {
+                Ok(captures) => captures,
+                Err(error) => {
+                    self.logger.error().println(error);
+                    break;
+                }
+            };
+            let Some(whole) = captures.get(0) else { continue };
+
+            result.push_str(&haystack[offset..whole.start()]);
+            for part in &self.template.parts {
+                match part {
+                    Part::Literal(text) => result.push_str(text),
+                    Part::Group(index) => {
+                        // groups that didn't participate in the match are replaced with nothing
+                        if let Some(group) = captures.get(*index) {
+                            result.push_str(&haystack[group.start()..group.end()]);
+                        }
+                    }
+                }
+            }
+            offset = whole.end();
+            count += 1;
+        }
+
+        result.push_str(&haystack[offset..]);
+        (result, count)
+    }
+
+    fn print_diff(&mut self, name: &str, old: &str, new: &str) {
+        let diff = TextDiff::from_lines(old, new);
+        let diff = diff.unified_diff().header(name, name).to_string();
+
+        for line in diff.split_inclusive('\n') {
+            let style = if line.starts_with("---") || line.starts_with("+++") {
+                Some(Style::MagentaBold)
+            } else if line.starts_with('-') {
+                Some(Style::Red)
+            } else if line.starts_with('+') {
+                Some(Style::Green)
+            } else if line.starts_with("@@") {
+                Some(Style::Cyan)
+            } else {
+                None
+            };
+            ignore_write_error(Segment { style, text: line, ticks: false }.write(
+                &mut self.out,
+                self.colored,
+                0,
+            ));
+        }
+    }
+}
+
+/// A replacement template such as `${year}-${month}`
+struct Template {
+    parts: Vec<Part>,
+}
+
+enum Part {
+    Literal(String),
+    /// Index of a capturing group, where 0 is the whole match
+    Group(usize),
+}
+
+impl Template {
+    /// Parses the template. Every group it refers to must exist in
+    /// `capture_names`, which contains the names of all capturing groups,
+    /// starting with the whole match.
+    fn parse(template: &str, capture_names: &[Option<&str>]) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+
+        while let Some(dollar) = rest.find('$') {
+            literal.push_str(&rest[..dollar]);
+            rest = &rest[dollar + 1..];
+
+            if let Some(after) = rest.strip_prefix('$') {
+                literal.push('$');
+                rest = after;
+                continue;
+            }
+            let Some(after) = rest.strip_prefix('{') else {
+                return Err(TemplateError::LoneDollar);
+            };
+            let Some(close) = after.find('}') else {
+                return Err(TemplateError::Unclosed);
+            };
+            let group = &after[..close];
+            rest = &after[close + 1..];
+
+            let index = if let Ok(index) = group.parse::<usize>() {
+                if index >= capture_names.len() {
+                    return Err(TemplateError::IndexOutOfRange(index, capture_names.len() - 1));
+                }
+                index
+            } else {
+                capture_names.iter().position(|&name| name == Some(group)).ok_or_else(|| {
+                    let names = capture_names.iter().flatten().map(|name| name.to_string());
+                    TemplateError::UnknownGroup(group.to_string(), names.collect())
+                })?
+            };
+
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(Part::Group(index));
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { parts })
+    }
+}
+
+#[derive(Debug)]
+enum TemplateError {
+    LoneDollar,
+    Unclosed,
+    IndexOutOfRange(usize, usize),
+    UnknownGroup(String, Vec<String>),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::LoneDollar => write!(
+                f,
+                "`$` in the replacement must be followed by `{{`\n\
+                    try `${{name}}` to insert a group, or `$$` to insert a `$`"
+            ),
+            TemplateError::Unclosed => write!(f, "`${{` in the replacement is missing a `}}`"),
+            TemplateError::IndexOutOfRange(index, 0) => write!(
+                f,
+                "The replacement refers to group {index}, but the expression has no capturing groups"
+            ),
+            TemplateError::IndexOutOfRange(index, count) => write!(
+                f,
+                "The replacement refers to group {index}, but the expression only has {count} \
+                    capturing group{}",
+                if *count == 1 { "" } else { "s" }
+            ),
+            TemplateError::UnknownGroup(name, names) if names.is_empty() => write!(
+                f,
+                "The replacement refers to group `{name}`, \
+                    but the expression has no named capturing groups"
+            ),
+            TemplateError::UnknownGroup(name, names) => write!(
+                f,
+                "The replacement refers to group `{name}`, which doesn't exist in the expression\n\
+                    possible values: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Machine-readable test reports for `pomsky test --reporter`
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    CompilationResult, Severity, TestCaseResult, TestFailure, args::Reporter,
+    format::ignore_write_error,
+};
+
+/// Prints the report of all tested files to stdout. The `pretty` reporter
+/// prints its output while the tests are running, so it does nothing here.
+pub(crate) fn report(reporter: Reporter, results: &[CompilationResult], current_dir: &Path) {
+    let mut out = io::stdout().lock();
+    ignore_write_error(match reporter {
+        Reporter::Pretty => Ok(()),
+        Reporter::Json => match serde_json::to_string(&results) {
+            Ok(string) => writeln!(out, "{string}"),
+            Err(e) => {
+                eprintln!("{e}");
+                Ok(())
+            }
+        },
+        Reporter::Junit => write_junit(&mut out, results, current_dir),
+        Reporter::Tap => write_tap(&mut out, results, current_dir),
+        Reporter::Sarif => {
+            crate::sarif::print(results, current_dir);
+            Ok(())
+        }
+    });
+}
+
+/// A file that couldn't be compiled, or whose tests couldn't be executed, is
+/// reported as a single failed test, since there are no test case results
+fn is_file_error(result: &CompilationResult) -> bool {
+    !result.success && result.tests.is_empty()
+}
+
+/// Error messages of a file that couldn't be tested
+fn file_errors(result: &CompilationResult) -> impl Iterator<Item = &str> {
+    result
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| d.description.as_str())
+}
+
+fn file_name(result: &CompilationResult, current_dir: &Path) -> String {
+    let path = Path::new(result.path.as_deref().unwrap_or("<input>"));
+    path.strip_prefix(current_dir).unwrap_or(path).display().to_string()
+}
+
+/// Line and column of a test case or failure, counting from 1
+fn line_col(span: &crate::Span) -> (usize, usize) {
+    span.start_position.as_ref().map_or((0, 0), |pos| (pos.line + 1, pos.column_char + 1))
+}
+
+fn write_junit(
+    out: &mut impl Write,
+    results: &[CompilationResult],
+    current_dir: &Path,
+) -> io::Result<()> {
+    let tests = results.iter().map(|r| r.tests.len().max(is_file_error(r) as usize)).sum::<usize>();
+    let failures = results.iter().flat_map(|r| &r.tests).filter(|test| !test.success).count();
+    let errors = results.iter().filter(|r| is_file_error(r)).count();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="pomsky" tests="{tests}" failures="{failures}" errors="{errors}">"#
+    )?;
+
+    for result in results {
+        let file = file_name(result, current_dir);
+        let file_xml = xml_escape(&file);
+        let time = result.timings.all as f64 / 1_000_000.0;
+
+        if is_file_error(result) {
+            writeln!(
+                out,
+                r#"  <testsuite name="{file_xml}" tests="1" failures="0" errors="1" time="{time:.6}">"#
+            )?;
+            writeln!(
+                out,
+                r#"    <testcase name="compile" classname="{file_xml}" file="{file_xml}">"#
+            )?;
+            let message = file_errors(result).next().unwrap_or("Compilation failed");
+            let details = file_errors(result).collect::<Vec<_>>().join("\n");
+            writeln!(
+                out,
+                r#"      <error message="{}">{}</error>"#,
+                xml_escape(message),
+                xml_escape(&details)
+            )?;
+            writeln!(out, "    </testcase>")?;
+        } else {
+            let failed = result.tests.iter().filter(|test| !test.success).count();
+            writeln!(
+                out,
+                r#"  <testsuite name="{file_xml}" tests="{}" failures="{failed}" errors="0" time="{time:.6}">"#,
+                result.tests.len()
+            )?;
+            for test in &result.tests {
+                let (line, _) = line_col(&test.span);
+                let name = xml_escape(&test.name);
+                let attrs = format!(
+                    r#"name="{name}" classname="{file_xml}" file="{file_xml}" line="{line}""#
+                );
+
+                if test.success {
+                    writeln!(out, "    <testcase {attrs}/>")?;
+                } else {
+                    let message = test.failures.first().map_or("", |f| f.description.as_str());
+                    let details = test
+                        .failures
+                        .iter()
+                        .map(|failure| failure_details(failure, &file))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                    writeln!(out, "    <testcase {attrs}>")?;
+                    writeln!(
+                        out,
+                        r#"      <failure message="{}" type="test">{}</failure>"#,
+                        xml_escape(message),
+                        xml_escape(&details)
+                    )?;
+                    writeln!(out, "    </testcase>")?;
+                }
+            }
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+
+    writeln!(out, "</testsuites>")
+}
+
+/// Human-readable description of a failure, used in the JUnit report
+fn failure_details(failure: &TestFailure, file: &str) -> String {
+    let (line, column) = line_col(&failure.span);
+    let mut details = format!("{}\n  at {file}:{line}:{column}\n", failure.description);
+    if let Some(group) = &failure.group {
+        details.push_str(&format!("  capture group: {group}\n"));
+    }
+    details.push_str(&format!("  expected: {}\n", show_text(failure.expected.as_deref())));
+    details.push_str(&format!("  actual: {}\n", show_text(failure.actual.as_deref())));
+    details
+}
+
+fn show_text(text: Option<&str>) -> String {
+    match text {
+        Some(text) => format!("{text:?}"),
+        None => "no match".to_string(),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            // these can't appear in XML 1.0 documents, not even escaped
+            '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => escaped.push('\u{fffd}'),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_tap(
+    out: &mut impl Write,
+    results: &[CompilationResult],
+    current_dir: &Path,
+) -> io::Result<()> {
+    let total = results.iter().map(|r| r.tests.len().max(is_file_error(r) as usize)).sum::<usize>();
+    writeln!(out, "TAP version 14")?;
+    writeln!(out, "1..{total}")?;
+
+    let mut number = 0;
+    for result in results {
+        let file = file_name(result, current_dir);
+
+        if is_file_error(result) {
+            number += 1;
+            writeln!(out, "not ok {number} - {}: compile", tap_escape(&file))?;
+            writeln!(out, "  ---")?;
+            let message = file_errors(result).collect::<Vec<_>>().join("\n");
+            writeln!(out, "  message: {}", yaml_string(&message))?;
+            writeln!(out, "  at:")?;
+            writeln!(out, "    file: {}", yaml_string(&file))?;
+            writeln!(out, "  ...")?;
+            continue;
+        }
+
+        for test in &result.tests {
+            number += 1;
+            let (line, _) = line_col(&test.span);
+            let description = tap_escape(&format!("{file}:{line}: {}", test.name));
+            if test.success {
+                writeln!(out, "ok {number} - {description}")?;
+            } else {
+                writeln!(out, "not ok {number} - {description}")?;
+                write_tap_failures(out, test, &file)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the YAML diagnostics block of a failed test case
+fn write_tap_failures(out: &mut impl Write, test: &TestCaseResult, file: &str) -> io::Result<()> {
+    writeln!(out, "  ---")?;
+    if let Some(first) = test.failures.first() {
+        writeln!(out, "  message: {}", yaml_string(&first.description))?;
+    }
+    writeln!(out, "  failures:")?;
+    for failure in &test.failures {
+        let (line, column) = line_col(&failure.span);
+        writeln!(out, "    - message: {}", yaml_string(&failure.description))?;
+        writeln!(out, "      at:")?;
+        writeln!(out, "        file: {}", yaml_string(file))?;
+        writeln!(out, "        line: {line}")?;
+        writeln!(out, "        column: {column}")?;
+        if let Some(group) = &failure.group {
+            writeln!(out, "      group: {}", yaml_string(group))?;
+        }
+        writeln!(out, "      expected: {}", yaml_option(failure.expected.as_deref()))?;
+        writeln!(out, "      actual: {}", yaml_option(failure.actual.as_deref()))?;
+    }
+    writeln!(out, "  ...")
+}
+
+/// `#` starts a directive in TAP, so it must be escaped in descriptions
+fn tap_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('#', "\\#").replace('\n', " ")
+}
+
+/// JSON strings are valid YAML strings
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn yaml_option(text: Option<&str>) -> String {
+    text.map_or_else(|| "null".to_string(), yaml_string)
+}
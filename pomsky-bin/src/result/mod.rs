@@ -1,7 +1,10 @@
 use std::{fmt, path::Path};
 
 use helptext::text;
-use pomsky::diagnose::{DiagnosticCode, DiagnosticKind};
+use pomsky::{
+    diagnose::{DiagnosticCode, DiagnosticKind},
+    line_index::{LineCol, LineIndex, TextUnit},
+};
 use serde::{Deserialize, Serialize};
 
 use crate::format::Logger;
@@ -23,6 +26,9 @@ pub struct CompilationResult {
     pub output: Option<String>,
     /// Array of errors and warnings
     pub diagnostics: Vec<Diagnostic>,
+    /// Results of the individual test cases, if tests were executed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TestCaseResult>,
     /// Compilation time
     pub timings: Timings,
 }
@@ -33,6 +39,203 @@ pub enum Version {
     V1,
 }
 
+/// The syntax tree of a pomsky expression, as printed by `pomsky parse --json`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParseResult {
+    /// Schema version
+    pub version: Version,
+    /// Whether parsing succeeded
+    ///
+    /// Equivalent to `result.ast.is_some()`
+    pub success: bool,
+    /// File that was parsed
+    pub path: Option<String>,
+    /// Syntax tree, with the structure of `pomsky_syntax::exprs::Rule`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast: Option<serde_json::Value>,
+    /// Array of errors and warnings
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseResult {
+    pub(crate) fn new(
+        path: Option<&Path>,
+        expr: Option<&pomsky::Expr>,
+        diagnostics: impl IntoIterator<Item = pomsky::diagnose::Diagnostic>,
+        source_code: &str,
+        lints: &pomsky::options::Lints,
+    ) -> Self {
+        let ast = expr.map(|expr| serde_json::to_value(expr).expect("AST should be serializable"));
+        Self {
+            path: path
+                .map(|p| p.canonicalize().as_deref().unwrap_or(p).to_string_lossy().to_string()),
+            version: Version::V1,
+            success: ast.is_some(),
+            ast,
+            diagnostics: CompilationResult::convert_diagnostics(
+                diagnostics,
+                source_code,
+                lints,
+                true,
+            ),
+        }
+    }
+}
+
+/// The explanation of a pomsky expression, as printed by `pomsky explain --json`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExplainResult {
+    /// Schema version
+    pub version: Version,
+    /// Whether parsing succeeded
+    ///
+    /// Equivalent to `result.explanation.is_some()`
+    pub success: bool,
+    /// File that was explained
+    pub path: Option<String>,
+    /// Explanation, with the structure of `pomsky::explain::Explanation`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<serde_json::Value>,
+    /// Array of errors and warnings
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ExplainResult {
+    pub(crate) fn new(
+        path: Option<&Path>,
+        explanation: Option<&pomsky::explain::Explanation>,
+        diagnostics: impl IntoIterator<Item = pomsky::diagnose::Diagnostic>,
+        source_code: &str,
+        lints: &pomsky::options::Lints,
+    ) -> Self {
+        let explanation = explanation.map(|explanation| {
+            serde_json::to_value(explanation).expect("explanation should be serializable")
+        });
+        Self {
+            path: path
+                .map(|p| p.canonicalize().as_deref().unwrap_or(p).to_string_lossy().to_string()),
+            version: Version::V1,
+            success: explanation.is_some(),
+            explanation,
+            diagnostics: CompilationResult::convert_diagnostics(
+                diagnostics,
+                source_code,
+                lints,
+                true,
+            ),
+        }
+    }
+}
+
+/// Lines matching a pomsky expression, as printed by `pomsky match --json`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    /// Schema version
+    pub version: Version,
+    /// Whether at least one line matched
+    pub success: bool,
+    /// File containing the pomsky expression
+    pub path: Option<String>,
+    /// The compiled regex
+    pub regex: String,
+    /// Warnings emitted while compiling the expression
+    pub diagnostics: Vec<Diagnostic>,
+    /// Searched files, in the order they were searched
+    pub files: Vec<FileMatches>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileMatches {
+    /// Searched file, or `null` for stdin
+    pub path: Option<String>,
+    /// Number of matching lines
+    pub count: usize,
+    /// Matching lines
+    ///
+    /// Empty when `--count` is used
+    pub lines: Vec<LineMatch>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineMatch {
+    /// Line number, counting from 1
+    pub line: usize,
+    /// Content of the line, without the line break
+    pub text: String,
+    /// Matches in this line. Guaranteed to be non-empty
+    pub matches: Vec<Match>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Match {
+    /// Start byte offset in the line, counting from zero
+    pub start: usize,
+    /// End byte offset in the line, non-inclusive
+    pub end: usize,
+    /// Matched text
+    pub text: String,
+    /// Named capturing groups that participated in the match, in the order
+    /// they appear in the expression
+    pub captures: Vec<NamedCapture>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NamedCapture {
+    /// Name of the capturing group
+    pub name: String,
+    /// Start byte offset in the line, counting from zero
+    pub start: usize,
+    /// End byte offset in the line, non-inclusive
+    pub end: usize,
+    /// Captured text
+    pub text: String,
+}
+
+/// Result of a single test case in a `test {}` block
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestCaseResult {
+    /// "match" | "match_in" | "reject" | "reject_in"
+    pub kind: TestCaseKind,
+    /// Short description of the test case, e.g. `match "foo" in "foobar"`
+    pub name: String,
+    /// The test string
+    pub input: String,
+    /// Location of the test string in the source code
+    pub span: Span,
+    /// Whether the test case passed
+    pub success: bool,
+    /// Reasons why the test case failed. Empty if it passed
+    pub failures: Vec<TestFailure>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TestCaseKind {
+    /// `match "foo"`
+    Match,
+    /// `match "foo" in "foobar"`
+    MatchIn,
+    /// `reject "foo"`
+    Reject,
+    /// `reject in "foo"`
+    RejectIn,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestFailure {
+    /// Error message
+    pub description: String,
+    /// Location of the failing part of the test case, e.g. an expected capture
+    pub span: Span,
+    /// Capturing group this failure refers to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Expected text, or `null` if no match was expected
+    pub expected: Option<String>,
+    /// Actual text, or `null` if there was no match
+    pub actual: Option<String>,
+}
+
 impl CompilationResult {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn success(
@@ -42,7 +245,7 @@ impl CompilationResult {
         time_test_micros: u128,
         diagnostics: impl IntoIterator<Item = pomsky::diagnose::Diagnostic>,
         source_code: &str,
-        warnings: &crate::args::DiagnosticSet,
+        lints: &pomsky::options::Lints,
         json: bool,
     ) -> Self {
         Self {
@@ -51,7 +254,8 @@ impl CompilationResult {
             version: Version::V1,
             success: true,
             output: Some(output),
-            diagnostics: Self::convert_diagnostics(diagnostics, source_code, warnings, json),
+            diagnostics: Self::convert_diagnostics(diagnostics, source_code, lints, json),
+            tests: Vec::new(),
             timings: Timings::from_micros(time_all_micros, time_test_micros),
         }
     }
@@ -62,7 +266,7 @@ impl CompilationResult {
         time_test_micros: u128,
         diagnostics: impl IntoIterator<Item = pomsky::diagnose::Diagnostic>,
         source_code: &str,
-        warnings: &crate::args::DiagnosticSet,
+        lints: &pomsky::options::Lints,
         json: bool,
     ) -> Self {
         Self {
@@ -71,22 +275,27 @@ impl CompilationResult {
             version: Version::V1,
             success: false,
             output: None,
-            diagnostics: Self::convert_diagnostics(diagnostics, source_code, warnings, json),
+            diagnostics: Self::convert_diagnostics(diagnostics, source_code, lints, json),
+            tests: Vec::new(),
             timings: Timings::from_micros(time_all_micros, time_test_micros),
         }
     }
 
-    fn convert_diagnostics(
+    pub(crate) fn convert_diagnostics(
         diagnostics: impl IntoIterator<Item = pomsky::diagnose::Diagnostic>,
         source_code: &str,
-        warnings: &crate::args::DiagnosticSet,
+        lints: &pomsky::options::Lints,
         json: bool,
     ) -> Vec<Diagnostic> {
         let source_code = Some(source_code);
         diagnostics
             .into_iter()
             .filter_map(|d| match d.severity {
-                pomsky::diagnose::Severity::Warning if !warnings.is_enabled(d.kind) => None,
+                pomsky::diagnose::Severity::Warning
+                    if lints.level(d.kind, d.code) == pomsky::options::LintLevel::Allow =>
+                {
+                    None
+                }
                 _ => Some(Diagnostic::from(d, source_code, json)),
             })
             .collect()
@@ -100,6 +309,22 @@ impl CompilationResult {
         in_test_suite: bool,
         source_code: &str,
     ) {
+        let success = self.print(logger, json, new_line, in_test_suite, source_code);
+        if !success && !in_test_suite {
+            std::process::exit(1);
+        }
+    }
+
+    /// Like [`CompilationResult::output`], but doesn't exit if compilation
+    /// failed. Returns whether it was successful.
+    pub(crate) fn print(
+        self,
+        logger: &Logger,
+        json: bool,
+        new_line: bool,
+        in_test_suite: bool,
+        source_code: &str,
+    ) -> bool {
         let success = self.success;
         if json {
             match serde_json::to_string(&self) {
@@ -112,9 +337,13 @@ impl CompilationResult {
             }
             self.output_human_readable(logger, new_line, in_test_suite, Some(source_code));
         }
-        if !success && !in_test_suite {
-            std::process::exit(1);
-        }
+        success
+    }
+
+    /// Prints the diagnostics to stderr, but not the compiled regex
+    #[cfg(feature = "test")]
+    pub(crate) fn output_diagnostics(self, logger: &Logger, source_code: &str) {
+        self.output_human_readable(logger, false, true, Some(source_code));
     }
 
     fn output_human_readable(
@@ -179,9 +408,8 @@ pub struct Diagnostic {
     ///
     /// Currently guaranteed to contain at most 1 string
     pub help: Vec<String>,
-    /// Automatically applicable fixes
-    ///
-    /// Currently unused and guaranteed to be empty
+    /// Automatically applicable fixes. If there are several, the first one is
+    /// the most likely to be correct
     pub fixes: Vec<QuickFix>,
     /// Visual representation of the diagnostic as displayed in the CLI
     pub visual: String,
@@ -279,11 +507,64 @@ pub struct Span {
     /// Currently unused, guaranteed to be absent
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
+    /// Line and column of `start`
+    ///
+    /// Present when the source code is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_position: Option<Position>,
+    /// Line and column of `end`
+    ///
+    /// Present when the source code is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_position: Option<Position>,
 }
 
 impl From<std::ops::Range<usize>> for Span {
     fn from(value: std::ops::Range<usize>) -> Self {
-        Span { start: value.start, end: value.end, label: None }
+        Span {
+            start: value.start,
+            end: value.end,
+            label: None,
+            start_position: None,
+            end_position: None,
+        }
+    }
+}
+
+impl Span {
+    pub(crate) fn with_positions(mut self, source_code: &str, line_index: &LineIndex) -> Self {
+        self.start_position = Some(Position::new(source_code, line_index, self.start));
+        self.end_position = Some(Position::new(source_code, line_index, self.end));
+        self
+    }
+}
+
+/// A position in the source code. All values count from zero.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Line number
+    pub line: usize,
+    /// Column in UTF-8 bytes
+    pub column: usize,
+    /// Column in UTF-16 code units, e.g. for JavaScript or LSP clients
+    pub column_utf16: usize,
+    /// Column in Unicode scalar values
+    pub column_char: usize,
+    /// Offset from the start of the source code in UTF-16 code units
+    pub offset_utf16: usize,
+}
+
+impl Position {
+    fn new(source_code: &str, line_index: &LineIndex, offset: usize) -> Self {
+        let LineCol { line, col: column } =
+            line_index.line_col(source_code, offset, TextUnit::Utf8);
+        Position {
+            line,
+            column,
+            column_utf16: line_index.line_col(source_code, offset, TextUnit::Utf16).col,
+            column_char: line_index.line_col(source_code, offset, TextUnit::Char).col,
+            offset_utf16: TextUnit::Utf16.offset(source_code, offset),
+        }
     }
 }
 
@@ -296,6 +577,10 @@ pub struct QuickFix {
     /// Guaranteed to be in source order and non-overlapping (e.g. `1-4`,
     /// `7-12`, `14-15`, `16-16`)
     pub replacements: Vec<Replacement>,
+    /// Whether applying the fix doesn't change what the expression matches.
+    /// Other fixes, e.g. for typos, have to guess what was meant.
+    #[serde(default)]
+    pub preserves_meaning: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -313,6 +598,23 @@ pub struct Replacement {
     pub insert: String,
 }
 
+impl From<pomsky::diagnose::QuickFix> for QuickFix {
+    fn from(value: pomsky::diagnose::QuickFix) -> Self {
+        QuickFix {
+            description: value.description,
+            replacements: value.replacements.into_iter().map(From::from).collect(),
+            preserves_meaning: value.preserves_meaning,
+        }
+    }
+}
+
+impl From<pomsky::diagnose::Replacement> for Replacement {
+    fn from(value: pomsky::diagnose::Replacement) -> Self {
+        let range = value.span.range_unchecked();
+        Replacement { start: range.start, end: range.end, insert: value.insert }
+    }
+}
+
 impl Diagnostic {
     pub(crate) fn from(
         value: pomsky::diagnose::Diagnostic,
@@ -339,10 +641,23 @@ impl Diagnostic {
             severity: value.severity.into(),
             kind: value.kind.into(),
             code: value.code,
-            spans: value.span.range().into_iter().map(From::from).collect(),
+            spans: value
+                .span
+                .range()
+                .into_iter()
+                .map(|range| {
+                    let span = Span::from(range);
+                    match source_code {
+                        Some(source_code) => {
+                            span.with_positions(source_code, &LineIndex::new(source_code))
+                        }
+                        None => span,
+                    }
+                })
+                .collect(),
             description: value.msg,
             help: value.help.into_iter().collect(),
-            fixes: vec![],
+            fixes: value.fixes.into_iter().map(From::from).collect(),
             visual,
         }
     }
@@ -392,7 +707,7 @@ impl Diagnostic {
             }
 
             fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
-                if let Some(Span { start, end, label }) = self.diagnostic.spans.first() {
+                if let Some(Span { start, end, label, .. }) = self.diagnostic.spans.first() {
                     let label = label.as_deref().unwrap_or(match self.diagnostic.severity {
                         Severity::Error => "error occurred here",
                         Severity::Warning => "warning originated here",
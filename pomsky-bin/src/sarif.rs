@@ -0,0 +1,213 @@
+//! Diagnostics in the [SARIF](https://sarifweb.azurewebsites.net/) format,
+//! printed with `--format sarif`
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde_json::{Value, json};
+
+use crate::{CompilationResult, Diagnostic, Severity, Span};
+
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Prints a SARIF log containing the diagnostics of all compiled files to
+/// stdout. Paths are made relative to `current_dir` where possible.
+pub(crate) fn print(results: &[CompilationResult], current_dir: &Path) {
+    let current_dir = current_dir.canonicalize().unwrap_or_else(|_| current_dir.to_owned());
+
+    let mut rules = Rules::default();
+    let mut sarif_results = Vec::new();
+    for result in results {
+        let artifact = result.path.as_deref().map(|path| artifact_location(path, &current_dir));
+        for diagnostic in &result.diagnostics {
+            sarif_results.push(convert_diagnostic(diagnostic, artifact.as_ref(), &mut rules));
+        }
+    }
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "pomsky",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": "https://pomsky-lang.org/",
+                "rules": rules.into_json(),
+            }
+        },
+        "columnKind": "utf16CodeUnits",
+        "results": sarif_results,
+    });
+    if let Some(uri) = directory_uri(&current_dir) {
+        run["originalUriBaseIds"] = json!({ SRCROOT: { "uri": uri } });
+    }
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [run],
+    });
+    match serde_json::to_string(&log) {
+        Ok(string) => println!("{string}"),
+        Err(e) => eprintln!("{e}"),
+    }
+}
+
+/// Rules that were reported, by their ID. Diagnostics without a code are
+/// reported with their kind as the rule ID
+#[derive(Default)]
+struct Rules {
+    rules: BTreeMap<String, Option<String>>,
+}
+
+impl Rules {
+    fn add(&mut self, diagnostic: &Diagnostic) -> String {
+        let (id, name) = match diagnostic.code {
+            Some(code) => (code.to_string(), Some(format!("{code:?}"))),
+            None => (diagnostic.kind.as_str().to_string(), None),
+        };
+        self.rules.entry(id.clone()).or_insert(name);
+        id
+    }
+
+    fn into_json(self) -> Vec<Value> {
+        self.rules
+            .into_iter()
+            .map(|(id, name)| match name {
+                Some(name) => json!({ "id": id, "name": name }),
+                None => json!({ "id": id }),
+            })
+            .collect()
+    }
+}
+
+fn convert_diagnostic(
+    diagnostic: &Diagnostic,
+    artifact: Option<&Value>,
+    rules: &mut Rules,
+) -> Value {
+    let mut message = diagnostic.description.clone();
+    for help in &diagnostic.help {
+        message.push_str("\nhelp: ");
+        message.push_str(help);
+    }
+
+    let mut result = json!({
+        "ruleId": rules.add(diagnostic),
+        "level": match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        "message": { "text": message },
+    });
+
+    // locations and fixes refer to a file, so they're omitted when compiling
+    // an expression passed as argument
+    if let Some(artifact) = artifact {
+        let locations = diagnostic
+            .spans
+            .iter()
+            .map(|span| {
+                json!({
+                    "physicalLocation": {
+                        "artifactLocation": artifact,
+                        "region": region(span),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        if !locations.is_empty() {
+            result["locations"] = locations.into();
+        }
+
+        let fixes = diagnostic
+            .fixes
+            .iter()
+            .map(|fix| {
+                let replacements = fix
+                    .replacements
+                    .iter()
+                    .map(|replacement| {
+                        json!({
+                            "deletedRegion": {
+                                "byteOffset": replacement.start,
+                                "byteLength": replacement.end - replacement.start,
+                            },
+                            "insertedContent": { "text": replacement.insert },
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "description": { "text": fix.description },
+                    "artifactChanges": [{
+                        "artifactLocation": artifact,
+                        "replacements": replacements,
+                    }],
+                })
+            })
+            .collect::<Vec<_>>();
+        if !fixes.is_empty() {
+            result["fixes"] = fixes.into();
+        }
+    }
+
+    result
+}
+
+/// Lines and columns in SARIF count from 1, and columns are measured in
+/// UTF-16 code units, as declared by the run's `columnKind`
+fn region(span: &Span) -> Value {
+    let mut region = json!({
+        "byteOffset": span.start,
+        "byteLength": span.end - span.start,
+    });
+    if let (Some(start), Some(end)) = (&span.start_position, &span.end_position) {
+        region["startLine"] = (start.line + 1).into();
+        region["startColumn"] = (start.column_utf16 + 1).into();
+        region["endLine"] = (end.line + 1).into();
+        region["endColumn"] = (end.column_utf16 + 1).into();
+    }
+    region
+}
+
+fn artifact_location(path: &str, current_dir: &Path) -> Value {
+    let path = Path::new(path);
+    match path.strip_prefix(current_dir) {
+        Ok(relative) => json!({
+            "uri": encode_path(&relative.to_string_lossy()),
+            "uriBaseId": SRCROOT,
+        }),
+        Err(_) => json!({ "uri": file_uri(&path.to_string_lossy()) }),
+    }
+}
+
+fn directory_uri(dir: &Path) -> Option<String> {
+    if !dir.is_absolute() {
+        return None;
+    }
+    let mut uri = file_uri(&dir.to_string_lossy());
+    // a base URI must end with a slash
+    if !uri.ends_with('/') {
+        uri.push('/');
+    }
+    Some(uri)
+}
+
+/// Converts an absolute path to a `file:` URI
+fn file_uri(path: &str) -> String {
+    let path = encode_path(path);
+    if path.starts_with('/') { format!("file://{path}") } else { format!("file:///{path}") }
+}
+
+/// Percent-encodes a path for use in a URI, and replaces backslashes on Windows
+/// with forward slashes
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'\\' if cfg!(windows) => encoded.push('/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
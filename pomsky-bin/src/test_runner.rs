@@ -1,315 +1,317 @@
-use std::ops::Index;
-
-use pcre2::bytes::Regex as PcreRegex;
 use pomsky::{
     Expr,
     diagnose::{Diagnostic, DiagnosticCode, Severity},
     features::PomskyFeatures,
+    line_index::LineIndex,
     options::CompileOptions,
-    test::{CaptureIdent, TestCase, TestCaseMatch, TestCaseMatchAll, TestCaseReject},
+    test::{CaptureIdent, TestCapture, TestCase, TestCaseMatch, TestCaseMatchAll, TestCaseReject},
 };
-use regex::Regex as RustRegex;
 
-use crate::args::RegexEngine;
+use crate::{
+    args::RegexEngine,
+    engine::{Captures, Regex},
+    result::{Span, TestCaseKind, TestCaseResult, TestFailure},
+};
 
+/// Runs all tests in the expression. Failures are added to `errors`, and
+/// the results of the individual test cases are returned.
 pub(crate) fn run_tests(
     parsed: &Expr,
     input: &str,
     options: CompileOptions,
     test_engine: RegexEngine,
     errors: &mut Vec<Diagnostic>,
-) {
-    let (Some(pattern), _) = parsed
-        .compile(input, CompileOptions { allowed_features: PomskyFeatures::default(), ..options })
+) -> Vec<TestCaseResult> {
+    let (Some(pattern), _) =
+        parsed.compile(input, options.with_allowed_features(PomskyFeatures::default()))
     else {
         let msg = "Failed to compile the expression in the PCRE flavor for running tests".into();
         errors.push(Diagnostic::ad_hoc(Severity::Error, None, msg, None));
-        return;
+        return Vec::new();
     };
 
-    let regex = match test_engine {
-        RegexEngine::Pcre2 => {
-            let regex = pcre2::bytes::RegexBuilder::new()
-                .jit_if_available(true)
-                .ucp(true)
-                .utf(true)
-                .build(&pattern);
-
-            match regex {
-                Ok(regex) => Regex::Pcre(regex),
-                Err(e) => {
-                    let help = Some(format!("The compiled regex is {pattern:?}"));
-                    errors.push(Diagnostic::ad_hoc(Severity::Error, None, e.to_string(), help));
-                    return;
-                }
-            }
-        }
-        RegexEngine::Rust => {
-            let regex = RustRegex::new(&pattern);
-
-            match regex {
-                Ok(regex) => Regex::Rust(regex),
-                Err(e) => {
-                    let help = Some(format!("The compiled regex is {pattern:?}"));
-                    errors.push(Diagnostic::ad_hoc(Severity::Error, None, e.to_string(), help));
-                    return;
-                }
-            }
+    let regex = match Regex::new(test_engine, &pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            let help = Some(format!("The compiled regex is {pattern:?}"));
+            errors.push(Diagnostic::ad_hoc(Severity::Error, None, e, help));
+            return Vec::new();
         }
     };
 
+    let line_index = LineIndex::new(input);
+    let mut results = Vec::new();
+
     let tests = parsed.extract_tests_ref();
     for test in tests {
         for test_case in &test.cases {
-            match test_case {
-                TestCase::Match(m) => check_test_match(&regex, m, errors),
-                TestCase::MatchAll(a) => check_all_test_matches(&regex, a, errors),
-                TestCase::Reject(r) => check_test_reject(&regex, r, errors),
-            }
-        }
-    }
-}
-
-fn check_test_match(regex: &Regex, test_case: &TestCaseMatch, errors: &mut Vec<Diagnostic>) {
-    let result = regex.captures(&test_case.literal.content);
-    match result {
-        Ok(Some(captures)) => {
-            if captures[0].len() != test_case.literal.content.len() {
-                errors.push(Diagnostic::test_failure(
-                    test_case.literal.span,
-                    DiagnosticCode::TestNoExactMatch,
-                    None,
-                ));
-                return;
-            }
-
-            for capture in &test_case.captures {
-                let Some(got_capture) = (match &capture.ident {
-                    CaptureIdent::Name(name) => captures.name(name),
-                    &CaptureIdent::Index(idx) => captures.get(idx as usize),
-                }) else {
-                    errors.push(Diagnostic::test_failure(
-                        capture.ident_span,
-                        DiagnosticCode::TestMissingCaptureGroup,
-                        None,
-                    ));
-                    continue;
-                };
-                if got_capture.as_bytes() != capture.literal.content.as_bytes() {
-                    errors.push(Diagnostic::test_failure(
-                        capture.literal.span,
-                        DiagnosticCode::TestWrongCaptureGroup,
-                        Some(&String::from_utf8_lossy(got_capture.as_bytes())),
-                    ));
+            let mut checker = Checker {
+                regex: &regex,
+                source_code: input,
+                line_index: &line_index,
+                errors: &mut *errors,
+                failures: Vec::new(),
+            };
+            let (kind, name, literal) = match test_case {
+                TestCase::Match(m) => {
+                    checker.check_test_match(m);
+                    (TestCaseKind::Match, format!("match {:?}", m.literal.content), &m.literal)
                 }
-            }
-        }
-        Ok(None) => {
-            errors.push(Diagnostic::test_failure(
-                test_case.literal.span,
-                DiagnosticCode::TestNoExactMatch,
-                None,
-            ));
-        }
-        Err(e) => {
-            let help = Some(format!("The compiled regex is {:?}", regex.as_str()));
-            errors.push(Diagnostic::ad_hoc(Severity::Error, None, e.to_string(), help));
-        }
-    }
-}
-
-fn check_all_test_matches(
-    regex: &Regex,
-    test_case: &TestCaseMatchAll,
-    errors: &mut Vec<Diagnostic>,
-) {
-    let captures_iter = regex
-        .captures_iter(&test_case.literal.content)
-        .map(Some)
-        .chain(std::iter::repeat_with(|| None));
-    let expected_iter = test_case.matches.iter().map(Some).chain(std::iter::repeat(None));
-
-    for (i, (captures, expected)) in std::iter::zip(captures_iter, expected_iter).enumerate() {
-        match (captures, expected) {
-            (None, None) => break,
-
-            (None, Some(expected)) => {
-                errors.push(Diagnostic::test_failure(
-                    expected.literal.span,
-                    DiagnosticCode::TestMissingSubstringMatch,
-                    None,
-                ));
-            }
-            (Some(Ok(captures)), None) => {
-                if i == 0 {
-                    break;
+                TestCase::MatchAll(a) => {
+                    checker.check_all_test_matches(a);
+                    let matches =
+                        a.matches.iter().map(|m| format!("{:?}", m.literal.content)).collect();
+                    let name = match_all_name(matches, &a.literal.content);
+                    (TestCaseKind::MatchIn, name, &a.literal)
                 }
-                errors.push(Diagnostic::test_failure(
-                    test_case.literal.span,
-                    DiagnosticCode::TestUnexpectedSubstringMatch,
-                    Some(&String::from_utf8_lossy(&captures[0])),
-                ));
-            }
-            (Some(Ok(captures)), Some(test_case)) => {
-                if &captures[0] != test_case.literal.content.as_bytes() {
-                    errors.push(Diagnostic::test_failure(
-                        test_case.literal.span,
-                        DiagnosticCode::TestWrongSubstringMatch,
-                        Some(&String::from_utf8_lossy(&captures[0])),
-                    ));
-                    continue;
+                TestCase::Reject(r) if r.as_substring => {
+                    checker.check_test_reject(r);
+                    (
+                        TestCaseKind::RejectIn,
+                        format!("reject in {:?}", r.literal.content),
+                        &r.literal,
+                    )
                 }
-
-                for capture in &test_case.captures {
-                    let Some(got_capture) = (match &capture.ident {
-                        CaptureIdent::Name(name) => captures.name(name),
-                        &CaptureIdent::Index(idx) => captures.get(idx as usize),
-                    }) else {
-                        errors.push(Diagnostic::test_failure(
-                            capture.ident_span,
-                            DiagnosticCode::TestMissingCaptureGroup,
-                            None,
-                        ));
-                        continue;
-                    };
-                    if got_capture.as_bytes() != capture.literal.content.as_bytes() {
-                        errors.push(Diagnostic::test_failure(
-                            capture.literal.span,
-                            DiagnosticCode::TestWrongCaptureGroup,
-                            Some(&String::from_utf8_lossy(got_capture.as_bytes())),
-                        ));
-                    }
+                TestCase::Reject(r) => {
+                    checker.check_test_reject(r);
+                    (TestCaseKind::Reject, format!("reject {:?}", r.literal.content), &r.literal)
                 }
-            }
+            };
 
-            (Some(Err(e)), _) => {
-                let help = Some(format!("The compiled regex is {:?}", regex.as_str()));
-                errors.push(Diagnostic::ad_hoc(Severity::Error, None, e.to_string(), help));
-            }
+            results.push(TestCaseResult {
+                kind,
+                name,
+                input: literal.content.clone(),
+                span: checker.span(literal.span),
+                success: checker.failures.is_empty(),
+                failures: checker.failures,
+            });
         }
     }
+    results
 }
 
-fn check_test_reject(regex: &Regex, test_case: &TestCaseReject, errors: &mut Vec<Diagnostic>) {
-    let result = regex.captures(&test_case.literal.content);
-    match result {
-        Ok(Some(captures)) => {
-            let is_exact = captures[0].len() == test_case.literal.content.len();
-            if test_case.as_substring || is_exact {
-                let actual_value;
-                let (code, actual_value) = if is_exact || !test_case.as_substring {
-                    (DiagnosticCode::TestUnexpectedExactMatch, None)
-                } else {
-                    actual_value = String::from_utf8_lossy(&captures[0]);
-                    (DiagnosticCode::TestUnexpectedSubstringMatch, Some(&*actual_value))
-                };
-                errors.push(Diagnostic::test_failure(test_case.literal.span, code, actual_value));
-            }
-        }
-        Ok(None) => {
-            // success
-        }
-        Err(e) => {
-            errors.push(Diagnostic::ad_hoc(
-                Severity::Error,
-                None,
-                e.to_string(),
-                Some(format!("The compiled regex is {:?}", regex.as_str())),
-            ));
-        }
+fn match_all_name(matches: Vec<String>, haystack: &str) -> String {
+    if matches.is_empty() {
+        format!("match in {haystack:?}")
+    } else {
+        format!("match {} in {haystack:?}", matches.join(", "))
     }
 }
 
-enum Regex {
-    Pcre(PcreRegex),
-    Rust(RustRegex),
+/// Checks a single test case
+struct Checker<'a> {
+    regex: &'a Regex,
+    source_code: &'a str,
+    line_index: &'a LineIndex,
+    errors: &'a mut Vec<Diagnostic>,
+    failures: Vec<TestFailure>,
 }
 
-impl Regex {
-    fn as_str(&self) -> &str {
-        match self {
-            Regex::Pcre(regex) => regex.as_str(),
-            Regex::Rust(regex) => regex.as_str(),
-        }
+impl Checker<'_> {
+    fn span(&self, span: pomsky::Span) -> Span {
+        Span::from(span.range_unchecked()).with_positions(self.source_code, self.line_index)
     }
 
-    fn captures<'a>(&'a self, subject: &'a str) -> Result<Option<Captures<'a>>, pcre2::Error> {
-        match self {
-            Regex::Pcre(regex) => regex.captures(subject.as_bytes()).map(|o| o.map(Captures::Pcre)),
-            Regex::Rust(regex) => Ok(regex.captures(subject).map(Captures::Rust)),
-        }
+    /// Adds a test failure to the test case, and its diagnostic to the errors
+    fn fail(
+        &mut self,
+        diagnostic: Diagnostic,
+        span: pomsky::Span,
+        group: Option<&CaptureIdent>,
+        expected: Option<&str>,
+        actual: Option<&str>,
+    ) {
+        self.failures.push(TestFailure {
+            description: diagnostic.msg.clone(),
+            span: self.span(span),
+            group: group.map(|ident| match ident {
+                CaptureIdent::Name(name) => name.clone(),
+                CaptureIdent::Index(index) => index.to_string(),
+            }),
+            expected: expected.map(String::from),
+            actual: actual.map(String::from),
+        });
+        self.errors.push(diagnostic);
     }
 
-    fn captures_iter<'r, 's>(&'r self, subject: &'s str) -> CaptureMatches<'r, 's> {
-        match self {
-            Regex::Pcre(regex) => CaptureMatches::Pcre(regex.captures_iter(subject.as_bytes())),
-            Regex::Rust(regex) => CaptureMatches::Rust(regex.captures_iter(subject)),
-        }
+    fn engine_error(&mut self, error: impl ToString, span: pomsky::Span) {
+        let help = Some(format!("The compiled regex is {:?}", self.regex.as_str()));
+        let diagnostic = Diagnostic::ad_hoc(Severity::Error, None, error.to_string(), help);
+        self.fail(diagnostic, span, None, None, None);
     }
-}
 
-enum Captures<'a> {
-    Pcre(pcre2::bytes::Captures<'a>),
-    Rust(regex::Captures<'a>),
-}
+    fn check_test_match(&mut self, test_case: &TestCaseMatch) {
+        let result = self.regex.captures(&test_case.literal.content);
+        match result {
+            Ok(Some(captures)) => {
+                if captures[0].len() != test_case.literal.content.len() {
+                    let actual = String::from_utf8_lossy(&captures[0]);
+                    self.fail(
+                        Diagnostic::test_failure(
+                            test_case.literal.span,
+                            DiagnosticCode::TestNoExactMatch,
+                            None,
+                        ),
+                        test_case.literal.span,
+                        None,
+                        Some(&test_case.literal.content),
+                        Some(&actual),
+                    );
+                    return;
+                }
 
-impl Captures<'_> {
-    fn name(&self, name: &str) -> Option<Match<'_>> {
-        match self {
-            Captures::Pcre(captures) => captures.name(name).map(Match::Pcre),
-            Captures::Rust(captures) => captures.name(name).map(Match::Rust),
+                self.check_captures(&captures, &test_case.captures);
+            }
+            Ok(None) => {
+                self.fail(
+                    Diagnostic::test_failure(
+                        test_case.literal.span,
+                        DiagnosticCode::TestNoExactMatch,
+                        None,
+                    ),
+                    test_case.literal.span,
+                    None,
+                    Some(&test_case.literal.content),
+                    None,
+                );
+            }
+            Err(e) => self.engine_error(e, test_case.literal.span),
         }
     }
 
-    fn get(&self, index: usize) -> Option<Match<'_>> {
-        match self {
-            Captures::Pcre(captures) => captures.get(index).map(Match::Pcre),
-            Captures::Rust(captures) => captures.get(index).map(Match::Rust),
-        }
-    }
-}
+    fn check_all_test_matches(&mut self, test_case: &TestCaseMatchAll) {
+        let regex = self.regex;
+        let captures_iter = regex
+            .captures_iter(&test_case.literal.content)
+            .map(Some)
+            .chain(std::iter::repeat_with(|| None));
+        let expected_iter = test_case.matches.iter().map(Some).chain(std::iter::repeat(None));
 
-impl Index<usize> for Captures<'_> {
-    type Output = [u8];
+        for (i, (captures, expected)) in std::iter::zip(captures_iter, expected_iter).enumerate() {
+            match (captures, expected) {
+                (None, None) => break,
 
-    fn index(&self, index: usize) -> &Self::Output {
-        match self {
-            Captures::Pcre(captures) => &captures[index],
-            Captures::Rust(captures) => captures[index].as_bytes(),
-        }
-    }
-}
+                (None, Some(expected)) => {
+                    self.fail(
+                        Diagnostic::test_failure(
+                            expected.literal.span,
+                            DiagnosticCode::TestMissingSubstringMatch,
+                            None,
+                        ),
+                        expected.literal.span,
+                        None,
+                        Some(&expected.literal.content),
+                        None,
+                    );
+                }
+                (Some(Ok(captures)), None) => {
+                    if i == 0 {
+                        break;
+                    }
+                    let actual = String::from_utf8_lossy(&captures[0]);
+                    self.fail(
+                        Diagnostic::test_failure(
+                            test_case.literal.span,
+                            DiagnosticCode::TestUnexpectedSubstringMatch,
+                            Some(&actual),
+                        ),
+                        test_case.literal.span,
+                        None,
+                        None,
+                        Some(&actual),
+                    );
+                }
+                (Some(Ok(captures)), Some(test_case)) => {
+                    if &captures[0] != test_case.literal.content.as_bytes() {
+                        let actual = String::from_utf8_lossy(&captures[0]);
+                        self.fail(
+                            Diagnostic::test_failure(
+                                test_case.literal.span,
+                                DiagnosticCode::TestWrongSubstringMatch,
+                                Some(&actual),
+                            ),
+                            test_case.literal.span,
+                            None,
+                            Some(&test_case.literal.content),
+                            Some(&actual),
+                        );
+                        continue;
+                    }
 
-enum Match<'s> {
-    Pcre(pcre2::bytes::Match<'s>),
-    Rust(regex::Match<'s>),
-}
+                    self.check_captures(&captures, &test_case.captures);
+                }
 
-impl Match<'_> {
-    fn as_bytes(&self) -> &[u8] {
-        match self {
-            Match::Pcre(mat) => mat.as_bytes(),
-            Match::Rust(mat) => mat.as_str().as_bytes(),
+                (Some(Err(e)), _) => self.engine_error(e, test_case.literal.span),
+            }
         }
     }
-}
 
-enum CaptureMatches<'r, 's> {
-    Pcre(pcre2::bytes::CaptureMatches<'r, 's>),
-    Rust(regex::CaptureMatches<'r, 's>),
-}
-
-impl<'s> Iterator for CaptureMatches<'_, 's> {
-    type Item = Result<Captures<'s>, pcre2::Error>;
+    fn check_captures(&mut self, captures: &Captures<'_>, expected: &[TestCapture]) {
+        for capture in expected {
+            let Some(got_capture) = (match &capture.ident {
+                CaptureIdent::Name(name) => captures.name(name),
+                &CaptureIdent::Index(idx) => captures.get(idx as usize),
+            }) else {
+                self.fail(
+                    Diagnostic::test_failure(
+                        capture.ident_span,
+                        DiagnosticCode::TestMissingCaptureGroup,
+                        None,
+                    ),
+                    capture.ident_span,
+                    Some(&capture.ident),
+                    Some(&capture.literal.content),
+                    None,
+                );
+                continue;
+            };
+            if got_capture.as_bytes() != capture.literal.content.as_bytes() {
+                let actual = String::from_utf8_lossy(got_capture.as_bytes());
+                self.fail(
+                    Diagnostic::test_failure(
+                        capture.literal.span,
+                        DiagnosticCode::TestWrongCaptureGroup,
+                        Some(&actual),
+                    ),
+                    capture.literal.span,
+                    Some(&capture.ident),
+                    Some(&capture.literal.content),
+                    Some(&actual),
+                );
+            }
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            CaptureMatches::Pcre(capture_matches) => {
-                capture_matches.next().map(|r| r.map(Captures::Pcre))
+    fn check_test_reject(&mut self, test_case: &TestCaseReject) {
+        let result = self.regex.captures(&test_case.literal.content);
+        match result {
+            Ok(Some(captures)) => {
+                let is_exact = captures[0].len() == test_case.literal.content.len();
+                if test_case.as_substring || is_exact {
+                    let actual_value = String::from_utf8_lossy(&captures[0]);
+                    let code = if is_exact || !test_case.as_substring {
+                        DiagnosticCode::TestUnexpectedExactMatch
+                    } else {
+                        DiagnosticCode::TestUnexpectedSubstringMatch
+                    };
+                    let help_value = match code {
+                        DiagnosticCode::TestUnexpectedSubstringMatch => Some(&*actual_value),
+                        _ => None,
+                    };
+                    self.fail(
+                        Diagnostic::test_failure(test_case.literal.span, code, help_value),
+                        test_case.literal.span,
+                        None,
+                        None,
+                        Some(&actual_value),
+                    );
+                }
             }
-            CaptureMatches::Rust(capture_matches) => {
-                capture_matches.next().map(|c| Ok(Captures::Rust(c)))
+            Ok(None) => {
+                // success
             }
+            Err(e) => self.engine_error(e, test_case.literal.span),
         }
     }
 }
@@ -1,12 +1,20 @@
-use std::{path::Path, process::exit, time::Instant};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::exit,
+    time::Instant,
+};
 
 use helptext::text;
 use pomsky::options::RegexFlavor;
 
 use crate::{
     CompilationResult,
-    args::{CompileOptions, GlobalOptions, Input, RegexEngine, TestOptions},
+    args::{CompileOptions, GlobalOptions, Input, RegexEngine, Reporter, TestOptions},
+    files::{handle_walk_error, pomsky_files},
     format::Logger,
+    reporters::report,
+    watch::Watcher,
 };
 
 pub(crate) struct TestDirectoryResult {
@@ -16,34 +24,26 @@ pub(crate) struct TestDirectoryResult {
 }
 
 pub(crate) fn test(logger: &Logger, args: GlobalOptions, test_args: TestOptions) {
-    let (test_engine, flavor) = match (args.flavor, test_args.engine) {
-        (None, None) => {
-            logger.error().println("No regex engine specified");
-            exit(2);
-        }
-        (None, Some(engine)) => match engine {
-            RegexEngine::Pcre2 => (engine, RegexFlavor::Pcre),
-            RegexEngine::Rust => (engine, RegexFlavor::Rust),
-        },
-        (Some(flavor), None) => match flavor {
-            RegexFlavor::Pcre => (RegexEngine::Pcre2, flavor),
-            RegexFlavor::Rust => (RegexEngine::Rust, flavor),
-            _ => {
-                logger
-                    .error()
-                    .println(format_args!("No supported regex engine for the {flavor:?} flavor"));
-                exit(2);
-            }
-        },
-        (Some(flavor), Some(engine)) => (engine, flavor),
-    };
+    let (test_engine, flavor) = select_engine(logger, args.flavor, test_args.engine);
 
-    let args = GlobalOptions { flavor: Some(flavor), ..args };
+    // all reporters except `pretty` print the results after all tests ran
+    let reporter = test_args.reporter;
+    let quiet_logger;
+    let logger = if matches!(reporter, Reporter::Junit | Reporter::Tap) {
+        quiet_logger = logger.color(false).enabled(false);
+        &quiet_logger
+    } else {
+        logger
+    };
+    let args = GlobalOptions { flavor: Some(flavor), json: reporter != Reporter::Pretty, ..args };
     let compile_args = CompileOptions {
         input: Input::File(test_args.path),
         no_new_line: false,
         test: Some(test_engine),
         in_test_suite: true,
+        fix: false,
+        unsafe_fixes: false,
+        watch: false,
     };
 
     let Input::File(path) = &compile_args.input else { unreachable!() };
@@ -58,6 +58,10 @@ pub(crate) fn test(logger: &Logger, args: GlobalOptions, test_args: TestOptions)
         exit(3);
     };
 
+    if test_args.watch {
+        watch_tests(logger, &path, metadata.is_dir(), &current_dir, &args, &compile_args, reporter);
+    }
+
     if metadata.is_dir() {
         let start = Instant::now();
 
@@ -89,12 +93,18 @@ pub(crate) fn test(logger: &Logger, args: GlobalOptions, test_args: TestOptions)
                 {&total.to_string()} " files tested in " {&time_fmt}
             ]);
         }
-        print_json_test_result(args.json, &results);
+        report(reporter, &results, &current_dir);
+        if failed > 0 {
+            exit(1);
+        }
     } else if metadata.is_file() {
         let mut results = Vec::new();
         let mut failed = 0;
         test_single(logger, &path, &current_dir, &args, &compile_args, &mut results, &mut failed);
-        print_json_test_result(args.json, &results);
+        report(reporter, &results, &current_dir);
+        if failed > 0 {
+            exit(1);
+        }
     } else {
         logger.error().println(format_args!(
             "expected file or directory, but `{}` is neither",
@@ -104,6 +114,36 @@ pub(crate) fn test(logger: &Logger, args: GlobalOptions, test_args: TestOptions)
     }
 }
 
+/// Returns the regex engine and the flavor to compile for. If only one of them
+/// is given, the other one is inferred.
+pub(crate) fn select_engine(
+    logger: &Logger,
+    flavor: Option<RegexFlavor>,
+    engine: Option<RegexEngine>,
+) -> (RegexEngine, RegexFlavor) {
+    match (flavor, engine) {
+        (None, None) => {
+            logger.error().println("No regex engine specified");
+            exit(2);
+        }
+        (None, Some(engine)) => match engine {
+            RegexEngine::Pcre2 => (engine, RegexFlavor::Pcre),
+            RegexEngine::Rust => (engine, RegexFlavor::Rust),
+        },
+        (Some(flavor), None) => match flavor {
+            RegexFlavor::Pcre => (RegexEngine::Pcre2, flavor),
+            RegexFlavor::Rust => (RegexEngine::Rust, flavor),
+            _ => {
+                logger
+                    .error()
+                    .println(format_args!("No supported regex engine for the {flavor:?} flavor"));
+                exit(2);
+            }
+        },
+        (Some(flavor), Some(engine)) => (engine, flavor),
+    }
+}
+
 fn test_directory(
     logger: &Logger,
     path: &Path,
@@ -115,24 +155,85 @@ fn test_directory(
     let mut failed = 0;
     let mut results = Vec::new();
 
-    for entry in ignore::WalkBuilder::new(path)
-        .follow_links(true)
-        .filter_entry(is_dir_or_pomsky_file)
-        .build()
-    {
-        if let Ok(entry) = entry.map_err(|d| handle_walk_error(d, logger, current_dir))
-            && entry.file_type().is_some_and(|ty| ty.is_file())
-        {
-            total += 1;
-
-            let path = entry.path();
-            test_single(logger, path, current_dir, args, compile_args, &mut results, &mut failed);
-        };
+    for path in pomsky_files(path, |error| handle_walk_error(error, logger, current_dir)) {
+        total += 1;
+        test_single(logger, &path, current_dir, args, compile_args, &mut results, &mut failed);
     }
 
     TestDirectoryResult { total, failed, results }
 }
 
+/// Tests all files, then tests the files that changed whenever something
+/// changes, until the process is killed
+fn watch_tests(
+    logger: &Logger,
+    path: &Path,
+    is_dir: bool,
+    current_dir: &Path,
+    args: &GlobalOptions,
+    compile_args: &CompileOptions,
+    reporter: Reporter,
+) -> ! {
+    let mut watcher = Watcher::new(|| {
+        if is_dir {
+            // errors are reported when the files are tested
+            pomsky_files(path, |_| {})
+        } else {
+            vec![path.to_owned()]
+        }
+    });
+    let mut to_test: Vec<PathBuf> = if is_dir {
+        pomsky_files(path, |error| handle_walk_error(error, logger, current_dir))
+    } else {
+        vec![path.to_owned()]
+    };
+    let mut failing = BTreeSet::new();
+
+    loop {
+        logger.clear_screen();
+        let start = Instant::now();
+
+        let mut results = Vec::new();
+        for file in &to_test {
+            let mut failed = 0;
+            test_single(logger, file, current_dir, args, compile_args, &mut results, &mut failed);
+            if failed > 0 {
+                failing.insert(file.clone());
+            } else {
+                failing.remove(file);
+            }
+        }
+        report(reporter, &results, current_dir);
+
+        logger.emptyln();
+        for file in failing.iter().filter(|&file| !to_test.contains(file)) {
+            logger.basic().fmtln(text![R!"still failing: " {&show_relative(file, current_dir)}]);
+        }
+
+        let tested = to_test.len().to_string();
+        let total = watcher.files().count().to_string();
+        let time_fmt = format!("{:.2?}", start.elapsed());
+        if failing.is_empty() {
+            logger.basic().fmtln(text![
+                "test result: " G!"ok" ", "
+                {&tested} " of " {&total} " files tested in " {&time_fmt}
+            ]);
+        } else {
+            logger.basic().fmtln(text![
+                "test result: " R!{&failing.len().to_string()} R!" pomsky file(s) failed" ", "
+                {&tested} " of " {&total} " files tested in " {&time_fmt}
+            ]);
+        }
+        logger.note().println("waiting for changes, press Ctrl+C to stop");
+
+        let changes = watcher.wait();
+        for file in &changes.removed {
+            failing.remove(file);
+        }
+        to_test = changes.changed;
+    }
+}
+
 fn test_single(
     logger: &Logger,
     path: &Path,
@@ -168,69 +269,6 @@ fn test_single(
     }
 }
 
-fn is_dir_or_pomsky_file(entry: &ignore::DirEntry) -> bool {
-    let Some(ty) = entry.file_type() else { return false };
-    if ty.is_dir() {
-        return true;
-    }
-    let Some(ext) = entry.path().extension() else { return false };
-    ext == "pomsky"
-}
-
-fn handle_walk_error(error: ignore::Error, logger: &Logger, current_dir: &Path) {
-    match error {
-        ignore::Error::Partial(errors) => {
-            for error in errors {
-                handle_walk_error(error, logger, current_dir);
-            }
-        }
-        ignore::Error::WithLineNumber { line, err } => {
-            handle_walk_error(*err, logger, current_dir);
-            logger.basic().fmtln(text!["    at line " C!{&line.to_string()}]);
-        }
-        ignore::Error::WithPath { path, err } => {
-            handle_walk_error(*err, logger, current_dir);
-            logger.basic().fmtln(text!["    at path " C!{&path.display().to_string()}]);
-        }
-        ignore::Error::WithDepth { depth, err } => {
-            handle_walk_error(*err, logger, current_dir);
-            logger.basic().fmtln(text!["    at depth " C!{&depth.to_string()}]);
-        }
-        ignore::Error::Loop { ancestor, child } => {
-            let ancestor = ancestor.strip_prefix(current_dir).unwrap_or(&ancestor);
-            let child = child.canonicalize().unwrap_or(child);
-
-            logger.error().println("file system loop detected!");
-            logger.basic().fmtln(text!["    ancestor: " C!{&ancestor.display().to_string()}]);
-            logger.basic().fmtln(text!["    child: " C!{&child.display().to_string()}]);
-        }
-        ignore::Error::Io(error) => {
-            logger.error().println(error);
-        }
-        ignore::Error::Glob { glob, err } => {
-            logger.error().println(err);
-            if let Some(glob) = glob {
-                logger.basic().fmtln(text!["    glob: " C!{&glob}]);
-            }
-        }
-        ignore::Error::UnrecognizedFileType(file_type) => {
-            logger.error().println(format_args!("file type `{file_type}` not recognized"));
-        }
-        ignore::Error::InvalidDefinition => {
-            logger.error().println("file type definition could not be parsed");
-        }
-    }
-}
-
-pub(crate) fn print_json_test_result(json: bool, results: &[CompilationResult]) {
-    if json {
-        match serde_json::to_string(&results) {
-            Ok(string) => println!("{string}"),
-            Err(e) => eprintln!("{e}"),
-        }
-    }
-}
-
 fn show_relative(path: &Path, relative_to: &Path) -> String {
     path.strip_prefix(relative_to).unwrap_or(path).display().to_string()
 }
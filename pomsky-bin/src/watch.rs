@@ -0,0 +1,139 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use helptext::text;
+
+use crate::{
+    args::{CompileOptions, GlobalOptions},
+    format::Logger,
+};
+
+/// How often the file system is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Detects changes to files by polling their modification times and lengths.
+/// This is slower than file system notifications, but works the same on every
+/// OS.
+pub(crate) struct Watcher<F> {
+    /// Returns the files that should be watched. It is called on every poll,
+    /// so new files in a watched directory are noticed.
+    list_files: F,
+    stamps: BTreeMap<PathBuf, Stamp>,
+}
+
+/// The modification time and length of a file. The length is compared as
+/// well, because the modification time may have a coarse resolution, so a
+/// file written twice in quick succession can keep the same time.
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+/// Files that were added, modified or removed since the last poll
+#[cfg_attr(not(feature = "test"), allow(dead_code))] // only used by `pomsky test`
+pub(crate) struct Changes {
+    pub(crate) changed: Vec<PathBuf>,
+    pub(crate) removed: Vec<PathBuf>,
+}
+
+impl<F: FnMut() -> Vec<PathBuf>> Watcher<F> {
+    pub(crate) fn new(list_files: F) -> Self {
+        let mut watcher = Watcher { list_files, stamps: BTreeMap::new() };
+        watcher.stamps = watcher.scan();
+        watcher
+    }
+
+    /// The files that were found in the last poll
+    #[cfg_attr(not(feature = "test"), allow(dead_code))]
+    pub(crate) fn files(&self) -> impl Iterator<Item = &Path> {
+        self.stamps.keys().map(PathBuf::as_path)
+    }
+
+    /// Blocks until a file is added, modified or removed
+    pub(crate) fn wait(&mut self) -> Changes {
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let stamps = self.scan();
+            if stamps != self.stamps {
+                let changed = stamps
+                    .iter()
+                    .filter(|&(path, stamp)| self.stamps.get(path) != Some(stamp))
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                let removed = self
+                    .stamps
+                    .keys()
+                    .filter(|&path| !stamps.contains_key(path))
+                    .cloned()
+                    .collect();
+
+                self.stamps = stamps;
+                return Changes { changed, removed };
+            }
+        }
+    }
+
+    fn scan(&mut self) -> BTreeMap<PathBuf, Stamp> {
+        (self.list_files)()
+            .into_iter()
+            .map(|path| {
+                let stamp = std::fs::metadata(&path).ok().map(|m| (m.modified().ok(), m.len()));
+                (path, stamp)
+            })
+            .collect()
+    }
+}
+
+/// Compiles the file whenever it changes, until the process is killed
+pub(crate) fn watch_compile(
+    logger: &Logger,
+    path: &Path,
+    compile_args: &CompileOptions,
+    args: &GlobalOptions,
+) -> ! {
+    let mut watcher = Watcher::new(|| vec![path.to_owned()]);
+
+    loop {
+        logger.clear_screen();
+        let start = Instant::now();
+
+        match std::fs::read_to_string(path) {
+            Ok(mut input) => {
+                if compile_args.fix {
+                    input = crate::fix(logger, path, input, compile_args.unsafe_fixes, args);
+                }
+                let result = crate::compile(Some(path), &input, compile_args, args);
+                let success = crate::print_result(result, logger, compile_args, args, &input);
+
+                let time = format!("{:.2?}", start.elapsed());
+                let path = path.display().to_string();
+                if success {
+                    logger.basic().fmtln(text![G!"compiled " C!{&path} " in " {&time}]);
+                } else {
+                    logger.basic().fmtln(text![R!"failed to compile " C!{&path}]);
+                }
+            }
+            Err(error) => logger.error().println(format_args!("{}: {error}", path.display())),
+        }
+        logger.note().println("waiting for changes, press Ctrl+C to stop");
+
+        watcher.wait();
+    }
+}
+
+#[test]
+fn watcher_compares_length() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("a.pomsky");
+    std::fs::write(&path, "'a'").unwrap();
+
+    let mut watcher = Watcher::new(|| vec![path.clone()]);
+    let time = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+    // the file is changed, but keeps its modification time
+    std::fs::write(&path, "'abc'").unwrap();
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(time).unwrap();
+
+    assert_ne!(watcher.scan(), watcher.stamps);
+}
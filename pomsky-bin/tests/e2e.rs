@@ -2,13 +2,21 @@
 #![cfg(not(target_os = "windows"))]
 
 use assert_cmd::prelude::*;
-use assert_fs::prelude::{FileWriteBin, FileWriteStr};
+use assert_fs::prelude::{FileWriteBin, FileWriteStr, PathAssert, PathChild};
 use predicates::reflection::{Case, Parameter, PredicateReflection};
 
 use pomsky::diagnose::DiagnosticCode;
-use pomsky_bin::{CompilationResult, Diagnostic, Kind, Severity, Span, Timings, Version};
+use pomsky_bin::{
+    CompilationResult, Diagnostic, ExplainResult, FileMatches, Kind, LineMatch, Match, MatchResult,
+    NamedCapture, ParseResult, Position, QuickFix, Replacement, Severity, Span, TestCaseKind,
+    Timings, Version,
+};
 
-use std::{fmt, process::Command};
+use std::{
+    fmt,
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+};
 
 pub struct Output {
     ignore_visual: bool,
@@ -78,6 +86,24 @@ const USAGE_COLOR: &str = "\n\
     command | pomsky [OPTIONS]\n\
 For more information try \u{1b}[36m--help\u{1b}[0m\n";
 
+/// A span in a single line of ASCII text, where all units are the same
+fn ascii_span(start: usize, end: usize) -> Span {
+    let position = |offset| Position {
+        line: 0,
+        column: offset,
+        column_utf16: offset,
+        column_char: offset,
+        offset_utf16: offset,
+    };
+    Span {
+        start,
+        end,
+        label: None,
+        start_position: Some(position(start)),
+        end_position: Some(position(end)),
+    }
+}
+
 fn command(args: &[&str]) -> Command {
     let mut cmd = Command::cargo_bin("pomsky").unwrap();
     for arg in args {
@@ -116,20 +142,30 @@ Usage:
     command | pomsky [OPTIONS]
 
 Subcommands:
-    pomsky test  Run unit tests in pomsky expressions
+    pomsky test     Run unit tests in pomsky expressions
+    pomsky import   Convert a regex to a pomsky expression
+    pomsky fmt      Format pomsky expressions
+    pomsky parse    Print the syntax tree of a pomsky expression
+    pomsky explain  Explain a pomsky expression in plain English
+    pomsky match    Search files for lines matching a pomsky expression
+    pomsky replace  Replace matches of a pomsky expression in files
 
 Args:
     <INPUT>  Pomsky expression to compile
 
 Options:
         --allowed-features <FEATURE>...  Comma-separated list of allowed features [default: all enabled]
+        --deny-warnings                  Turn all warnings into errors
     -f, --flavor <FLAVOR>                Regex flavor [default: `pcre`]
+        --fix                            Fix errors and warnings automatically in the file given with `--path`
     -h, --help                           Print help information
         --list shorthands                Show all available character class shorthands
     -n, --no-new-line                    Don't print a new-line after the output
     -p, --path <FILE>                    File containing the pomsky expression to compile
+        --unsafe-fixes                   Also apply fixes that may change what the expression matches, with `--fix`
     -V, --version                        Print version information
     -W, --warnings <DIAGNOSTICS>         Disable certain warnings (disable all with `-W0`)
+        --watch                          Compile the file given with `--path` again whenever it changes
 "#, env!("CARGO_PKG_VERSION")));
 }
 
@@ -235,18 +271,79 @@ fn invalid_flavor() {
     let mut cmd = command(&[":foo('test')+", "-f", "jsx"]);
     cmd.assert().failure().stderr(format!(
         "error: `jsx` isn't a valid flavor
-possible values: pcre, python, java, javascript, dotnet, ruby, rust
+possible values: pcre, python, python-regex, java, javascript, dotnet, ruby, rust, re2, postgres
 {USAGE}"
     ));
 
     let mut cmd = command_color(&[":foo('test')+", "-f", "jsx"]);
     cmd.assert().failure().stderr(format!(
         "{ERROR_COLOR}`jsx` isn't a valid flavor
-possible values: pcre, python, java, javascript, dotnet, ruby, rust
+possible values: pcre, python, python-regex, java, javascript, dotnet, ruby, rust, re2, postgres
 {USAGE_COLOR}"
     ));
 }
 
+#[test]
+fn flavor_with_version() {
+    let mut cmd = command(&["atomic('test')", "-f", "python@3.11"]);
+    cmd.assert().success().stdout("(?>test)\n").stderr("");
+
+    let mut cmd = command(&["atomic('test')", "-f", "python@3.11.2"]);
+    cmd.assert().success().stdout("(?>test)\n").stderr("");
+
+    let mut cmd = command(&["atomic('test')", "-f", "python@3.x"]);
+    cmd.assert().failure().stderr(format!(
+        "error: `3.x` isn't a valid flavor version
+try a version like `--flavor=python@3.11`
+{USAGE}"
+    ));
+}
+
+#[test]
+fn multiple_flavors() {
+    let mut cmd = command(&["'test'+ ['0'-'9']", "-f", "rust,js@2018,java"]);
+    cmd.assert().success().stdout("(?:test)+[0-9]\n").stderr("");
+
+    let mut cmd = command(&[":name('a')", "-f", "rust,js,java"]);
+    cmd.assert().success().stdout("(?<name>a)\n").stderr("");
+
+    let mut cmd = command(&["'test' [word]", "-f", "rust,js", "--json"]);
+    cmd.assert()
+        .failure()
+        .stdout(Output::new(CompilationResult {
+            path: None,
+            version: Version::V1,
+            success: false,
+            output: None,
+            diagnostics: vec![Diagnostic {
+                severity: Severity::Error,
+                kind: Kind::Compat,
+                code: Some(DiagnosticCode::IncompatibleFlavors),
+                spans: vec![ascii_span(7, 13)],
+                description: "This expression compiles to different regexes in the `Rust` and \
+                    `JavaScript` flavors, so no single regex works in both"
+                    .into(),
+                help: vec![
+                    "The `Rust` flavor produces `\\w`,\nbut the `JavaScript` flavor \
+                    produces `[\\p{Alphabetic}\\p{M}\\p{Nd}\\p{Pc}]`"
+                        .into(),
+                ],
+                fixes: vec![],
+                visual: String::new(),
+            }],
+            tests: vec![],
+            timings: Timings { all: 0, tests: 0 },
+        }))
+        .stderr("");
+
+    let mut cmd = command(&["'test'", "-f", "rust,perl"]);
+    cmd.assert().failure().stderr(format!(
+        "error: `perl` isn't a valid flavor
+possible values: pcre, python, python-regex, java, javascript, dotnet, ruby, rust, re2, postgres
+{USAGE}"
+    ));
+}
+
 #[test]
 fn flavor_used_multiple_times() {
     let mut cmd = command(&[":foo('test')+", "-fjs", "-f", "rust"]);
@@ -256,6 +353,226 @@ fn flavor_used_multiple_times() {
     ));
 }
 
+#[test]
+fn import() {
+    let mut cmd = command(&["import", r"(?<year>\d{4})-(?<month>\d{2})"]);
+    cmd.assert().success().stdout(":year([digit]{4}) '-' :month([digit]{2})\n").stderr("");
+
+    let mut cmd = command(&["import", "-f", "js", r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}|\w+"]);
+    cmd.assert()
+        .success()
+        .stdout(
+            "let part1 = [ascii_digit]{1,3};\n\npart1 '.' part1 '.' part1 '.' part1 | [ascii_word]+\n",
+        )
+        .stderr("");
+
+    let mut cmd = command(&["import", "a(?i)b", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(Output::new(CompilationResult {
+            path: None,
+            version: Version::V1,
+            success: true,
+            output: Some("regex 'a(?i)b'".into()),
+            diagnostics: vec![Diagnostic {
+                severity: Severity::Warning,
+                kind: Kind::Unsupported,
+                code: Some(DiagnosticCode::ImportUnsupported),
+                spans: vec![ascii_span(0, 6)],
+                description: "An inline modifier can't be expressed in pomsky, so it was \
+                    embedded as a `regex` literal"
+                    .into(),
+                help: vec![
+                    "Pomsky doesn't validate embedded regexes, so check that it still works".into(),
+                ],
+                fixes: vec![],
+                visual: String::new(),
+            }],
+            tests: vec![],
+            timings: Timings { all: 0, tests: 0 },
+        }))
+        .stderr("");
+
+    let mut cmd = command(&["import", "a(b"]);
+    cmd.assert().failure().stderr(
+        "error P0600(syntax):  × This group is never closed
+   ╭────
+ 1 │ a(b
+   ·  ┬
+   ·  ╰── error occurred here
+   ╰────
+",
+    );
+}
+
+#[test]
+fn fmt() {
+    let mut cmd = command(&["fmt", "let  x='a'|'b' ;# comment\n\n\n:( x ) +"]);
+    cmd.assert().success().stdout("let x = 'a' | 'b'; # comment\n\n:(x)+\n").stderr("");
+
+    let mut cmd = command(&["fmt", "--width", "20", "--indent", "4", "'alpha' | 'beta' | 'gamma'"]);
+    cmd.assert().success().stdout("| 'alpha'\n| 'beta'\n| 'gamma'\n").stderr("");
+
+    let mut cmd = command(&["fmt", "--check", "'a' 'b'"]);
+    cmd.assert().success().stdout("").stderr("");
+
+    let mut cmd = command(&["fmt", "--check", "'a'  'b'"]);
+    cmd.assert().failure().stdout("").stderr(format!("{ERROR}The input is not formatted\n"));
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("sub/a.pomsky");
+    file.write_str("[ 'a' - 'z' ]+").unwrap();
+    dir.child("b.pomsky").write_str("'b'\n").unwrap();
+    let dir_path = dir.path().to_str().unwrap();
+
+    let mut cmd = command(&["fmt", "--check", "-p", dir_path]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(format!("{ERROR}`{}` is not formatted\n", file.path().display()));
+
+    let mut cmd = command(&["fmt", "-p", dir_path]);
+    cmd.assert().success().stdout("").stderr("");
+    assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "['a'-'z']+\n");
+
+    let mut cmd = command(&["fmt", "--check", "-p", dir_path]);
+    cmd.assert().success().stdout("").stderr("");
+
+    // ignored files aren't formatted
+    dir.child("ignored/c.pomsky").write_str("'c'  'c'").unwrap();
+    dir.child(".ignore").write_str("ignored/\n").unwrap();
+    let mut cmd = command(&["fmt", "--check", "-p", dir_path]);
+    cmd.assert().success().stdout("").stderr("");
+
+    let mut cmd = command(&["fmt", "'a' |"]);
+    cmd.assert().failure().stdout("").stderr(
+        "error P0100(syntax):  × A pipe must be followed by an expression
+   ╭────
+ 1 │ 'a' |
+   ·     ┬
+   ·     ╰── error occurred here
+   ╰────
+  help: Add an empty string ('') to match nothing
+",
+    );
+}
+
+#[test]
+fn fmt_comments() {
+    let mut cmd = command(&["fmt", "[ # c\n 'a' # d\n ]"]);
+    cmd.assert().success().stdout("[ # c\n  'a' # d\n]\n").stderr("");
+
+    let mut cmd = command(&["fmt", "['a'\n# c\n'b'-'z' word]"]);
+    cmd.assert().success().stdout("[\n  'a'\n  # c\n  'b'-'z' word\n]\n").stderr("");
+
+    let mut cmd = command(&["fmt", "( # c\n 'a' # d\n )"]);
+    cmd.assert().success().stdout("(\n  # c\n  'a' # d\n)\n").stderr("");
+
+    let mut cmd = command(&["fmt", ":name( 'a'\n# c\n'b' )"]);
+    cmd.assert().success().stdout(":name(\n  'a'\n  # c\n  'b'\n)\n").stderr("");
+
+    let mut cmd = command(&["fmt", "'a' # c\n+"]);
+    cmd.assert().success().stdout("'a'+ # c\n").stderr("");
+
+    let mut cmd = command(&["fmt", "'a'\n# c\n{2} 'b'"]);
+    cmd.assert().success().stdout("'a'{2}\n# c\n'b'\n").stderr("");
+
+    let mut cmd = command(&["fmt", ">> # c\n'a'"]);
+    cmd.assert().success().stdout(">>\n  # c\n  'a'\n").stderr("");
+}
+
+#[test]
+fn parse() {
+    let mut cmd = command(&["parse", "let x = 'a'; :(x)+"]);
+    cmd.assert().success().stdout("let x = \"a\";\n:(\n  x\n){1,}\n").stderr("");
+
+    let mut cmd = command(&["parse", "--json", "'a' | [w]"]);
+    let output = cmd.assert().success().stderr("").get_output().stdout.clone();
+    let result: ParseResult = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        result,
+        ParseResult {
+            version: Version::V1,
+            success: true,
+            path: None,
+            ast: Some(serde_json::json!({
+                "Alternation": {
+                    "rules": [
+                        { "Literal": { "content": "a", "span": { "start": 0, "end": 3 } } },
+                        { "CharClass": {
+                            "inner": [{ "Named": {
+                                "name": "Word",
+                                "negative": false,
+                                "span": { "start": 7, "end": 8 }
+                            } }],
+                            "span": { "start": 6, "end": 9 },
+                            "unicode_aware": true
+                        } }
+                    ],
+                    "span": { "start": 0, "end": 9 }
+                }
+            })),
+            diagnostics: vec![],
+        }
+    );
+
+    let mut cmd = command(&["parse", "--json", "'a' |"]);
+    let output = cmd.assert().failure().stderr("").get_output().stdout.clone();
+    let result: ParseResult = serde_json::from_slice(&output).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.ast, None);
+    assert_eq!(result.diagnostics[0].code, Some(DiagnosticCode::UnexpectedToken));
+}
+
+#[test]
+fn explain() {
+    let mut cmd = command(&["explain", "let x = [word]+; :name(x) '!'"]);
+    cmd.assert().success().stderr("").stdout(
+        "a sequence of:
+- a capturing group named `name`, containing:
+  - a word character, repeated one or more times (from variable `x`)
+- the character '!'
+",
+    );
+
+    let mut cmd = command(&["explain", "-f", "rust", "(>> 'a') 'b'"]);
+    cmd.assert().success().stderr("").stdout(
+        "a sequence of:
+- a position followed by:
+  note: Unsupported feature `lookahead/behind` in the `Rust` regex flavor
+  - the character 'a'
+- the character 'b'
+",
+    );
+
+    let mut cmd = command(&["explain", "--json", "'a'+"]);
+    let output = cmd.assert().success().stderr("").get_output().stdout.clone();
+    let result: ExplainResult = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        result,
+        ExplainResult {
+            version: Version::V1,
+            success: true,
+            path: None,
+            explanation: Some(serde_json::json!({
+                "description": "the character 'a', repeated one or more times",
+                "span": { "start": 0, "end": 4 },
+                "variable": null,
+                "notes": [],
+                "children": []
+            })),
+            diagnostics: vec![],
+        }
+    );
+
+    let mut cmd = command(&["explain", "--json", "'a' |"]);
+    let output = cmd.assert().failure().stderr("").get_output().stdout.clone();
+    let result: ExplainResult = serde_json::from_slice(&output).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.explanation, None);
+    assert_eq!(result.diagnostics[0].code, Some(DiagnosticCode::UnexpectedToken));
+}
+
 #[test]
 fn input_and_path() {
     let mut cmd = command(&[":foo('test')+", "-p", "foo"]);
@@ -319,6 +636,30 @@ fn wrong_order() {
         .stderr(format!("{ERROR}You can only provide an input or a path, but not both\n{USAGE}"));
 }
 
+#[test]
+fn disable_and_deny_warnings() {
+    let mut cmd = command(&["[a-f]", "-W0"]);
+    cmd.assert().success().stdout("[\\x07-\\f]\n").stderr("");
+
+    let mut cmd = command(&["[a-f]", "-WP0105=0"]);
+    cmd.assert().success().stdout("[\\x07-\\f]\n").stderr("");
+
+    let mut cmd = command(&["[a-f]", "--deny-warnings", "-Wdeprecated=0"]);
+    cmd.assert().success().stdout("[\\x07-\\f]\n").stderr("");
+
+    let mut cmd = command(&["allow deprecated; [a-f]", "--deny-warnings"]);
+    cmd.assert().success().stdout("[\\x07-\\f]\n").stderr("");
+
+    let mut cmd = command(&["[a-f]", "--deny-warnings", "--json"]);
+    cmd.assert().failure().stdout(predicates::str::contains(r#""success":false"#)).stderr("");
+
+    let mut cmd = command(&["[a-f]", "-WP9999=0"]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(format!("{ERROR}`P9999` is not a recognized diagnostic kind or code\n{USAGE}"));
+}
+
 #[test]
 fn specify_features() {
     let mut cmd = command(&[
@@ -395,6 +736,192 @@ error P0503(test):  × The regex found a different match in the test string
     );
 }
 
+#[test]
+fn test_reporters() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("a.pomsky")
+        .write_str(
+            "test {\n  match 'ab' as { 1: 'b' };\n  reject in 'xyz';\n  match 'ac';\n}\n'a' :('b')",
+        )
+        .unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--reporter", "tap", "-p", path]);
+    cmd.current_dir(dir.path());
+    cmd.assert().failure().code(1).stderr("").stdout(
+        r#"TAP version 14
+1..3
+ok 1 - a.pomsky:2: match "ab"
+ok 2 - a.pomsky:3: reject in "xyz"
+not ok 3 - a.pomsky:4: match "ac"
+  ---
+  message: "The regex does not exactly match the test string"
+  failures:
+    - message: "The regex does not exactly match the test string"
+      at:
+        file: "a.pomsky"
+        line: 4
+        column: 9
+      expected: "ac"
+      actual: null
+  ...
+"#,
+    );
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--reporter", "junit", "-p", path]);
+    cmd.current_dir(dir.path());
+    let output = cmd.assert().failure().code(1).stderr("").get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+    let expected = [
+        r#"<testsuites name="pomsky" tests="3" failures="1" errors="0">"#,
+        r#"<testcase name="match &quot;ab&quot;" classname="a.pomsky" file="a.pomsky" line="2"/>"#,
+        r#"<testcase name="match &quot;ac&quot;" classname="a.pomsky" file="a.pomsky" line="4">"#,
+        r#"<failure message="The regex does not exactly match the test string" type="test">"#,
+        "  expected: &quot;ac&quot;\n  actual: no match\n</failure>",
+    ];
+    for line in expected {
+        assert!(output.contains(line), "{line:?} not found in:\n{output}");
+    }
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--reporter", "json", "-p", path]);
+    let output = cmd.assert().failure().code(1).stderr("").get_output().stdout.clone();
+    let results: Vec<CompilationResult> = serde_json::from_slice(&output).unwrap();
+    let tests = &results[0].tests;
+    assert_eq!(tests.len(), 3);
+    assert_eq!(tests[0].kind, TestCaseKind::Match);
+    assert_eq!(tests[1].kind, TestCaseKind::RejectIn);
+    assert!(tests[0].success && tests[1].success && !tests[2].success);
+    assert_eq!(tests[2].failures[0].expected.as_deref(), Some("ac"));
+    assert_eq!(tests[2].failures[0].actual, None);
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--reporter", "pretty", "-p", path]);
+    cmd.assert().failure().code(1);
+
+    let file = dir.child("a.pomsky");
+    let file = file.path().to_str().unwrap();
+    let mut cmd = command(&["test", "-e", "pcre2", "--reporter", "tap", "-p", file]);
+    cmd.assert().failure().code(1);
+
+    dir.child("a.pomsky").write_str("test {\n  match 'ab';\n}\n'a' :('b')").unwrap();
+    for reporter in ["pretty", "json", "junit", "tap", "sarif"] {
+        let mut cmd = command(&["test", "-e", "pcre2", "--reporter", reporter, "-p", path]);
+        cmd.assert().success();
+    }
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--json", "--reporter", "tap", "-p", path]);
+    cmd.assert().failure().stdout("").stderr(format!(
+        "{ERROR}`--json` can't be combined with another `--reporter` than `json`\n{USAGE}"
+    ));
+}
+
+#[test]
+fn match_lines() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("dates.txt");
+    file.write_str("released 2024-06-01\nnothing here\nfrom 2023-12-24 to 2025-01-31\n").unwrap();
+    dir.child("sub/other.txt").write_str("2022-02-02\n").unwrap();
+    let path = file.path().to_str().unwrap();
+    let date = ":year([digit]{4}) '-' :month([digit]{2}) '-' [digit]{2}";
+
+    let mut cmd = command(&["match", date, path]);
+    cmd.assert().success().stderr("").stdout(
+        r#"1:released 2024-06-01
+    year="2024" month="06"
+3:from 2023-12-24 to 2025-01-31
+    year="2023" month="12"
+    year="2025" month="01"
+"#,
+    );
+
+    let mut cmd = command(&["match", "-o", "-e", "rust", "[digit]{4}", path]);
+    cmd.assert().success().stderr("").stdout("1:2024\n3:2023\n3:2025\n");
+
+    let mut cmd = command(&["match", "--count", "'2025'", dir.path().to_str().unwrap()]);
+    let stdout = String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap();
+    let mut counts = stdout.lines().collect::<Vec<_>>();
+    counts.sort();
+    assert_eq!(
+        counts,
+        [format!("{path}:1"), format!("{}:0", dir.child("sub/other.txt").path().display())]
+    );
+
+    let mut cmd = command(&["match", "'2021'", path]);
+    cmd.assert().failure().code(1).stderr("").stdout("");
+
+    let mut cmd =
+        assert_cmd::Command::from_std(command(&["match", ":m([digit]{2}) '-01'", "--json"]));
+    cmd.write_stdin("2024-06-01\n");
+    let stdout = cmd.assert().success().stderr("").get_output().stdout.clone();
+    let result = serde_json::from_slice::<MatchResult>(&stdout).unwrap();
+    assert_eq!(
+        result,
+        MatchResult {
+            version: Version::V1,
+            success: true,
+            path: None,
+            regex: r"(?P<m>\d{2})-01".into(),
+            diagnostics: vec![],
+            files: vec![FileMatches {
+                path: None,
+                count: 1,
+                lines: vec![LineMatch {
+                    line: 1,
+                    text: "2024-06-01".into(),
+                    matches: vec![Match {
+                        start: 5,
+                        end: 10,
+                        text: "06-01".into(),
+                        captures: vec![NamedCapture {
+                            name: "m".into(),
+                            start: 5,
+                            end: 7,
+                            text: "06".into(),
+                        }],
+                    }],
+                }],
+            }],
+        }
+    );
+}
+
+#[test]
+fn replace_matches() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("dates.txt");
+    file.write_str("released 2024-06-01\nnothing here\n").unwrap();
+    let path = file.path().to_str().unwrap();
+    let date = ":year([digit]{4}) '-' :month([digit]{2}) '-' :day([digit]{2})";
+
+    let mut cmd =
+        command(&["replace", date, "--with", "${day}.${month}.${year}", "--dry-run", path]);
+    cmd.assert().success().stderr("").stdout(format!(
+        "--- {path}\n+++ {path}\n@@ -1,2 +1,2 @@\n-released 2024-06-01\n+released 01.06.2024\n nothing here\n"
+    ));
+    file.assert("released 2024-06-01\nnothing here\n");
+
+    let mut cmd = command(&["replace", date, "-w", "${day}.${month}.${year}", path]);
+    cmd.assert().success().stdout("").stderr("replaced 1 match in 1 file\n");
+    file.assert("released 01.06.2024\nnothing here\n");
+
+    let mut cmd = command(&["replace", date, "-w", "${day}", path]);
+    cmd.assert().failure().code(1).stderr("").stdout("");
+
+    let mut cmd = assert_cmd::Command::from_std(command(&["replace", "'b'", "-w", "[${0}$$]"]));
+    cmd.write_stdin("abc\n");
+    cmd.assert().success().stderr("").stdout("a[b$]c\n");
+
+    let mut cmd = command(&["replace", date, "-w", "${yr}", path]);
+    cmd.assert().failure().code(1).stdout("").stderr(
+        "error: The replacement refers to group `yr`, which doesn't exist in the expression\n\
+        possible values: year, month, day\n",
+    );
+
+    let mut cmd = command(&["replace", date, "-w", "${4}", path]);
+    cmd.assert().failure().code(1).stdout("").stderr(
+        "error: The replacement refers to group 4, but the expression only has 3 capturing groups\n",
+    );
+}
+
 #[test]
 fn json_output() {
     let mut cmd = command(&["..[word]", "--json"]);
@@ -406,6 +933,7 @@ fn json_output() {
             success: true,
             output: Some("..\\w".into()),
             diagnostics: vec![],
+            tests: vec![],
             timings: Timings { all: 0, tests: 0 },
         }))
         .stderr("");
@@ -422,29 +950,293 @@ fn json_output_errors() {
                 version: Version::V1,
                 success: false,
                 output: None,
-                diagnostics: vec![Diagnostic {
-                    severity: Severity::Error,
-                    kind: Kind::Syntax,
-                    code: Some(DiagnosticCode::UnexpectedToken),
-                    spans: vec![Span { start: 1, end: 2, label: None }],
-                    description:
-                        "Expected character class, string, code point, Unicode property or `]`"
-                            .into(),
-                    help: vec![],
-                    fixes: vec![],
-                    visual: String::from(
-                        "error P0100(syntax):  × Expected character class, string, code point, Unicode property or `]`
+                diagnostics: vec![
+                    Diagnostic {
+                        severity: Severity::Error,
+                        kind: Kind::Syntax,
+                        code: Some(DiagnosticCode::UnexpectedToken),
+                        spans: vec![ascii_span(1, 2)],
+                        description:
+                            "Expected character class, string, code point, Unicode property or `]`"
+                                .into(),
+                        help: vec![],
+                        fixes: vec![],
+                        visual: String::from(
+                            "error P0100(syntax):  × Expected character class, string, code point, Unicode property or `]`
    ╭────
  1 │ [.][^test]
    ·  ┬
    ·  ╰── error occurred here
    ╰────
 ",
-                    ),
-                }],
+                        ),
+                    },
+                    Diagnostic {
+                        severity: Severity::Error,
+                        kind: Kind::Syntax,
+                        code: Some(DiagnosticCode::UnexpectedToken),
+                        spans: vec![ascii_span(4, 5)],
+                        description: "`^` is not allowed here".into(),
+                        help: vec!["Use `![...]` to negate a character class".into()],
+                        fixes: vec![],
+                        visual: String::from(
+                            "error P0100(syntax):  × `^` is not allowed here
+   ╭────
+ 1 │ [.][^test]
+   ·     ┬
+   ·     ╰── error occurred here
+   ╰────
+  help: Use `![...]` to negate a character class
+",
+                        ),
+                    },
+                ],
+                tests: vec![],
                 timings: Timings { all: 0, tests: 0 },
             })
             .ignore_visual(false),
         )
         .stderr("");
 }
+
+#[test]
+fn json_output_positions() {
+    let mut cmd = command(&["'x'\n'ä😀' [.]", "--json"]);
+    cmd.assert()
+        .failure()
+        .stdout(Output::new(CompilationResult {
+            path: None,
+            version: Version::V1,
+            success: false,
+            output: None,
+            diagnostics: vec![Diagnostic {
+                severity: Severity::Error,
+                kind: Kind::Syntax,
+                code: Some(DiagnosticCode::UnexpectedToken),
+                spans: vec![Span {
+                    start: 14,
+                    end: 15,
+                    label: None,
+                    start_position: Some(Position {
+                        line: 1,
+                        column: 10,
+                        column_utf16: 7,
+                        column_char: 6,
+                        offset_utf16: 11,
+                    }),
+                    end_position: Some(Position {
+                        line: 1,
+                        column: 11,
+                        column_utf16: 8,
+                        column_char: 7,
+                        offset_utf16: 12,
+                    }),
+                }],
+                description:
+                    "Expected character class, string, code point, Unicode property or `]`".into(),
+                help: vec![],
+                fixes: vec![],
+                visual: String::new(),
+            }],
+            tests: vec![],
+            timings: Timings { all: 0, tests: 0 },
+        }))
+        .stderr("");
+}
+
+#[test]
+fn json_output_fixes() {
+    let mut cmd = command(&["'a'{2}{3}", "--json"]);
+    cmd.assert()
+        .failure()
+        .stdout(Output::new(CompilationResult {
+            path: None,
+            version: Version::V1,
+            success: false,
+            output: None,
+            diagnostics: vec![Diagnostic {
+                severity: Severity::Error,
+                kind: Kind::Syntax,
+                code: Some(DiagnosticCode::RepetitionChain),
+                spans: vec![ascii_span(6, 9)],
+                description: "Only one repetition allowed".into(),
+                help: vec!["Add parentheses around the first repetition.".into()],
+                fixes: vec![QuickFix {
+                    description: "Add parentheses around the first repetition".into(),
+                    replacements: vec![
+                        Replacement { start: 0, end: 0, insert: "(".into() },
+                        Replacement { start: 6, end: 6, insert: ")".into() },
+                    ],
+                    preserves_meaning: true,
+                }],
+                visual: String::new(),
+            }],
+            tests: vec![],
+            timings: Timings { all: 0, tests: 0 },
+        }))
+        .stderr("");
+}
+
+#[test]
+fn sarif_output() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("a.pomsky").write_str("let x = 'x';\n'ä' U147A").unwrap();
+    dir.child("tests/b.pomsky").write_str("test { match 'c'; }\n'b'").unwrap();
+
+    let mut cmd = command(&["--format", "sarif", "-p", "a.pomsky"]);
+    cmd.current_dir(dir.path());
+    let output = cmd.assert().failure().stderr("").get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+
+    let run = &log["runs"][0];
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        serde_json::json!([
+            { "id": "P0310", "name": "UnknownVariable" }
+        ])
+    );
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "P0310");
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        result["message"]["text"],
+        "Variable `U147A` doesn't exist\nhelp: Perhaps you meant a code point: `U+147A`"
+    );
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "a.pomsky");
+    assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+    assert_eq!(
+        location["region"],
+        serde_json::json!({
+            "startLine": 2, "startColumn": 5, "endLine": 2, "endColumn": 10,
+            "byteOffset": 18, "byteLength": 5,
+        })
+    );
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "Replace with `U+147A`");
+    assert_eq!(
+        fix["artifactChanges"][0]["replacements"],
+        serde_json::json!([{
+            "deletedRegion": { "byteOffset": 18, "byteLength": 5 },
+            "insertedContent": { "text": "U+147A" },
+        }])
+    );
+
+    let mut cmd = command(&["test", "-e", "pcre2", "--format", "sarif", "-p", "tests"]);
+    cmd.current_dir(dir.path());
+    let output = cmd.assert().failure().code(1).stderr("").get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "P0500");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "tests/b.pomsky");
+    assert_eq!(location["region"]["startColumn"], 14);
+
+    let mut cmd = command(&["parse", "--format", "sarif", "'a'"]);
+    cmd.assert().failure().stdout("").stderr(format!(
+        "{ERROR}`--format sarif` is only supported when compiling or testing pomsky expressions\n\
+        {USAGE}"
+    ));
+}
+
+#[test]
+fn fix() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("a.pomsky");
+    file.write_str("let number = [d]+;\nnumbr 'a'+* U147A").unwrap();
+    let path = file.path().to_str().unwrap();
+
+    // the typo is only fixed with `--unsafe-fixes`
+    let mut cmd = command(&["--fix", "-p", path]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(predicates::str::contains("Variable `numbr` doesn't exist"));
+    assert_eq!(
+        std::fs::read_to_string(file.path()).unwrap(),
+        "let number = [d]+;\nnumbr ('a'+)* U147A"
+    );
+
+    let mut cmd = command(&["--fix", "--unsafe-fixes", "-p", path]);
+    cmd.assert().success().stdout("\\d+a*ᑺ\n").stderr(format!("note: applied fixes to `{path}`\n"));
+    assert_eq!(
+        std::fs::read_to_string(file.path()).unwrap(),
+        "let number = [d]+;\nnumber ('a'+)* U+147A"
+    );
+
+    // nothing left to fix
+    let mut cmd = command(&["--fix", "-p", path]);
+    cmd.assert().success().stdout("\\d+a*ᑺ\n").stderr("");
+
+    let mut cmd = command(&["--fix", "'a'+*"]);
+    cmd.assert().failure().stdout("").stderr(format!(
+        "{ERROR}`--fix` can only be used with a file, e.g. `--fix --path file.pomsky`\n{USAGE}"
+    ));
+
+    let mut cmd = command(&["--unsafe-fixes", "-p", path]);
+    cmd.assert()
+        .failure()
+        .stdout("")
+        .stderr(format!("{ERROR}`--unsafe-fixes` can only be used together with `--fix`\n{USAGE}"));
+
+    // making the repetition lazy would change what it matches
+    file.write_str("'a'+?").unwrap();
+    let mut cmd = command(&["--fix", "-p", path]);
+    cmd.assert().failure().stdout("");
+    assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "'a'+?");
+}
+
+#[test]
+fn watch() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("a.pomsky");
+    file.write_str("'a'+").unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let mut child = command(&["--watch", "-p", path])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "a+\n");
+
+    file.write_str("'b'").unwrap();
+    line.clear();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "b\n");
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut child =
+        command(&["test", "--watch", "-e", "pcre2", "-p", dir.path().to_str().unwrap()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut next_summary = || {
+        let mut line = String::new();
+        while !line.starts_with("test result") {
+            line.clear();
+            assert_ne!(stderr.read_line(&mut line).unwrap(), 0);
+        }
+        line
+    };
+    assert!(next_summary().starts_with("test result: ok, 1 of 1 files tested in "));
+
+    dir.child("b.pomsky").write_str("test { match 'x'; }\n'b'").unwrap();
+    assert!(
+        next_summary().starts_with("test result: 1 pomsky file(s) failed, 1 of 2 files tested in ")
+    );
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut cmd = command(&["--watch", "'a'"]);
+    cmd.assert().failure().stdout("").stderr(format!(
+        "{ERROR}`--watch` can only be used with a file, e.g. `--watch --path file.pomsky`\n{USAGE}"
+    ));
+}
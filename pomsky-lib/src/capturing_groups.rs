@@ -1,17 +1,18 @@
-use std::collections::HashMap;
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+};
 
 use pomsky_syntax::exprs::{self, Capture, GroupKind};
+use pomsky_syntax::visitor::{NestingKind, RuleVisitor};
 
-use crate::{
-    diagnose::{CompileError, CompileErrorKind},
-    visitor::RuleVisitor,
-};
+use crate::diagnose::{CompileError, CompileErrorKind};
 
 #[derive(Default)]
 pub(crate) struct CapturingGroupsCollector {
     pub(crate) count_named: u32,
     pub(crate) count_numbered: u32,
-    pub(crate) names: HashMap<String, CapturingGroupIndex>,
+    pub(crate) names: BTreeMap<String, CapturingGroupIndex>,
     pub(crate) variable_nesting: u32,
 }
 
@@ -28,14 +29,14 @@ impl CapturingGroupsCollector {
 }
 
 impl RuleVisitor<CompileError> for CapturingGroupsCollector {
-    fn down(&mut self, kind: crate::visitor::NestingKind) {
-        if let crate::visitor::NestingKind::Let = kind {
+    fn down(&mut self, kind: NestingKind) {
+        if let NestingKind::Let = kind {
             self.variable_nesting += 1;
         }
     }
 
-    fn up(&mut self, kind: crate::visitor::NestingKind) {
-        if let crate::visitor::NestingKind::Let = kind {
+    fn up(&mut self, kind: NestingKind) {
+        if let NestingKind::Let = kind {
             self.variable_nesting -= 1;
         }
     }
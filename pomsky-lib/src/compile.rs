@@ -1,10 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec,
+    vec::Vec,
+};
 
-use pomsky_syntax::exprs::Rule;
+use pomsky_syntax::{Span, exprs::Rule};
 
 use crate::{
     capturing_groups::{CapturingGroupIndex, CapturingGroupsCollector},
-    diagnose::{CompileError, Diagnostic},
+    diagnose::{CompileError, CompileErrorKind, Diagnostic},
+    options::CompileOptions,
     regex::Regex,
 };
 
@@ -14,13 +20,18 @@ pub(crate) type CompileResult = Result<Regex, CompileError>;
 pub(crate) struct CompileState<'i> {
     pub(crate) next_idx: u32,
     pub(crate) used_names_vec: Vec<Option<String>>,
-    pub(crate) used_names: HashMap<String, CapturingGroupIndex>,
+    pub(crate) used_names: BTreeMap<String, CapturingGroupIndex>,
     pub(crate) groups_count: u32,
     pub(crate) numbered_groups_count: u32,
     pub(crate) in_lookbehind: bool,
 
     pub(crate) variables: Vec<(&'i str, &'i Rule)>,
-    pub(crate) current_vars: HashSet<usize>,
+    pub(crate) current_vars: BTreeSet<usize>,
+    /// Number of rules compiled so far, to enforce `Limits::max_compiled_nodes`
+    pub(crate) compiled_nodes: u32,
+    /// Number of bytes in the literals compiled so far, to enforce
+    /// `Limits::max_output_len` before the output is generated
+    pub(crate) output_len: usize,
 
     pub(crate) diagnostics: Vec<Diagnostic>,
 }
@@ -50,11 +61,31 @@ impl<'i> CompileState<'i> {
 
             variables,
             current_vars: Default::default(),
+            compiled_nodes: 0,
+            output_len: 0,
 
             diagnostics: vec![],
         }
     }
 
+    /// Adds the length of a compiled literal to the output length. Since
+    /// variables can be expanded exponentially often, this must be checked
+    /// while compiling, so the compiler doesn't run out of memory.
+    pub(crate) fn add_output_len(
+        &mut self,
+        len: usize,
+        options: CompileOptions,
+        span: Span,
+    ) -> Result<(), CompileError> {
+        self.output_len = self.output_len.saturating_add(len);
+        match options.limits.max_output_len {
+            Some(max) if self.output_len > max as usize => {
+                Err(CompileErrorKind::OutputIsTooBig(max).at(span))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub(crate) fn has_named_groups(&self) -> bool {
         self.numbered_groups_count < self.groups_count
     }
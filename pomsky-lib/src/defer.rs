@@ -1,4 +1,4 @@
-use std::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut};
 
 pub(crate) struct Deferred<'a, S, F: FnMut(&mut S)> {
     state: &'a mut S,
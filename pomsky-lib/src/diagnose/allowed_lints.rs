@@ -0,0 +1,50 @@
+use alloc::vec::Vec;
+
+use pomsky_syntax::{
+    Span,
+    exprs::{Rule, Stmt},
+    visitor::RuleVisitor,
+};
+
+use crate::options::Lint;
+
+use super::{Diagnostic, Severity};
+
+/// The lints allowed with `allow` statements, each with the span of the
+/// statement and the expression it applies to
+pub(crate) struct AllowedLints(Vec<(Lint, Span)>);
+
+impl AllowedLints {
+    pub(crate) fn collect(rule: &Rule) -> Self {
+        let mut lints = AllowedLints(Vec::new());
+        let _ = lints.visit_rule(rule);
+        lints
+    }
+
+    /// Returns whether the diagnostic is a warning that was silenced with an
+    /// `allow` statement
+    pub(crate) fn is_allowed(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(range) = diagnostic.span.range() else { return false };
+        diagnostic.severity == Severity::Warning
+            && self.0.iter().any(|&(lint, span)| {
+                let matches = match lint {
+                    Lint::Kind(kind) => kind == diagnostic.kind,
+                    Lint::Code(code) => Some(code) == diagnostic.code,
+                };
+                matches
+                    && span.range().is_some_and(|r| r.start <= range.start && range.end <= r.end)
+            })
+    }
+}
+
+impl RuleVisitor<()> for AllowedLints {
+    fn enter_rule(&mut self, rule: &Rule) -> Result<(), ()> {
+        if let Rule::StmtExpr(s) = rule
+            && let Stmt::Allow(allow) = &s.stmt
+            && let Ok(lint) = allow.lint.parse()
+        {
+            self.0.push((lint, s.span));
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
+use alloc::{boxed::Box, string::String};
+
 use pomsky_syntax::{
     Span,
     diagnose::{ParseError, ParseErrorKind},
 };
 
-use crate::{exprs::char_class::RegexCharSetItem, options::RegexFlavor};
+use crate::{
+    exprs::char_class::RegexCharSetItem,
+    options::{FlavorVersion, RegexFlavor},
+};
 
 use super::{Diagnostic, Feature};
 
@@ -28,11 +33,11 @@ impl CompileError {
     }
 }
 
-impl std::error::Error for CompileError {}
+impl core::error::Error for CompileError {}
 
 impl core::fmt::Display for CompileError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        if let Some(std::ops::Range { start, end }) = self.span.range() {
+        if let Some(core::ops::Range { start, end }) = self.span.range() {
             write!(f, "{}\n  at {start}..{end}", self.kind)
         } else {
             self.kind.fmt(f)
@@ -52,6 +57,12 @@ impl From<ParseError> for CompileError {
 pub(crate) enum CompileErrorKind {
     ParseError(ParseErrorKind),
     Unsupported(Feature, RegexFlavor),
+    UnsupportedInVersion {
+        feature: Feature,
+        flavor: RegexFlavor,
+        version: FlavorVersion,
+        required: FlavorVersion,
+    },
     UnsupportedPomskySyntax(UnsupportedError),
     HugeReference,
     UnknownReferenceNumber(i32),
@@ -80,7 +91,12 @@ pub(crate) enum CompileErrorKind {
         similar: Option<Box<str>>,
     },
     RecursiveVariable,
+    UnknownLint(String),
     RangeIsTooBig(u8),
+    VariablesTooDeep(u32),
+    TooManyCompiledNodes(u32),
+    RepetitionIsTooBig(u32),
+    OutputIsTooBig(u32),
     NegativeShorthandInAsciiMode,
     UnicodeInAsciiMode,
     DotNetNumberedRefWithMixedGroups,
@@ -98,6 +114,14 @@ pub(crate) enum CompileErrorKind {
     InfiniteRecursion,
     BadIntersection,
     EmptyIntersection,
+    IncompatibleFlavors {
+        flavors: (RegexFlavor, RegexFlavor),
+        regexes: Box<(String, String)>,
+    },
+    FlavorsDisagree {
+        construct: NonPortable,
+        flavors: (RegexFlavor, RegexFlavor),
+    },
 }
 
 impl CompileErrorKind {
@@ -110,7 +134,7 @@ impl CompileErrorKind {
     }
 }
 
-impl std::error::Error for CompileErrorKind {}
+impl core::error::Error for CompileErrorKind {}
 
 impl core::fmt::Display for CompileErrorKind {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -151,6 +175,14 @@ impl core::fmt::Display for CompileErrorKind {
                     feature.name(),
                 ),
             },
+            CompileErrorKind::UnsupportedInVersion { feature, flavor, version, required } => {
+                write!(
+                    f,
+                    "Unsupported feature `{}` in version {version} of the `{flavor:?}` regex \
+                    flavor. It requires version {required} or later",
+                    feature.name(),
+                )
+            }
             CompileErrorKind::UnsupportedPomskySyntax(inner) => inner.fmt(f),
             CompileErrorKind::HugeReference => {
                 write!(f, "Group references this large aren't supported")
@@ -184,9 +216,22 @@ impl core::fmt::Display for CompileErrorKind {
                 write!(f, "Variable `{found}` doesn't exist")
             }
             CompileErrorKind::RecursiveVariable => write!(f, "Variables can't be used recursively"),
+            CompileErrorKind::UnknownLint(lint) => write!(f, "Unknown lint `{lint}`"),
             CompileErrorKind::RangeIsTooBig(digits) => {
                 write!(f, "Range is too big, it isn't allowed to contain more than {digits} digits")
             }
+            CompileErrorKind::VariablesTooDeep(depth) => {
+                write!(f, "Variables are nested too deeply, the maximum depth is {depth}")
+            }
+            CompileErrorKind::TooManyCompiledNodes(max) => {
+                write!(f, "Expression is too big, it expands to more than {max} sub-expressions")
+            }
+            CompileErrorKind::RepetitionIsTooBig(max) => {
+                write!(f, "Repetition is too big, its bounds can't exceed {max}")
+            }
+            CompileErrorKind::OutputIsTooBig(max) => {
+                write!(f, "The compiled regex is too big, it can't exceed {max} bytes")
+            }
             CompileErrorKind::NegativeShorthandInAsciiMode => {
                 write!(f, "Shorthands can't be negated when Unicode is disabled")
             }
@@ -232,10 +277,47 @@ impl core::fmt::Display for CompileErrorKind {
             CompileErrorKind::EmptyIntersection => {
                 write!(f, "Intersection of expressions that do not overlap")
             }
+            CompileErrorKind::IncompatibleFlavors { flavors: (a, b), .. } => write!(
+                f,
+                "This expression compiles to different regexes in the `{a:?}` and `{b:?}` \
+                flavors, so no single regex works in both"
+            ),
+            CompileErrorKind::FlavorsDisagree { construct, flavors: (a, b) } => match construct {
+                NonPortable::NamedGroup => write!(
+                    f,
+                    "No syntax for named capturing groups works in both the `{a:?}` and `{b:?}` \
+                    flavors"
+                ),
+                NonPortable::Dot => write!(
+                    f,
+                    "The dot matches different characters in the `{a:?}` and `{b:?}` flavors"
+                ),
+                NonPortable::Shorthand => write!(
+                    f,
+                    "This character class matches different characters in the `{a:?}` and \
+                    `{b:?}` flavors"
+                ),
+                NonPortable::WordBoundary => write!(
+                    f,
+                    "This word boundary matches at different positions in the `{a:?}` and \
+                    `{b:?}` flavors"
+                ),
+            },
         }
     }
 }
 
+/// A construct that compiles to the same regex in several flavors, but doesn't
+/// match the same thing in all of them, or has no syntax that all of them
+/// support
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum NonPortable {
+    NamedGroup,
+    Dot,
+    Shorthand,
+    WordBoundary,
+}
+
 /// An error that indicates that an unsupported feature was used.
 ///
 /// See [`crate::features::PomskyFeatures`] for details.
@@ -260,7 +342,7 @@ pub(crate) enum UnsupportedError {
     Intersection,
 }
 
-impl std::error::Error for UnsupportedError {}
+impl core::error::Error for UnsupportedError {}
 
 impl core::fmt::Display for UnsupportedError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -1,4 +1,4 @@
-use std::fmt::{self, Write};
+use core::fmt::{self, Write};
 
 use pomsky_syntax::diagnose::{
     CharClassError, CharStringError, LexErrorMsg, ParseErrorKind, ParseWarningKind, RepetitionError,
@@ -101,6 +101,12 @@ diagnostic_code! {
         InfiniteRecursion = 322,
         BadIntersection = 323,
         EmptyIntersection = 324,
+        IncompatibleFlavors = 325,
+        UnknownLint = 326,
+        VariablesTooDeep = 327,
+        TooManyCompiledNodes = 328,
+        RepetitionIsTooBig = 329,
+        OutputIsTooBig = 330,
 
         // Warning indicating something might not be supported
         PossiblyUnsupported = 400,
@@ -113,6 +119,12 @@ diagnostic_code! {
         TestUnexpectedExactMatch = 504,
         TestMissingCaptureGroup = 505,
         TestWrongCaptureGroup = 506,
+
+        // Diagnostics from importing a regex
+        ImportInvalidRegex = 600,
+        ImportUnsupported = 601,
+        ImportRenamedGroup = 602,
+        ImportAmbiguousReference = 603,
     }
 }
 
@@ -214,7 +226,7 @@ impl<'a> From<&'a CompileErrorKind> for DiagnosticCode {
         use CompileErrorKind as C;
         match value {
             C::ParseError(p) => p.into(),
-            C::Unsupported(..) => Self::UnsupportedRegexFeature,
+            C::Unsupported(..) | C::UnsupportedInVersion { .. } => Self::UnsupportedRegexFeature,
             C::UnsupportedPomskySyntax(_) => Self::UnsupportedPomskySyntax,
             C::HugeReference => Self::HugeReference,
             C::UnknownReferenceNumber(_) | C::UnknownReferenceName { .. } => Self::UnknownReference,
@@ -225,7 +237,12 @@ impl<'a> From<&'a CompileErrorKind> for DiagnosticCode {
             C::RelativeRefZero => Self::UnknownReference,
             C::UnknownVariable { .. } => Self::UnknownVariable,
             C::RecursiveVariable => Self::RecursiveVariable,
+            C::UnknownLint(_) => Self::UnknownLint,
             C::RangeIsTooBig(_) => Self::RangeIsTooBig,
+            C::VariablesTooDeep(_) => Self::VariablesTooDeep,
+            C::TooManyCompiledNodes(_) => Self::TooManyCompiledNodes,
+            C::RepetitionIsTooBig(_) => Self::RepetitionIsTooBig,
+            C::OutputIsTooBig(_) => Self::OutputIsTooBig,
             C::NegatedHorizVertSpace => Self::NegatedHorizVertSpace,
             C::IllegalNegation { .. } => Self::IllegalNegation,
             C::NegativeShorthandInAsciiMode => Self::UnsupportedInAsciiMode,
@@ -238,6 +255,7 @@ impl<'a> From<&'a CompileErrorKind> for DiagnosticCode {
             C::InfiniteRecursion => Self::InfiniteRecursion,
             C::BadIntersection => Self::BadIntersection,
             C::EmptyIntersection => Self::EmptyIntersection,
+            C::IncompatibleFlavors { .. } | C::FlavorsDisagree { .. } => Self::IncompatibleFlavors,
         }
     }
 }
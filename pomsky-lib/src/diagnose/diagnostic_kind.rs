@@ -1,4 +1,4 @@
-use std::{fmt::Display, str::FromStr};
+use core::{fmt::Display, str::FromStr};
 
 use pomsky_syntax::diagnose::{ParseErrorKind, ParseWarningKind};
 
@@ -33,12 +33,16 @@ impl From<&CompileErrorKind> for DiagnosticKind {
         use CompileErrorKind as K;
         match kind {
             K::ParseError(p) => DiagnosticKind::from(p),
-            K::Unsupported(..) => DiagnosticKind::Compat,
+            K::Unsupported(..)
+            | K::UnsupportedInVersion { .. }
+            | K::IncompatibleFlavors { .. }
+            | K::FlavorsDisagree { .. } => DiagnosticKind::Compat,
             K::UnsupportedPomskySyntax(_) | K::HugeReference => DiagnosticKind::Syntax,
             K::UnknownReferenceNumber(_)
             | K::UnknownReferenceName { .. }
             | K::NameUsedMultipleTimes(_)
             | K::UnknownVariable { .. }
+            | K::UnknownLint(_)
             | K::RelativeRefZero => DiagnosticKind::Resolve,
             K::EmptyClassNegated { .. }
             | K::InfiniteRecursion
@@ -56,7 +60,11 @@ impl From<&CompileErrorKind> for DiagnosticKind {
             | K::UnsupportedInLookbehind { .. }
             | K::LookbehindNotConstantLength { .. }
             | K::BadIntersection => DiagnosticKind::Unsupported,
-            K::RangeIsTooBig(_) => DiagnosticKind::Limits,
+            K::RangeIsTooBig(_)
+            | K::VariablesTooDeep(_)
+            | K::TooManyCompiledNodes(_)
+            | K::RepetitionIsTooBig(_)
+            | K::OutputIsTooBig(_) => DiagnosticKind::Limits,
         }
     }
 }
@@ -81,7 +89,7 @@ impl From<&ParseWarningKind> for DiagnosticKind {
 }
 
 impl Display for DiagnosticKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str(match self {
             DiagnosticKind::Syntax => "(syntax)",
             DiagnosticKind::Resolve => "(resolve)",
@@ -1,12 +1,22 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
 use pomsky_syntax::{
     Span,
     diagnose::{ParseDiagnostic, ParseDiagnosticKind, ParseErrorKind, ParseWarningKind},
+    line_index::{LineCol, LineIndex, TextUnit},
 };
 
 use super::{
-    CompileError, CompileErrorKind, DiagnosticKind,
+    CompileError, CompileErrorKind, DiagnosticKind, ImportError, ImportWarning, ImportWarningKind,
+    QuickFix,
     diagnostic_code::DiagnosticCode,
-    help::{get_compiler_help, get_parse_warning_help},
+    fixes::{get_compiler_fixes, get_parse_warning_fixes, get_parser_fixes},
+    help::{get_compiler_help, get_import_warning_help, get_parse_warning_help},
 };
 
 #[derive(Debug, Clone)]
@@ -27,10 +37,13 @@ pub struct Diagnostic {
     pub span: Span,
     /// The kind or origin of error/warning
     pub kind: DiagnosticKind,
+    /// Changes that fix the error automatically. If there are several, the
+    /// first one is the most likely to be correct.
+    pub fixes: Vec<QuickFix>,
 }
 
 #[cfg(feature = "miette")]
-impl std::error::Error for Diagnostic {}
+impl core::error::Error for Diagnostic {}
 
 #[cfg(feature = "miette")]
 impl core::fmt::Display for Diagnostic {
@@ -69,6 +82,7 @@ impl Diagnostic {
 
         let help = super::help::get_parser_help(kind, slice, &mut span);
         let code = Some(DiagnosticCode::from(kind));
+        let fixes = get_parser_fixes(kind, span, source_code);
 
         Diagnostic {
             severity: Severity::Error,
@@ -77,6 +91,7 @@ impl Diagnostic {
             help,
             span,
             kind: DiagnosticKind::from(kind),
+            fixes,
         }
     }
 
@@ -100,6 +115,7 @@ impl Diagnostic {
                     help,
                     span,
                     kind: DiagnosticKind::from(kind),
+                    fixes: get_compiler_fixes(kind, span, source_code),
                 }
             }
         }
@@ -116,6 +132,47 @@ impl Diagnostic {
             help: get_parse_warning_help(kind),
             span,
             kind: DiagnosticKind::from(kind),
+            fixes: get_parse_warning_fixes(kind, span),
+        }
+    }
+
+    pub(crate) fn from_import_error(err: &ImportError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: Some(DiagnosticCode::ImportInvalidRegex),
+            msg: err.kind.to_string(),
+            help: None,
+            span: err.span,
+            kind: DiagnosticKind::Syntax,
+            fixes: vec![],
+        }
+    }
+
+    pub(crate) fn from_import_warning(warning: &ImportWarning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: Some(match warning.kind {
+                ImportWarningKind::Unsupported(_) => DiagnosticCode::ImportUnsupported,
+                ImportWarningKind::RenamedGroup { .. }
+                | ImportWarningKind::ReservedGroupName { .. }
+                | ImportWarningKind::DuplicateGroupName { .. } => {
+                    DiagnosticCode::ImportRenamedGroup
+                }
+                ImportWarningKind::AmbiguousReference { .. } => {
+                    DiagnosticCode::ImportAmbiguousReference
+                }
+            }),
+            msg: warning.kind.to_string(),
+            help: get_import_warning_help(&warning.kind),
+            span: warning.span,
+            kind: match warning.kind {
+                ImportWarningKind::Unsupported(_) => DiagnosticKind::Unsupported,
+                ImportWarningKind::RenamedGroup { .. }
+                | ImportWarningKind::ReservedGroupName { .. }
+                | ImportWarningKind::DuplicateGroupName { .. }
+                | ImportWarningKind::AmbiguousReference { .. } => DiagnosticKind::Other,
+            },
+            fixes: vec![],
         }
     }
 
@@ -166,6 +223,7 @@ impl Diagnostic {
             help,
             span,
             kind: DiagnosticKind::Test,
+            fixes: vec![],
         }
     }
 
@@ -177,7 +235,37 @@ impl Diagnostic {
         msg: String,
         help: Option<String>,
     ) -> Self {
-        Diagnostic { severity, code, msg, help, span: Span::empty(), kind: DiagnosticKind::Other }
+        Diagnostic {
+            severity,
+            code,
+            msg,
+            help,
+            span: Span::empty(),
+            kind: DiagnosticKind::Other,
+            fixes: vec![],
+        }
+    }
+
+    /// Returns the start and end of the diagnostic's span as lines and
+    /// columns, counted in the given unit. Returns `None` if the diagnostic
+    /// has no span.
+    ///
+    /// When converting many diagnostics for the same source code, create a
+    /// [`LineIndex`] once and use [`LineIndex::span_line_cols`] instead.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use pomsky::{Expr, line_index::{LineCol, TextUnit}, options::CompileOptions};
+    ///
+    /// let source = "'ä'\n  foo";
+    /// let (_, diagnostics, _) = Expr::parse_and_compile(source, CompileOptions::default());
+    /// let (start, end) = diagnostics[0].line_cols(source, TextUnit::Utf16).unwrap();
+    /// assert_eq!((start, end), (LineCol::new(1, 2), LineCol::new(1, 5)));
+    /// ```
+    #[must_use]
+    pub fn line_cols(&self, source_code: &str, unit: TextUnit) -> Option<(LineCol, LineCol)> {
+        LineIndex::new(source_code).span_line_cols(source_code, self.span, unit)
     }
 
     /// Returns a value that can display the diagnostic with the [`Display`]
@@ -187,9 +275,10 @@ impl Diagnostic {
     pub fn display_ascii<'a>(
         &'a self,
         source_code: Option<&'a str>,
-    ) -> impl std::fmt::Display + 'a {
+    ) -> impl core::fmt::Display + 'a {
+        use alloc::boxed::Box;
+        use core::fmt;
         use miette::GraphicalTheme;
-        use std::fmt;
 
         #[derive(Debug)]
         struct MietteDiagnostic<'a> {
@@ -203,7 +292,7 @@ impl Diagnostic {
             }
         }
 
-        impl std::error::Error for MietteDiagnostic<'_> {}
+        impl core::error::Error for MietteDiagnostic<'_> {}
 
         impl miette::Diagnostic for MietteDiagnostic<'_> {
             fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
@@ -215,12 +304,12 @@ impl Diagnostic {
             }
 
             fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
-                if let Some(std::ops::Range { start, end }) = self.diagnostic.span.range() {
+                if let Some(core::ops::Range { start, end }) = self.diagnostic.span.range() {
                     let label = match self.diagnostic.severity {
                         Severity::Error => "error occurred here",
                         Severity::Warning => "warning originated here",
                     };
-                    Some(Box::new(std::iter::once(miette::LabeledSpan::new(
+                    Some(Box::new(core::iter::once(miette::LabeledSpan::new(
                         Some(label.into()),
                         start,
                         end - start,
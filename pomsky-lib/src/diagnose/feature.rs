@@ -1,3 +1,5 @@
+use crate::options::{FlavorVersion, RegexFlavor};
+
 /// A regex feature, which might not be supported in every regex flavor.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
@@ -50,6 +52,10 @@ pub enum Feature {
     CharSetIntersection,
     /// Repetition higher than 1000
     RepetitionAbove1000,
+    /// Named capturing groups, e.g. `(?<name>group)`
+    NamedCaptureGroups,
+    /// Lookbehind, e.g. `(?<=lookbehind)`
+    Lookbehind,
 }
 
 impl Feature {
@@ -76,6 +82,30 @@ impl Feature {
             Feature::ScriptExtensions => "Unicode script extensions",
             Feature::CharSetIntersection => "Character set intersections",
             Feature::RepetitionAbove1000 => "Repetition above 1000",
+            Feature::NamedCaptureGroups => "named capturing groups",
+            Feature::Lookbehind => "lookbehind",
         }
     }
+    /// Returns the oldest version of the regex engine that supports this
+    /// feature, if it wasn't supported from the start.
+    ///
+    /// Only syntax that pomsky emits needs to be listed here. Possessive
+    /// quantifiers (Python 3.11) and inline modifiers (ES2025) aren't, since
+    /// pomsky never produces them.
+    pub(crate) fn min_version(self, flavor: RegexFlavor) -> Option<FlavorVersion> {
+        let (major, minor) = match (self, flavor) {
+            (Feature::AtomicGroups, RegexFlavor::Python) => (3, 11),
+            (Feature::Lookbehind | Feature::NamedCaptureGroups, RegexFlavor::JavaScript) => {
+                (2018, 0)
+            }
+            (Feature::UnicodeProp, RegexFlavor::JavaScript) => (2018, 0),
+            // set operations require the `v` flag
+            (Feature::CharSetIntersection, RegexFlavor::JavaScript) => (2024, 0),
+            (Feature::Grapheme, RegexFlavor::Java) => (9, 0),
+            (Feature::WordStartEnd, RegexFlavor::Rust) => (1, 10),
+            (Feature::Lookbehind, RegexFlavor::Postgres) => (9, 6),
+            _ => return None,
+        };
+        Some(FlavorVersion::new(major, minor))
+    }
 }
@@ -0,0 +1,133 @@
+use alloc::{format, vec, vec::Vec};
+
+use pomsky_syntax::{
+    Span,
+    diagnose::{DeprecationWarning, ParseErrorKind, ParseWarningKind, RepetitionError},
+    exprs::{Quantifier, Repetition},
+    visitor::RuleVisitor,
+};
+
+#[cfg(feature = "suggestions")]
+use pomsky_syntax::diagnose::CharClassError;
+
+use super::{CompileErrorKind, QuickFix, Replacement};
+
+pub(super) fn get_parser_fixes(kind: &ParseErrorKind, span: Span, source: &str) -> Vec<QuickFix> {
+    match kind {
+        #[cfg(feature = "suggestions")]
+        ParseErrorKind::CharClass(CharClassError::UnknownNamedClass {
+            found,
+            extra_in_prefix: false,
+            similar: Some(similar),
+        }) => replace_name(source, span, found, similar).into_iter().collect(),
+        &ParseErrorKind::UnallowedMultiNot(n) => {
+            let (description, insert) = if n % 2 == 0 {
+                ("Remove the exclamation marks", "")
+            } else {
+                ("Remove all exclamation marks but one", "!")
+            };
+            vec![
+                QuickFix::new(description, vec![Replacement::new(span, insert)])
+                    .meaning_preserving(),
+            ]
+        }
+        ParseErrorKind::Repetition(
+            error @ (RepetitionError::Multi | RepetitionError::QmSuffix),
+        ) => {
+            let Some(first) = first_repetition(source, span) else { return vec![] };
+            let first_range = first.span.range_unchecked();
+            let parens = vec![
+                Replacement::new(Span::new(first_range.start, first_range.start), "("),
+                Replacement::new(Span::new(first_range.end, first_range.end), ")"),
+            ];
+
+            if let RepetitionError::Multi = error {
+                vec![
+                    QuickFix::new("Add parentheses around the first repetition", parens)
+                        .meaning_preserving(),
+                ]
+            } else if let Quantifier::DefaultGreedy = first.quantifier {
+                let lazy = vec![Replacement::new(span, " lazy")];
+                vec![
+                    QuickFix::new("Make the repetition lazy", lazy),
+                    QuickFix::new("Add parentheses around the inner repetition", parens),
+                ]
+            } else {
+                vec![QuickFix::new("Add parentheses around the inner repetition", parens)]
+            }
+        }
+        _ => vec![],
+    }
+}
+
+pub(super) fn get_parse_warning_fixes(kind: &ParseWarningKind, span: Span) -> Vec<QuickFix> {
+    let ParseWarningKind::Deprecation(d) = kind;
+    match d {
+        DeprecationWarning::Unicode(cp) => {
+            let hex = cp[1..].trim_start_matches(|c: char| c == '+' || c.is_whitespace());
+            vec![QuickFix::replace(span, format!("U+{hex}")).meaning_preserving()]
+        }
+        &DeprecationWarning::ShorthandInRange(c) => {
+            vec![QuickFix::replace(span, format!("U+{:02X}", c as u32)).meaning_preserving()]
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "suggestions"), allow(unused_variables))]
+pub(super) fn get_compiler_fixes(
+    kind: &CompileErrorKind,
+    span: Span,
+    source: &str,
+) -> Vec<QuickFix> {
+    match kind {
+        CompileErrorKind::UnknownVariable { found, .. }
+            if found.len() > 1
+                && found.starts_with('U')
+                && found[1..].chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            vec![QuickFix::replace(span, format!("U+{}", &found[1..])).meaning_preserving()]
+        }
+
+        #[cfg(feature = "suggestions")]
+        CompileErrorKind::UnknownVariable { found, similar: Some(similar) }
+        | CompileErrorKind::UnknownReferenceName { found, similar: Some(similar) } => {
+            replace_name(source, span, found, similar).into_iter().collect()
+        }
+
+        _ => vec![],
+    }
+}
+
+/// Replaces the last occurrence of a misspelled name within the span
+#[cfg(feature = "suggestions")]
+fn replace_name(source: &str, span: Span, found: &str, similar: &str) -> Option<QuickFix> {
+    let range = span.range()?;
+    let offset = range.start + source.get(range)?.rfind(found)?;
+    Some(QuickFix::replace(Span::new(offset, offset + found.len()), similar))
+}
+
+/// Finds the repetition preceding an erroneous repetition, e.g. `'a'+` in
+/// `'a'+*`. Since the parser stops at the error, the source is parsed again
+/// with the erroneous repetition removed.
+fn first_repetition(source: &str, span: Span) -> Option<Repetition> {
+    struct Finder {
+        end: usize,
+    }
+
+    impl RuleVisitor<Repetition> for Finder {
+        fn visit_repetition(&mut self, repetition: &Repetition) -> Result<(), Repetition> {
+            match repetition.span.range() {
+                Some(range) if range.end == self.end => Err(repetition.clone()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    let range = span.range()?;
+    let end = source[..range.start].trim_end().len();
+    let source =
+        format!("{}{}{}", &source[..range.start], " ".repeat(range.len()), &source[range.end..]);
+
+    let (rule, _) = pomsky_syntax::parse_partial(&source, 256);
+    Finder { end }.visit_rule(&rule).err()
+}
@@ -1,3 +1,8 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
 use pomsky_syntax::{
     Span,
     diagnose::{
@@ -6,7 +11,7 @@ use pomsky_syntax::{
     },
 };
 
-use super::{CompileErrorKind, IllegalNegationKind};
+use super::{CompileErrorKind, Feature, IllegalNegationKind, ImportWarningKind, NonPortable};
 
 pub(super) fn get_parser_help(
     kind: &ParseErrorKind,
@@ -145,6 +150,12 @@ pub(super) fn get_compiler_help(kind: &CompileErrorKind, _span: Span) -> Option<
             which together match every code point",
         )),
 
+        CompileErrorKind::UnknownLint(_) => Some(
+            "Expected a diagnostic kind such as `deprecated` or `compat`, \
+            or a diagnostic code such as `P0105`"
+                .into(),
+        ),
+
         CompileErrorKind::NameUsedMultipleTimes(_) => {
             Some("Give this group a different name".into())
         }
@@ -180,6 +191,29 @@ pub(super) fn get_compiler_help(kind: &CompileErrorKind, _span: Span) -> Option<
             Parentheses may be required to clarify the parsing order."
                 .to_string(),
         ),
+        CompileErrorKind::Unsupported(Feature::NamedCaptureGroups, _) => {
+            Some("Use an unnamed capturing group instead, e.g. `:(...)`".into())
+        }
+        CompileErrorKind::UnsupportedInVersion { flavor, required, .. } => {
+            Some(format!("Target version {required} of the `{flavor:?}` flavor or later"))
+        }
+        CompileErrorKind::IncompatibleFlavors { flavors: (a, b), regexes } => Some(format!(
+            "The `{a:?}` flavor produces `{}`,\nbut the `{b:?}` flavor produces `{}`",
+            regexes.0, regexes.1
+        )),
+        CompileErrorKind::FlavorsDisagree { construct, .. } => Some(match construct {
+            NonPortable::NamedGroup => {
+                "Use an unnamed capturing group instead, e.g. `:(...)`".into()
+            }
+            NonPortable::Dot => "Use `![n]` to match any character except a line feed".into(),
+            NonPortable::Shorthand => "`[word]`, `[digit]` and `[space]` only match ASCII \
+                characters in some flavors.\n\
+                Use `disable unicode;` to match only ASCII characters in every flavor"
+                .into(),
+            NonPortable::WordBoundary => {
+                "Word boundaries only recognize ASCII word characters in some flavors".into()
+            }
+        }),
         CompileErrorKind::InfiniteRecursion => Some(
             "A recursive expression must have a branch that \
             doesn't reach the `recursion`, or can repeat 0 times"
@@ -189,3 +223,23 @@ pub(super) fn get_compiler_help(kind: &CompileErrorKind, _span: Span) -> Option<
         _ => None,
     }
 }
+
+pub(super) fn get_import_warning_help(kind: &ImportWarningKind) -> Option<String> {
+    match kind {
+        ImportWarningKind::Unsupported(_) => {
+            Some("Pomsky doesn't validate embedded regexes, so check that it still works".into())
+        }
+        ImportWarningKind::RenamedGroup { from, .. }
+        | ImportWarningKind::ReservedGroupName { name: from, .. } => {
+            Some(format!("Code that refers to the group `{from}` must be updated"))
+        }
+        ImportWarningKind::DuplicateGroupName { .. } => {
+            Some("Code that refers to this group must be updated".into())
+        }
+        ImportWarningKind::AmbiguousReference { first, .. } => Some(format!(
+            "It refers to a different group if the {} group didn't match, \
+            so check that the pomsky expression behaves the same",
+            if *first { "first" } else { "last" }
+        )),
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::string::String;
+
+use pomsky_syntax::Span;
+
+use super::Diagnostic;
+
+/// An error that can occur while importing a regex
+#[derive(Debug, Clone)]
+pub(crate) struct ImportError {
+    pub(super) kind: ImportErrorKind,
+    pub(super) span: Span,
+}
+
+impl ImportError {
+    /// Create a [Diagnostic] from this error.
+    #[must_use]
+    pub(crate) fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::from_import_error(self)
+    }
+}
+
+/// An error kind (without span) that can occur while importing a regex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImportErrorKind {
+    UnclosedGroup,
+    UnopenedGroup,
+    UnclosedClass,
+    TrailingBackslash,
+    NothingToRepeat,
+    InvalidCodePoint,
+    RangeNotIncreasing,
+    NumberTooLarge,
+    QuantifierNotIncreasing,
+}
+
+impl ImportErrorKind {
+    pub(crate) fn at(self, span: Span) -> ImportError {
+        ImportError { kind: self, span }
+    }
+}
+
+impl core::fmt::Display for ImportErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            ImportErrorKind::UnclosedGroup => "This group is never closed",
+            ImportErrorKind::UnopenedGroup => "This closing parenthesis has no matching group",
+            ImportErrorKind::UnclosedClass => "This character class is never closed",
+            ImportErrorKind::TrailingBackslash => "The regex ends with an incomplete escape",
+            ImportErrorKind::NothingToRepeat => "This quantifier has nothing to repeat",
+            ImportErrorKind::InvalidCodePoint => "This escape is not a valid code point",
+            ImportErrorKind::RangeNotIncreasing => "Character range must be in increasing order",
+            ImportErrorKind::NumberTooLarge => "This number is too large",
+            ImportErrorKind::QuantifierNotIncreasing => {
+                "The minimum of this quantifier is greater than the maximum"
+            }
+        })
+    }
+}
+
+/// A warning that can occur while importing a regex
+#[derive(Debug, Clone)]
+pub(crate) struct ImportWarning {
+    pub(super) kind: ImportWarningKind,
+    pub(super) span: Span,
+}
+
+impl ImportWarning {
+    /// Create a [Diagnostic] from this warning.
+    #[must_use]
+    pub(crate) fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::from_import_warning(self)
+    }
+}
+
+/// A warning kind (without span) that can occur while importing a regex
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ImportWarningKind {
+    /// A construct without a pomsky equivalent, which is embedded verbatim
+    Unsupported(&'static str),
+    /// A group name that is not allowed in pomsky
+    RenamedGroup { from: String, to: String },
+    /// A group name that is a reserved word in pomsky
+    ReservedGroupName { name: String, to: String },
+    /// A group name that is used more than once, which pomsky doesn't allow
+    DuplicateGroupName { name: String, to: String },
+    /// A backreference to a group name that is used more than once
+    AmbiguousReference { name: String, to: String, first: bool },
+}
+
+impl ImportWarningKind {
+    pub(crate) fn at(self, span: Span) -> ImportWarning {
+        ImportWarning { kind: self, span }
+    }
+}
+
+impl core::fmt::Display for ImportWarningKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ImportWarningKind::Unsupported(construct) => write!(
+                f,
+                "{construct} can't be expressed in pomsky, so it was embedded as a `regex` literal"
+            ),
+            ImportWarningKind::RenamedGroup { from, to } => {
+                write!(
+                    f,
+                    "The group name `{from}` isn't allowed in pomsky, so it was renamed to `{to}`"
+                )
+            }
+            ImportWarningKind::ReservedGroupName { name, to } => write!(
+                f,
+                "The group name `{name}` is a reserved word in pomsky, so it was renamed to `{to}`"
+            ),
+            ImportWarningKind::DuplicateGroupName { name, to } => write!(
+                f,
+                "The group name `{name}` is used more than once, which pomsky doesn't allow, \
+                so this group was renamed to `{to}`"
+            ),
+            ImportWarningKind::AmbiguousReference { name, to, first } => write!(
+                f,
+                "There is more than one group named `{name}`, so this backreference was \
+                converted to refer to `{to}`, the {} of them",
+                if *first { "first" } else { "last" }
+            ),
+        }
+    }
+}
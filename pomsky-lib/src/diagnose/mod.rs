@@ -1,17 +1,24 @@
 //! Crate containing diagnostics, i.e. errors and warnings
 
+pub(crate) use allowed_lints::AllowedLints;
 pub(crate) use compile_error::{
-    CompileError, CompileErrorKind, IllegalNegationKind, UnsupportedError,
+    CompileError, CompileErrorKind, IllegalNegationKind, NonPortable, UnsupportedError,
 };
+pub(crate) use import_error::{ImportError, ImportErrorKind, ImportWarning, ImportWarningKind};
 
 pub use diagnostic_code::DiagnosticCode;
 pub use diagnostic_kind::DiagnosticKind;
 pub use diagnostics::{Diagnostic, Severity};
 pub use feature::Feature;
+pub use quick_fix::{QuickFix, Replacement, apply_fixes};
 
+mod allowed_lints;
 mod compile_error;
 mod diagnostic_code;
 mod diagnostic_kind;
 mod diagnostics;
 mod feature;
+mod fixes;
 mod help;
+mod import_error;
+mod quick_fix;
@@ -0,0 +1,104 @@
+use alloc::{format, string::String, vec, vec::Vec};
+
+use pomsky_syntax::Span;
+
+/// A change that can be applied automatically to fix a [`Diagnostic`].
+///
+/// [`Diagnostic`]: super::Diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct QuickFix {
+    /// A short description of the fix, e.g. "Replace with `U+147A`"
+    pub description: String,
+    /// The changes to make. They are sorted by position and don't overlap.
+    pub replacements: Vec<Replacement>,
+    /// Whether the fixed expression means the same as the original one, e.g.
+    /// when a deprecated syntax is replaced. Fixes that have to guess what was
+    /// meant, e.g. by correcting a typo, should only be applied after asking
+    /// the user.
+    pub preserves_meaning: bool,
+}
+
+/// A part of the source code that should be replaced by a [`QuickFix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Replacement {
+    /// The start and end byte positions of the replaced source code. If they
+    /// are equal, the text is inserted at this position. Unlike in
+    /// diagnostics, the span `0..0` is not missing; it refers to the start of
+    /// the source code.
+    pub span: Span,
+    /// The text to replace this part of the source code with
+    pub insert: String,
+}
+
+impl QuickFix {
+    pub(crate) fn new(description: impl Into<String>, replacements: Vec<Replacement>) -> Self {
+        QuickFix { description: description.into(), replacements, preserves_meaning: false }
+    }
+
+    pub(crate) fn replace(span: Span, insert: impl Into<String>) -> Self {
+        let insert = insert.into();
+        QuickFix::new(format!("Replace with `{insert}`"), vec![Replacement::new(span, insert)])
+    }
+
+    /// Marks the fix as [preserving the meaning](QuickFix::preserves_meaning)
+    /// of the expression
+    pub(crate) fn meaning_preserving(self) -> Self {
+        QuickFix { preserves_meaning: true, ..self }
+    }
+}
+
+impl Replacement {
+    pub(crate) fn new(span: Span, insert: impl Into<String>) -> Self {
+        Replacement { span, insert: insert.into() }
+    }
+}
+
+/// Applies quick fixes to the source code, and returns the result.
+///
+/// A fix is skipped if it overlaps with a fix that was applied before, so
+/// it is possible that not all fixes are applied. After fixing some
+/// diagnostics, the expression should be compiled again to get the remaining
+/// diagnostics, and their fixes.
+///
+/// ```
+/// use pomsky::{Expr, diagnose::apply_fixes, options::CompileOptions};
+///
+/// let source = "[U+1-n]+ U147A";
+/// let (_, diagnostics, _) = Expr::parse_and_compile(source, CompileOptions::default());
+///
+/// let fixes = diagnostics.iter().filter_map(|d| d.fixes.iter().find(|f| f.preserves_meaning));
+/// assert_eq!(apply_fixes(source, fixes), "[U+1-U+0A]+ U+147A");
+/// ```
+pub fn apply_fixes<'a>(source: &str, fixes: impl IntoIterator<Item = &'a QuickFix>) -> String {
+    let mut applied: Vec<&Replacement> = Vec::new();
+    for fix in fixes {
+        let ranges = fix.replacements.iter().map(|r| r.span.range_unchecked()).collect::<Vec<_>>();
+        if ranges.iter().any(|r| r.end > source.len()) {
+            continue;
+        }
+
+        let overlaps = ranges.iter().any(|r1| {
+            applied.iter().any(|r2| {
+                let r2 = r2.span.range_unchecked();
+                r1.start < r2.end && r2.start < r1.end || r1.start == r2.start
+            })
+        });
+        if !overlaps {
+            applied.extend(&fix.replacements);
+        }
+    }
+    applied.sort_by_key(|r| r.span.range_unchecked().start);
+
+    let mut result = String::with_capacity(source.len());
+    let mut offset = 0;
+    for replacement in applied {
+        let range = replacement.span.range_unchecked();
+        result.push_str(&source[offset..range.start]);
+        result.push_str(&replacement.insert);
+        offset = range.end;
+    }
+    result.push_str(&source[offset..]);
+    result
+}
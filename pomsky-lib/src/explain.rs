@@ -0,0 +1,627 @@
+//! Natural-language explanations of pomsky expressions
+//!
+//! An [`Explanation`] is a tree that mirrors the structure of the expression.
+//! Each node describes one part of the expression in English, records the
+//! variable it was inserted from, and lists notes about behaviour that is
+//! specific to the regex flavor.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+use pomsky_syntax::exprs::{
+    Boundary, BoundaryKind, CharClass, Group, GroupItem, GroupKind, GroupName, Lookaround,
+    LookaroundKind, Quantifier, Reference, ReferenceTarget, Repetition, RepetitionKind, Rule,
+    ScriptExtension, Stmt,
+};
+
+use crate::{
+    Expr, Span,
+    capturing_groups::CapturingGroupsCollector,
+    diagnose::{Diagnostic, DiagnosticKind, Severity},
+    options::{CompileOptions, RegexFlavor},
+    visitor::RuleVisitor,
+};
+
+/// The explanation of a part of a pomsky expression. It can be displayed as
+/// an indented list:
+///
+/// ```
+/// use pomsky::{explain::explain, options::RegexFlavor};
+///
+/// let (explanation, _) = explain("let x = [word]+; :name(x) '!'", RegexFlavor::Pcre);
+/// assert_eq!(explanation.unwrap().to_string(), "\
+/// a sequence of:
+/// - a capturing group named `name`, containing:
+///   - a word character, repeated one or more times (from variable `x`)
+/// - the character '!'
+/// ");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Explanation {
+    /// Description of this part of the expression
+    pub description: String,
+    /// The span of this part in the source code. If the part was inserted
+    /// from a variable, this is the span where the variable is used.
+    pub span: Span,
+    /// The name of the variable this part was inserted from, if any
+    pub variable: Option<String>,
+    /// Behaviour that is specific to the regex flavor, or features that are
+    /// not supported in the regex flavor
+    pub notes: Vec<String>,
+    /// Explanations of the sub-expressions
+    pub children: Vec<Explanation>,
+}
+
+impl Explanation {
+    fn new(description: impl Into<String>, span: Span) -> Self {
+        Explanation {
+            description: description.into(),
+            span,
+            variable: None,
+            notes: vec![],
+            children: vec![],
+        }
+    }
+
+    fn with_children(mut self, children: Vec<Explanation>) -> Self {
+        self.children = children;
+        self
+    }
+
+    fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    fn with_notes(mut self, notes: Vec<String>) -> Self {
+        self.notes.extend(notes);
+        self
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = depth.saturating_sub(1) * 2;
+        if depth > 0 {
+            write!(f, "{:indent$}- ", "")?;
+        }
+        f.write_str(&self.description)?;
+        if let Some(variable) = &self.variable {
+            write!(f, " (from variable `{variable}`)")?;
+        }
+        f.write_str("\n")?;
+
+        let indent = depth * 2;
+        for note in &self.notes {
+            writeln!(f, "{:indent$}note: {note}", "")?;
+        }
+        for child in &self.children {
+            child.write_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Parses a pomsky expression and explains it. Returns `None` if it contains
+/// a syntax error. The `flavor` determines which flavor-specific notes are
+/// included.
+///
+/// Compilation errors, e.g. unknown variables, don't prevent the expression
+/// from being explained; use [`Expr::compile`] to find them.
+pub fn explain(input: &str, flavor: RegexFlavor) -> (Option<Explanation>, Vec<Diagnostic>) {
+    let (expr, diagnostics) = Expr::parse(input);
+    let diagnostics = diagnostics.collect();
+    (expr.map(|expr| expr.explain(input, flavor)), diagnostics)
+}
+
+pub(crate) struct Explainer<'i> {
+    input: &'i str,
+    flavor: RegexFlavor,
+    /// Variables that are in scope, including the built-in ones
+    variables: Vec<(&'i str, &'i Rule)>,
+    /// Number of built-in variables at the start of `variables`
+    builtins: usize,
+    /// Indices of the variables that are currently being expanded
+    current_vars: BTreeSet<usize>,
+    groups: CapturingGroupsCollector,
+    next_group: u32,
+}
+
+impl<'i> Explainer<'i> {
+    pub(crate) fn new(
+        input: &'i str,
+        flavor: RegexFlavor,
+        rule: &Rule,
+        builtins: Vec<(&'i str, &'i Rule)>,
+    ) -> Self {
+        // invalid groups are reported by the compiler, so errors are ignored
+        let mut groups = CapturingGroupsCollector::new();
+        let _ = groups.visit_rule(rule);
+
+        Explainer {
+            input,
+            flavor,
+            builtins: builtins.len(),
+            variables: builtins,
+            current_vars: BTreeSet::new(),
+            groups,
+            next_group: 1,
+        }
+    }
+
+    pub(crate) fn explain(&mut self, rule: &'i Rule) -> Explanation {
+        match rule {
+            Rule::Literal(lit) => Explanation::new(literal(&lit.content), lit.span),
+            Rule::CharClass(class) => {
+                let explanation = self.explain_char_class(class);
+                self.unsupported_notes(explanation, rule)
+            }
+            Rule::Group(group) => self.explain_group(group),
+            Rule::Alternation(alt) => {
+                let children = alt.rules.iter().map(|rule| self.explain(rule)).collect();
+                Explanation::new("one of the following alternatives:", rule.span())
+                    .with_children(children)
+            }
+            Rule::Intersection(int) => {
+                let children = int.rules.iter().map(|rule| self.explain(rule)).collect();
+                let explanation = Explanation::new("a character that matches all of:", int.span)
+                    .with_children(children);
+                self.unsupported_notes(explanation, rule)
+            }
+            Rule::Repetition(rep) => self.explain_repetition(rep),
+            Rule::Boundary(boundary) => {
+                let explanation = self.explain_boundary(boundary);
+                self.unsupported_notes(explanation, rule)
+            }
+            Rule::Lookaround(lookaround) => self.explain_lookaround(lookaround, lookaround.kind),
+            Rule::Variable(var) => self.explain_variable(&var.name, var.span),
+            Rule::Reference(reference) => self.explain_reference(reference),
+            Rule::Range(range) => {
+                let start = digits(&range.start);
+                let end = digits(&range.end);
+                let description = match range.radix {
+                    10 => format!("a number between {start} and {end}"),
+                    radix => format!("a base-{radix} number between {start} and {end}"),
+                };
+                Explanation::new(format!("{description}, without leading zeros"), range.span)
+            }
+            Rule::StmtExpr(stmt) => match &stmt.stmt {
+                Stmt::Let(r#let) => {
+                    self.variables.push((&r#let.name, &r#let.rule));
+                    let explanation = self.explain(&stmt.rule);
+                    self.variables.pop();
+                    explanation
+                }
+                // the parser already applied these modes to the affected expressions
+                Stmt::Enable(..) | Stmt::Disable(..) | Stmt::Allow(_) | Stmt::Test(_) => {
+                    self.explain(&stmt.rule)
+                }
+            },
+            Rule::Negation(negation) => match &negation.rule {
+                Rule::Lookaround(lookaround) => {
+                    let kind = match lookaround.kind {
+                        LookaroundKind::Ahead => LookaroundKind::AheadNegative,
+                        LookaroundKind::AheadNegative => LookaroundKind::Ahead,
+                        LookaroundKind::Behind => LookaroundKind::BehindNegative,
+                        LookaroundKind::BehindNegative => LookaroundKind::Behind,
+                    };
+                    self.explain_lookaround(lookaround, kind)
+                }
+                Rule::Boundary(boundary) if boundary.kind == BoundaryKind::Word => {
+                    let boundary = Boundary { kind: BoundaryKind::NotWord, ..*boundary };
+                    let mut explanation = self.explain_boundary(&boundary);
+                    explanation.span = rule.span();
+                    self.unsupported_notes(explanation, rule)
+                }
+                inner => {
+                    let inner = self.explain(inner);
+                    let explanation = if inner.children.is_empty() && inner.variable.is_none() {
+                        let description = format!("any character except {}", inner.description);
+                        Explanation { description, span: rule.span(), ..inner }
+                    } else {
+                        Explanation::new("any character except:", rule.span())
+                            .with_children(vec![inner])
+                    };
+                    self.unsupported_notes(explanation, rule)
+                }
+            },
+            Rule::Regex(regex) => Explanation::new(
+                format!("the regex `{}`, which is inserted verbatim", regex.content),
+                regex.span,
+            ),
+            Rule::Recursion(recursion) => {
+                let explanation =
+                    Explanation::new("the entire expression, recursively", recursion.span);
+                match self.flavor {
+                    RegexFlavor::Pcre | RegexFlavor::Ruby | RegexFlavor::PythonRegex => explanation,
+                    flavor => explanation.with_note(unsupported("recursion", flavor)),
+                }
+            }
+            Rule::Grapheme => {
+                let explanation = Explanation::new(
+                    "a Unicode grapheme cluster, i.e. a user-perceived character",
+                    rule.span(),
+                );
+                self.unsupported_notes(explanation, rule)
+            }
+            Rule::Codepoint => Explanation::new("any Unicode code point", rule.span()),
+            Rule::Dot(_) => Explanation::new("any character except a line break", rule.span()),
+            Rule::Error(span) => Explanation::new("an invalid expression", *span),
+        }
+    }
+
+    fn explain_group(&mut self, group: &'i Group) -> Explanation {
+        let description = match &group.kind {
+            GroupKind::Capturing(capture) => {
+                let index = self.next_group;
+                self.next_group += 1;
+                match &capture.name {
+                    Some(name) => format!("a capturing group named `{name}`, containing:"),
+                    None => format!("capturing group {index}, containing:"),
+                }
+            }
+            GroupKind::Atomic => {
+                "an atomic group, which is never backtracked into, containing:".to_string()
+            }
+            GroupKind::Normal | GroupKind::Implicit => match group.parts.as_slice() {
+                [] => return Explanation::new("the empty string", group.span),
+                [single] => return self.explain(single),
+                _ => "a sequence of:".to_string(),
+            },
+        };
+
+        // parentheses inside the group, e.g. `atomic('a' 'b')`, don't need another level
+        let parts = match group.parts.as_slice() {
+            [Rule::Group(inner)] if inner.kind.is_normal() => &inner.parts,
+            _ => &group.parts,
+        };
+        let children = match parts.as_slice() {
+            [] => vec![Explanation::new("the empty string", group.span)],
+            parts => parts.iter().map(|part| self.explain(part)).collect(),
+        };
+        let mut explanation = Explanation::new(description, group.span).with_children(children);
+
+        match (&group.kind, self.flavor) {
+            (
+                GroupKind::Atomic,
+                flavor @ (RegexFlavor::JavaScript
+                | RegexFlavor::Rust
+                | RegexFlavor::RE2
+                | RegexFlavor::Postgres),
+            ) => {
+                explanation.notes.push(unsupported("atomic groups", flavor));
+            }
+            (GroupKind::Atomic, RegexFlavor::Python) => {
+                explanation.notes.push("Atomic groups require Python 3.11 or later".to_string());
+            }
+            (GroupKind::Capturing(capture), RegexFlavor::Postgres) if capture.name.is_some() => {
+                explanation.notes.push(unsupported("named capturing groups", self.flavor));
+            }
+            _ => {}
+        }
+        explanation
+    }
+
+    fn explain_repetition(&mut self, rep: &'i Repetition) -> Explanation {
+        let mut how_often = match rep.kind {
+            RepetitionKind { lower_bound: 0, upper_bound: Some(1) } => "optional".to_string(),
+            RepetitionKind { lower_bound: 0, upper_bound: None } => {
+                "repeated zero or more times".to_string()
+            }
+            RepetitionKind { lower_bound: 1, upper_bound: None } => {
+                "repeated one or more times".to_string()
+            }
+            RepetitionKind { lower_bound, upper_bound: None } => {
+                format!("repeated at least {lower_bound} times")
+            }
+            RepetitionKind { lower_bound: 1, upper_bound: Some(1) } => {
+                "repeated exactly once".to_string()
+            }
+            RepetitionKind { lower_bound, upper_bound: Some(upper) } if lower_bound == upper => {
+                format!("repeated exactly {lower_bound} times")
+            }
+            RepetitionKind { lower_bound, upper_bound: Some(upper) } => {
+                format!("repeated between {lower_bound} and {upper} times")
+            }
+        };
+        let is_fixed = rep.kind.upper_bound == Some(rep.kind.lower_bound);
+        if let (Quantifier::Lazy | Quantifier::DefaultLazy, false) = (rep.quantifier, is_fixed) {
+            how_often.push_str(", as few times as possible");
+        }
+
+        let mut inner = self.explain(&rep.rule);
+        if inner.children.is_empty() && inner.variable.is_none() {
+            // simple expressions are explained in a single line
+            inner.description = format!("{}, {how_often}", inner.description);
+            inner.span = rep.span;
+            inner
+        } else {
+            let description = format!("the following, {how_often}:");
+            Explanation::new(description, rep.span).with_children(vec![inner])
+        }
+    }
+
+    fn explain_char_class(&self, class: &CharClass) -> Explanation {
+        let ascii = !class.unicode_aware;
+        match class.inner.as_slice() {
+            [item] => Explanation::new(char_class_item(*item, ascii), class.span),
+            items => {
+                let children = items
+                    .iter()
+                    .map(|&item| Explanation::new(char_class_item(item, ascii), class.span))
+                    .collect();
+                Explanation::new("a character that is any of:", class.span).with_children(children)
+            }
+        }
+        .with_notes(self.shorthand_notes(class))
+    }
+
+    /// Explains how shorthands are emulated when the flavor's shorthands only
+    /// support ASCII
+    fn shorthand_notes(&self, class: &CharClass) -> Vec<String> {
+        let mut notes = vec![];
+        if !class.unicode_aware {
+            return notes;
+        }
+        for item in &class.inner {
+            let GroupItem::Named { name, .. } = item else { continue };
+            let note = match (name, self.flavor) {
+                (GroupName::Word, RegexFlavor::JavaScript) => {
+                    "`\\w` only matches ASCII characters in the `JavaScript` flavor, so `word` \
+                    is emulated with Unicode properties"
+                }
+                (GroupName::Digit, RegexFlavor::JavaScript) => {
+                    "`\\d` only matches ASCII digits in the `JavaScript` flavor, so `digit` \
+                    is emulated with `\\p{Nd}`"
+                }
+                (GroupName::Digit, RegexFlavor::RE2) => {
+                    "`\\d` only matches ASCII digits in the `RE2` flavor, so `digit` \
+                    is emulated with `\\p{Nd}`"
+                }
+                (GroupName::Space, RegexFlavor::RE2) => {
+                    "`\\s` only matches ASCII whitespace in the `RE2` flavor, so the other \
+                    whitespace characters are added explicitly"
+                }
+                _ => continue,
+            };
+            if !notes.iter().any(|n| n == note) {
+                notes.push(note.to_string());
+            }
+        }
+        notes
+    }
+
+    fn explain_boundary(&self, boundary: &Boundary) -> Explanation {
+        let description = match boundary.kind {
+            BoundaryKind::Start => "the start of the string",
+            BoundaryKind::End => "the end of the string",
+            BoundaryKind::Word => "a word boundary",
+            BoundaryKind::NotWord => "a position that is not a word boundary",
+            BoundaryKind::WordStart => "the start of a word",
+            BoundaryKind::WordEnd => "the end of a word",
+        };
+        let mut explanation = Explanation::new(description, boundary.span);
+
+        let is_word = !matches!(boundary.kind, BoundaryKind::Start | BoundaryKind::End);
+        if is_word && !boundary.unicode_aware {
+            explanation.description.push_str(", where only ASCII characters are word characters");
+        }
+
+        match (boundary.kind, self.flavor) {
+            (BoundaryKind::Start, RegexFlavor::Ruby) => {
+                explanation.notes.push(
+                    "In the `Ruby` flavor, this also matches at the start of every line"
+                        .to_string(),
+                );
+            }
+            (BoundaryKind::End, RegexFlavor::Ruby) => {
+                explanation.notes.push(
+                    "In the `Ruby` flavor, this also matches at the end of every line".to_string(),
+                );
+            }
+            (
+                BoundaryKind::End,
+                flavor @ (RegexFlavor::Pcre
+                | RegexFlavor::Python
+                | RegexFlavor::PythonRegex
+                | RegexFlavor::Java
+                | RegexFlavor::DotNet),
+            ) => {
+                explanation.notes.push(format!(
+                    "In the `{flavor:?}` flavor, this also matches before a line break at the \
+                    end of the string"
+                ));
+            }
+            (
+                BoundaryKind::WordStart | BoundaryKind::WordEnd,
+                flavor @ (RegexFlavor::Python
+                | RegexFlavor::Java
+                | RegexFlavor::JavaScript
+                | RegexFlavor::DotNet
+                | RegexFlavor::Ruby),
+            ) => {
+                explanation.notes.push(format!(
+                    "The `{flavor:?}` flavor has no syntax for this, so it is emulated with \
+                    lookarounds"
+                ));
+            }
+            _ => {}
+        }
+        explanation
+    }
+
+    fn explain_lookaround(
+        &mut self,
+        lookaround: &'i Lookaround,
+        kind: LookaroundKind,
+    ) -> Explanation {
+        let description = match kind {
+            LookaroundKind::Ahead => "a position followed by:",
+            LookaroundKind::AheadNegative => "a position not followed by:",
+            LookaroundKind::Behind => "a position preceded by:",
+            LookaroundKind::BehindNegative => "a position not preceded by:",
+        };
+        let inner = self.explain(&lookaround.rule);
+        let explanation = Explanation::new(description, lookaround.span).with_children(vec![inner]);
+
+        let is_behind = matches!(kind, LookaroundKind::Behind | LookaroundKind::BehindNegative);
+        match self.flavor {
+            flavor @ (RegexFlavor::Rust | RegexFlavor::RE2) => {
+                explanation.with_note(unsupported("lookahead/behind", flavor))
+            }
+            RegexFlavor::Python if is_behind => explanation
+                .with_note("In the `Python` flavor, a lookbehind must have a fixed length"),
+            RegexFlavor::Java if is_behind => explanation
+                .with_note("In the `Java` flavor, a lookbehind must have a bounded length"),
+            _ => explanation,
+        }
+    }
+
+    fn explain_variable(&mut self, name: &str, span: Span) -> Explanation {
+        // like in the compiler, variables that are currently expanded are skipped
+        let variable = self
+            .variables
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|&(i, &(var, _))| var == name && !self.current_vars.contains(&i));
+
+        let Some((i, &(_, rule))) = variable else {
+            return Explanation::new(format!("the unknown variable `{name}`"), span);
+        };
+
+        self.current_vars.insert(i);
+        let mut explanation = self.explain(rule);
+        self.current_vars.remove(&i);
+
+        explanation.span = span;
+        if i >= self.builtins && explanation.variable.is_none() {
+            explanation.variable = Some(name.to_string());
+        }
+        explanation
+    }
+
+    fn explain_reference(&self, reference: &Reference) -> Explanation {
+        let index = match &reference.target {
+            ReferenceTarget::Named(name) => self.groups.names.get(name).map(|g| g.absolute),
+            &ReferenceTarget::Number(number) => Some(number),
+            &ReferenceTarget::Relative(offset) => {
+                let index = match offset {
+                    ..0 => offset + self.next_group as i32,
+                    _ => offset + self.next_group as i32 - 1,
+                };
+                u32::try_from(index).ok()
+            }
+        };
+        let group = match &reference.target {
+            ReferenceTarget::Named(name) => format!("group `{name}`"),
+            _ => match index {
+                Some(index) => format!("group {index}"),
+                None => "a group that doesn't exist".to_string(),
+            },
+        };
+
+        let is_forward = index.is_some_and(|index| index >= self.next_group);
+        let description = if is_forward {
+            format!("the same text as {group}, which appears later (a forward reference)")
+        } else {
+            format!("the same text as {group}")
+        };
+        let explanation = Explanation::new(description, reference.span);
+
+        match self.flavor {
+            flavor @ (RegexFlavor::Rust | RegexFlavor::RE2) => {
+                explanation.with_note(unsupported("backreference", flavor))
+            }
+            flavor @ (RegexFlavor::JavaScript
+            | RegexFlavor::Python
+            | RegexFlavor::Ruby
+            | RegexFlavor::Postgres)
+                if is_forward =>
+            {
+                explanation.with_note(unsupported("forward reference", flavor))
+            }
+            _ => explanation,
+        }
+    }
+
+    /// Compiles the rule on its own, and adds a note for every feature that
+    /// isn't supported in the regex flavor
+    fn unsupported_notes(&self, mut explanation: Explanation, rule: &Rule) -> Explanation {
+        let (_, diagnostics) = Expr::from(rule.clone())
+            .compile(self.input, CompileOptions { flavor: self.flavor, ..Default::default() });
+        for d in diagnostics {
+            let relevant = matches!(d.kind, DiagnosticKind::Compat | DiagnosticKind::Invalid);
+            if d.severity == Severity::Error && relevant && !explanation.notes.contains(&d.msg) {
+                explanation.notes.push(d.msg);
+            }
+        }
+        explanation
+    }
+}
+
+fn unsupported(feature: &str, flavor: RegexFlavor) -> String {
+    format!("Unsupported feature `{feature}` in the `{flavor:?}` regex flavor")
+}
+
+fn literal(content: &str) -> String {
+    let mut chars = content.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => "the empty string".to_string(),
+        (Some(c), None) => format!("the character {c:?}"),
+        _ => format!("the text {content:?}"),
+    }
+}
+
+fn digits(digits: &[u8]) -> String {
+    digits
+        .iter()
+        .map(|&d| char::from_digit(d.into(), 36).unwrap_or('?').to_ascii_uppercase())
+        .collect()
+}
+
+fn char_class_item(item: GroupItem, ascii: bool) -> String {
+    match item {
+        GroupItem::Char(c) => format!("the character {c:?}"),
+        GroupItem::Range { first, last } => {
+            format!("a character between {first:?} and {last:?}")
+        }
+        GroupItem::Named { name, negative, .. } => {
+            let ascii = if ascii { "an ASCII " } else { "a " };
+            let named = match name {
+                GroupName::Word => format!("{ascii}word character"),
+                GroupName::Digit => format!("{ascii}digit"),
+                GroupName::Space => format!("{ascii}whitespace character"),
+                GroupName::HorizSpace => "a horizontal whitespace character".to_string(),
+                GroupName::VertSpace => "a vertical whitespace character".to_string(),
+                GroupName::Category(category) => {
+                    format!("a character in the Unicode category `{}`", category.as_str())
+                }
+                GroupName::Script(script, ScriptExtension::Yes) => {
+                    format!("a character whose script extensions include `{}`", script.as_str())
+                }
+                GroupName::Script(script, _) => {
+                    format!("a character in the `{}` script", script.as_str())
+                }
+                GroupName::CodeBlock(block) => {
+                    format!("a character in the Unicode block `{}`", block.as_str())
+                }
+                GroupName::OtherProperties(prop) => {
+                    format!("a character with the Unicode property `{}`", prop.as_str())
+                }
+            };
+            if negative { format!("any character except {named}") } else { named }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Implements [alternation](https://www.regular-expressions.info/alternation.html):
 //! `('alt1' | 'alt2' | 'alt3')`.
 
+use alloc::{string::String, vec::Vec};
+
 use crate::{
     compile::{CompileResult, CompileState},
     options::{CompileOptions, RegexFlavor},
@@ -35,9 +37,9 @@ impl RegexAlternation {
         Self { parts }
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
         for rule in &self.parts {
-            rule.codegen(buf, flavor);
+            rule.codegen(buf, flavor, portable);
             buf.push('|');
         }
         if !self.parts.is_empty() {
@@ -2,6 +2,8 @@
 //! [word boundaries](https://www.regular-expressions.info/wordboundaries.html) and
 //! [anchors](https://www.regular-expressions.info/anchors.html).
 
+use alloc::string::String;
+
 use pomsky_syntax::exprs::{Boundary, BoundaryKind};
 
 use crate::{
@@ -29,27 +31,50 @@ impl Compile for Boundary {
             Err(CompileErrorKind::RubyLookaheadInLookbehind { was_word_boundary: true }
                 .at(self.span))
         } else {
+            if let WordStart | WordEnd = self.kind {
+                options.require_version(Feature::WordStartEnd, self.span)?;
+                if !has_native_word_start_end(options.flavor) {
+                    // the polyfill uses lookbehind
+                    options.require_version(Feature::Lookbehind, self.span)?;
+                }
+            }
             Ok(Regex::Boundary(self.kind))
         }
     }
 }
 
+fn has_native_word_start_end(flavor: RegexFlavor) -> bool {
+    matches!(
+        flavor,
+        RegexFlavor::Pcre | RegexFlavor::Rust | RegexFlavor::Postgres | RegexFlavor::PythonRegex
+    )
+}
+
 pub(crate) fn boundary_kind_codegen(bk: BoundaryKind, buf: &mut String, flavor: RegexFlavor) {
     match bk {
         BoundaryKind::Start => buf.push('^'),
         BoundaryKind::End => buf.push('$'),
 
-        BoundaryKind::Word => buf.push_str(r"\b"),
-        BoundaryKind::NotWord => buf.push_str(r"\B"),
+        // in PostgreSQL, `\b` is a backspace
+        BoundaryKind::Word => buf.push_str(match flavor {
+            RegexFlavor::Postgres => r"\y",
+            _ => r"\b",
+        }),
+        BoundaryKind::NotWord => buf.push_str(match flavor {
+            RegexFlavor::Postgres => r"\Y",
+            _ => r"\B",
+        }),
 
         BoundaryKind::WordStart => buf.push_str(match flavor {
             RegexFlavor::Pcre => "[[:<:]]",
             RegexFlavor::Rust => r"\<",
+            RegexFlavor::Postgres | RegexFlavor::PythonRegex => r"\m",
             _ => r"(?<!\w)(?=\w)",
         }),
         BoundaryKind::WordEnd => buf.push_str(match flavor {
             RegexFlavor::Pcre => "[[:>:]]",
             RegexFlavor::Rust => r"\>",
+            RegexFlavor::Postgres | RegexFlavor::PythonRegex => r"\M",
             _ => r"(?<=\w)(?!\w)",
         }),
     }
@@ -0,0 +1,360 @@
+//! Functions for building an [`Expr`] programmatically, without parsing.
+//!
+//! All nodes created here have an empty span. Diagnostics for such an
+//! expression therefore can't point to a source location; pass an empty string
+//! as input when compiling it.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::ops::{Bound, RangeBounds};
+
+use pomsky_syntax::{
+    Span,
+    diagnose::{CharClassError, ParseError, ParseErrorKind},
+    exprs::{
+        Alternation, Boundary, BoundaryKind, Capture, CharClass, CharGroup, Group, GroupItem,
+        GroupKind, Literal, Lookaround, LookaroundKind, Negation, Quantifier, Range, Recursion,
+        Reference, ReferenceTarget, Regex, Repetition, RepetitionKind, Rule,
+    },
+};
+
+use super::Expr;
+
+/// An item in a character class, used by [`Expr::class`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassItem(Vec<GroupItem>);
+
+impl ClassItem {
+    /// A single character
+    pub fn char(c: char) -> Self {
+        ClassItem(vec![GroupItem::Char(c)])
+    }
+
+    /// All characters in the string
+    pub fn chars(s: &str) -> Self {
+        ClassItem(s.chars().map(GroupItem::Char).collect())
+    }
+
+    /// An inclusive range of characters, e.g. `'a'-'z'`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `first` is greater than `last`.
+    pub fn range(first: char, last: char) -> Self {
+        assert!(first <= last, "character range {first:?}-{last:?} is not ascending");
+        if first == last {
+            Self::char(first)
+        } else {
+            ClassItem(vec![GroupItem::Range { first, last }])
+        }
+    }
+
+    /// A shorthand, POSIX class or Unicode property, e.g. `word`, `ascii_alpha`,
+    /// `Latin` or `scx:Greek`. Returns an error if the name is unknown.
+    pub fn named(name: &str) -> Result<Self, ParseError> {
+        Self::named_impl(name, false)
+    }
+
+    /// A negated shorthand or Unicode property, e.g. `!word` or `!Latin`.
+    /// Returns an error if the name is unknown or can't be negated.
+    pub fn named_negated(name: &str) -> Result<Self, ParseError> {
+        Self::named_impl(name, true)
+    }
+
+    fn named_impl(name: &str, negative: bool) -> Result<Self, ParseError> {
+        let (kind, name) = match name.split_once(':') {
+            Some((kind, name)) => (Some(kind), name),
+            None => (None, name),
+        };
+        CharGroup::try_from_group_name(kind, name, negative, Span::empty())
+            .map(ClassItem)
+            .map_err(|e| e.at(Span::empty()))
+    }
+}
+
+/// ## Building expressions
+///
+/// Expressions can be built from Rust code, which is useful when a pattern is
+/// generated from data, and avoids manual escaping. Such an expression is
+/// compiled like one that was parsed:
+///
+/// ```
+/// use pomsky::{ClassItem, Expr, options::CompileOptions};
+///
+/// let extension = Expr::alternatives(["jpg", "png", "gif"].map(Expr::literal))?;
+/// let name = Expr::class([ClassItem::named("word")?, ClassItem::char('-')])?.repeat(1..);
+/// let expr = Expr::sequence([name, Expr::literal("."), extension.capture_named("ext")?]);
+///
+/// let (regex, _) = expr.compile("", CompileOptions::default());
+/// assert_eq!(regex.as_deref(), Some(r"[\w\-]+\.(?P<ext>jpg|png|gif)"));
+/// # Ok::<(), pomsky::ParseError>(())
+/// ```
+impl Expr {
+    /// A string literal, which is matched verbatim
+    pub fn literal(content: impl Into<String>) -> Self {
+        Expr(Rule::Literal(Literal::new(content.into(), Span::empty())))
+    }
+
+    /// A character class, matching any of the items. Returns an error if
+    /// there are no items, like the empty class `[]`.
+    pub fn class(items: impl IntoIterator<Item = ClassItem>) -> Result<Self, ParseError> {
+        let items: Vec<_> = items.into_iter().flat_map(|item| item.0).collect();
+        if items.is_empty() {
+            return Err(ParseErrorKind::CharClass(CharClassError::Empty).at(Span::empty()));
+        }
+        Ok(Expr(Rule::CharClass(CharClass::new(items, Span::empty(), true))))
+    }
+
+    /// A range of integers, e.g. `range '0'-'255'`, in base 10
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is greater than `end`.
+    pub fn range(start: u64, end: u64) -> Self {
+        fn digits(n: u64) -> Box<[u8]> {
+            n.to_string().bytes().map(|b| b - b'0').collect()
+        }
+
+        assert!(start <= end, "range {start}-{end} is not ascending");
+        Expr(Rule::Range(Range::new(digits(start), digits(end), 10, Span::empty())))
+    }
+
+    /// A regex that is inserted verbatim, without escaping
+    pub fn regex(content: impl Into<String>) -> Self {
+        Expr(Rule::Regex(Regex::new(content.into(), Span::empty())))
+    }
+
+    /// The dot, matching any code point except line breaks
+    pub fn dot() -> Self {
+        Expr(Rule::Dot(Span::empty()))
+    }
+
+    /// `Grapheme`, matching an extended grapheme cluster
+    pub fn grapheme() -> Self {
+        Expr(Rule::Grapheme)
+    }
+
+    /// `Codepoint`, matching any code point
+    pub fn codepoint() -> Self {
+        Expr(Rule::Codepoint)
+    }
+
+    /// `^`, the start of the string
+    pub fn start() -> Self {
+        Self::boundary(BoundaryKind::Start)
+    }
+
+    /// `$`, the end of the string
+    pub fn end() -> Self {
+        Self::boundary(BoundaryKind::End)
+    }
+
+    /// `%`, a word boundary
+    pub fn word_boundary() -> Self {
+        Self::boundary(BoundaryKind::Word)
+    }
+
+    fn boundary(kind: BoundaryKind) -> Self {
+        Expr(Rule::Boundary(Boundary::new(kind, true, Span::empty())))
+    }
+
+    /// `recursion`, matching the whole expression recursively
+    pub fn recursion() -> Self {
+        Expr(Rule::Recursion(Recursion { span: Span::empty() }))
+    }
+
+    /// A reference to the capturing group with the given number
+    pub fn reference(number: u32) -> Self {
+        Self::reference_to(ReferenceTarget::Number(number))
+    }
+
+    /// A reference to the capturing group with the given name
+    pub fn named_reference(name: &str) -> Self {
+        Self::reference_to(ReferenceTarget::Named(name.to_string()))
+    }
+
+    fn reference_to(target: ReferenceTarget) -> Self {
+        Expr(Rule::Reference(Reference::new(target, Span::empty())))
+    }
+
+    /// A sequence of expressions, which must match one after the other
+    pub fn sequence(parts: impl IntoIterator<Item = Expr>) -> Self {
+        let parts = parts.into_iter().map(|expr| expr.0).collect();
+        Expr(Rule::Group(Group::new(parts, GroupKind::Normal, Span::empty())))
+    }
+
+    /// A list of alternatives; at least one of them has to match. Returns an
+    /// error if the list is empty.
+    pub fn alternatives(alternatives: impl IntoIterator<Item = Expr>) -> Result<Self, ParseError> {
+        let rules: Vec<_> = alternatives.into_iter().map(|expr| expr.0).collect();
+        if rules.is_empty() {
+            return Err(ParseErrorKind::Expected("expression").at(Span::empty()));
+        }
+        Ok(Expr(Rule::Alternation(Alternation::new(rules, Span::empty()))))
+    }
+
+    /// Appends an expression, so that it must match after this one
+    pub fn then(self, next: Expr) -> Self {
+        match self.0 {
+            Rule::Group(mut group) if group.kind == GroupKind::Normal => {
+                group.parts.push(next.0);
+                Expr(Rule::Group(group))
+            }
+            rule => Self::sequence([Expr(rule), next]),
+        }
+    }
+
+    /// Adds an alternative, which may match instead of this expression
+    pub fn or(self, alternative: Expr) -> Self {
+        match self.0 {
+            Rule::Alternation(mut alt) => {
+                alt.rules.push(alternative.0);
+                Expr(Rule::Alternation(alt))
+            }
+            rule => {
+                Expr(Rule::Alternation(Alternation::new(vec![rule, alternative.0], Span::empty())))
+            }
+        }
+    }
+
+    /// Repeats the expression greedily, e.g. `repeat(1..)` is equivalent to
+    /// `+` and `repeat(2..=4)` to `{2,4}`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn repeat(self, range: impl RangeBounds<u32>) -> Self {
+        self.repeat_with(range, Quantifier::DefaultGreedy)
+    }
+
+    /// Repeats the expression lazily, like [`Expr::repeat`] followed by `lazy`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn repeat_lazy(self, range: impl RangeBounds<u32>) -> Self {
+        self.repeat_with(range, Quantifier::Lazy)
+    }
+
+    /// Makes the expression optional, like `?`
+    pub fn optional(self) -> Self {
+        self.repeat(0..=1)
+    }
+
+    fn repeat_with(self, range: impl RangeBounds<u32>, quantifier: Quantifier) -> Self {
+        let lower_bound = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("repetition lower bound overflows"),
+            Bound::Unbounded => 0,
+        };
+        let upper_bound = match range.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n.checked_sub(1).expect("repetition range is empty")),
+            Bound::Unbounded => None,
+        };
+        assert!(upper_bound.is_none_or(|upper| lower_bound <= upper), "repetition range is empty");
+
+        let kind = RepetitionKind { lower_bound, upper_bound };
+        Expr(Rule::Repetition(Box::new(Repetition::new(self.0, kind, quantifier, Span::empty()))))
+    }
+
+    /// Wraps the expression in a numbered capturing group
+    pub fn capture(self) -> Self {
+        self.group(GroupKind::Capturing(Capture::new(None)))
+    }
+
+    /// Wraps the expression in a named capturing group. Returns an error if
+    /// the name is not a valid group name, i.e. if it isn't ASCII alphanumeric
+    /// or starts with a digit.
+    pub fn capture_named(self, name: &str) -> Result<Self, ParseError> {
+        if let Some(c) = name.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(ParseErrorKind::NonAsciiIdentAfterColon(c).at(Span::empty()));
+        }
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(ParseErrorKind::Expected("group name").at(Span::empty()));
+        }
+        if name.len() > 128 {
+            return Err(ParseErrorKind::GroupNameTooLong(name.len()).at(Span::empty()));
+        }
+        Ok(self.group(GroupKind::Capturing(Capture::new(Some(name)))))
+    }
+
+    /// Wraps the expression in an atomic group
+    pub fn atomic(self) -> Self {
+        self.group(GroupKind::Atomic)
+    }
+
+    fn group(self, kind: GroupKind) -> Self {
+        Expr(Rule::Group(Group::new(vec![self.0], kind, Span::empty())))
+    }
+
+    /// Negates the expression, like `!`. This is only allowed for character
+    /// classes, lookarounds, word boundaries and single characters; other
+    /// expressions produce an error when compiled.
+    pub fn negate(self) -> Self {
+        Expr(Rule::Negation(Box::new(Negation { rule: self.0, not_span: Span::empty() })))
+    }
+
+    /// A lookahead (`>>`), which checks that the expression matches at this
+    /// position without consuming it
+    pub fn lookahead(self) -> Self {
+        self.lookaround(LookaroundKind::Ahead)
+    }
+
+    /// A lookbehind (`<<`), which checks that the expression matches before
+    /// this position
+    pub fn lookbehind(self) -> Self {
+        self.lookaround(LookaroundKind::Behind)
+    }
+
+    fn lookaround(self, kind: LookaroundKind) -> Self {
+        Expr(Rule::Lookaround(Box::new(Lookaround::new(self.0, kind, Span::empty()))))
+    }
+}
+
+#[cfg(test)]
+use crate::options::CompileOptions;
+
+#[cfg(test)]
+fn compile(expr: Expr) -> Option<String> {
+    expr.compile("", CompileOptions::default()).0
+}
+
+#[test]
+fn builder_compiles() {
+    let expr = Expr::literal("a.b").then(Expr::dot()).or(Expr::codepoint()).capture();
+    assert_eq!(compile(expr).as_deref(), Some(r"(a\.b.|[\s\S])"));
+
+    let expr = Expr::class([ClassItem::range('a', 'f'), ClassItem::chars("xy")]).unwrap();
+    assert_eq!(compile(expr.repeat_lazy(2..=4)).as_deref(), Some("[a-fxy]{2,4}?"));
+
+    let expr = Expr::alternatives([Expr::literal("ab"), Expr::range(0, 255)]).unwrap();
+    assert_eq!(
+        compile(Expr::sequence([Expr::start(), expr, Expr::end()])).as_deref(),
+        Some("^(?:ab|0|1[0-9]{0,2}|2(?:[0-4][0-9]?|5[0-5]?|[6-9])?|[3-9][0-9]?)$")
+    );
+
+    let expr =
+        Expr::literal("a").capture_named("name").unwrap().then(Expr::named_reference("name"));
+    assert_eq!(compile(expr).as_deref(), Some(r"(?P<name>a)(?:\1)"));
+}
+
+#[test]
+fn builder_rejects_empty() {
+    let empty_class = ParseErrorKind::CharClass(CharClassError::Empty).at(Span::empty());
+    assert_eq!(Expr::class([]).err(), Some(empty_class.clone()));
+    assert_eq!(Expr::class([ClassItem::chars("")]).err(), Some(empty_class));
+
+    assert_eq!(
+        Expr::alternatives([]).err(),
+        Some(ParseErrorKind::Expected("expression").at(Span::empty()))
+    );
+
+    assert!(Expr::literal("a").capture_named("1a").is_err());
+    assert!(Expr::literal("a").capture_named("a-b").is_err());
+    assert!(ClassItem::named("foo").is_err());
+}
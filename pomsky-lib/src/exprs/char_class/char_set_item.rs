@@ -1,4 +1,5 @@
-use std::fmt;
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
 
 use crate::options::RegexFlavor;
 
@@ -45,7 +46,7 @@ impl RegexCompoundCharSet {
         self.intersections.iter().all(|set| set.may_intersect(other))
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
         if self.negative {
             buf.push_str("[^");
         } else {
@@ -57,7 +58,7 @@ impl RegexCompoundCharSet {
             if !is_first {
                 buf.push_str("&&");
             }
-            intersection.codegen(buf, flavor, true);
+            intersection.codegen(buf, flavor, portable, true);
             is_first = false;
         }
 
@@ -86,12 +87,18 @@ impl RegexCharSet {
         self.negative || other.negative || self.set.may_intersect(&other.set)
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, inside_compound: bool) {
+    pub(crate) fn codegen(
+        &self,
+        buf: &mut String,
+        flavor: RegexFlavor,
+        portable: bool,
+        inside_compound: bool,
+    ) {
         if self.set.len() == 1 {
             if let Some(range) = self.set.ranges().next() {
                 let (first, last) = range.as_chars();
                 if first == last && !self.negative {
-                    return literal::codegen_char_esc(first, buf, flavor);
+                    return literal::codegen_char_esc(first, buf, flavor, portable);
                 }
             } else if let Some(prop) = self.set.props().next() {
                 match prop {
@@ -127,13 +134,13 @@ impl RegexCharSet {
         for range in self.set.ranges() {
             let (first, last) = range.as_chars();
             if first == last {
-                literal::compile_char_esc_in_class(first, buf, is_first, flavor);
+                literal::compile_char_esc_in_class(first, buf, is_first, flavor, portable);
             } else {
-                literal::compile_char_esc_in_class(first, buf, is_first, flavor);
+                literal::compile_char_esc_in_class(first, buf, is_first, flavor, portable);
                 if range.first + 1 < range.last {
                     buf.push('-');
                 }
-                literal::compile_char_esc_in_class(last, buf, false, flavor);
+                literal::compile_char_esc_in_class(last, buf, false, flavor, portable);
             }
             is_first = false;
         }
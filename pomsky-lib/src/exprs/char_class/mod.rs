@@ -76,6 +76,8 @@
 //!   negated, the class is   removed and the negations cancel each other out:
 //!   `![!w]` = `\w`, `![!L]` = `\p{L}`.
 
+use alloc::string::ToString;
+
 use pomsky_syntax::Span;
 use pomsky_syntax::exprs::{
     Category, CharClass, CodeBlock, GroupItem, GroupName, OtherProperties, Script, ScriptExtension,
@@ -127,12 +129,16 @@ impl Compile for CharClass {
                             &mut set,
                         )?;
                     } else {
-                        named_class_to_regex_ascii(name, negative, options.flavor, span, &mut set)?;
+                        named_class_to_regex_ascii(name, negative, options, span, &mut set)?;
                     }
                 }
             }
         }
 
+        if set.props().any(|prop| matches!(prop, RegexCharSetItem::Property { .. })) {
+            options.require_version(Feature::UnicodeProp, self.span)?;
+        }
+
         // this makes it possible to use code points outside the BMP in .NET,
         // as long as there is only one in the character set
         if let Some(only_char) = set.try_into_char() {
@@ -175,19 +181,22 @@ pub fn is_ascii_only_in_flavor(group: GroupName, flavor: RegexFlavor) -> bool {
 fn named_class_to_regex_ascii(
     group: GroupName,
     negative: bool,
-    flavor: RegexFlavor,
+    options: CompileOptions,
     span: Span,
     set: &mut UnicodeSet,
 ) -> Result<(), CompileError> {
     // In JS, \W and \D can be used for negation because they're ascii-only
-    // Same goes for \W, \D and \S in RE2
-    if negative && !is_ascii_only_in_flavor(group, flavor) {
+    // Same goes for \W, \D and \S in RE2.
+    // When targeting several flavors, the shorthand must be ascii-only in all of them
+    let use_shorthand =
+        options.all_flavors().all(|(flavor, _)| is_ascii_only_in_flavor(group, flavor));
+    if negative && !use_shorthand {
         return Err(CompileErrorKind::NegativeShorthandInAsciiMode.at(span));
     }
 
     match group {
         GroupName::Word => {
-            if let RegexFlavor::JavaScript | RegexFlavor::RE2 = flavor {
+            if use_shorthand {
                 let s = if negative { RegexShorthand::NotWord } else { RegexShorthand::Word };
                 set.add_prop(RegexCharSetItem::Shorthand(s));
             } else {
@@ -199,7 +208,7 @@ fn named_class_to_regex_ascii(
             }
         }
         GroupName::Digit => {
-            if let RegexFlavor::JavaScript | RegexFlavor::RE2 = flavor {
+            if use_shorthand {
                 let s = if negative { RegexShorthand::NotDigit } else { RegexShorthand::Digit };
                 set.add_prop(RegexCharSetItem::Shorthand(s));
             } else {
@@ -208,7 +217,7 @@ fn named_class_to_regex_ascii(
             }
         }
         GroupName::Space => {
-            if let RegexFlavor::RE2 = flavor {
+            if use_shorthand {
                 let s = if negative { RegexShorthand::NotSpace } else { RegexShorthand::Space };
                 set.add_prop(RegexCharSetItem::Shorthand(s));
             } else {
@@ -323,7 +332,7 @@ fn named_class_to_regex_unicode(
         }
         GroupName::HorizSpace => {
             set.add_char('\t');
-            if flavor == RegexFlavor::Python {
+            if let RegexFlavor::Python | RegexFlavor::Postgres = flavor {
                 return Err(CompileErrorKind::Unsupported(Feature::UnicodeProp, flavor).at(span));
             } else {
                 set.add_prop(
@@ -338,7 +347,7 @@ fn named_class_to_regex_unicode(
             set.add_char('\u{2029}');
         }
 
-        _ if flavor == RegexFlavor::Python => {
+        _ if matches!(flavor, RegexFlavor::Python | RegexFlavor::Postgres) => {
             return Err(CompileErrorKind::Unsupported(Feature::UnicodeProp, flavor).at(span));
         }
         GroupName::Category(c) => {
@@ -359,14 +368,16 @@ fn named_class_to_regex_unicode(
 
             let set_extensions = match e {
                 ScriptExtension::Yes => match flavor {
-                    RegexFlavor::Rust | RegexFlavor::Pcre | RegexFlavor::JavaScript => {
-                        ScriptExtension::Yes
-                    }
+                    RegexFlavor::Rust
+                    | RegexFlavor::Pcre
+                    | RegexFlavor::JavaScript
+                    | RegexFlavor::PythonRegex => ScriptExtension::Yes,
                     RegexFlavor::Java
                     | RegexFlavor::DotNet
                     | RegexFlavor::Ruby
                     | RegexFlavor::Python
-                    | RegexFlavor::RE2 => {
+                    | RegexFlavor::RE2
+                    | RegexFlavor::Postgres => {
                         return Err(CompileErrorKind::Unsupported(
                             Feature::ScriptExtensions,
                             flavor,
@@ -385,7 +396,10 @@ fn named_class_to_regex_unicode(
             set.add_prop(RegexProperty::Script(s, set_extensions).negative_item(negative));
         }
         GroupName::CodeBlock(b) => match flavor {
-            RegexFlavor::DotNet | RegexFlavor::Java | RegexFlavor::Ruby => {
+            RegexFlavor::DotNet
+            | RegexFlavor::Java
+            | RegexFlavor::Ruby
+            | RegexFlavor::PythonRegex => {
                 match (flavor, b) {
                     (RegexFlavor::Java, CodeBlock::No_Block)
                     | (
@@ -422,7 +436,7 @@ fn named_class_to_regex_unicode(
             use OtherProperties as OP;
             use RegexFlavor as RF;
 
-            if let RF::JavaScript | RF::Rust | RF::Pcre | RF::Ruby = flavor {
+            if let RF::JavaScript | RF::Rust | RF::Pcre | RF::Ruby | RF::PythonRegex = flavor {
                 match (flavor, o) {
                     (RF::JavaScript, _) => {}
                     (_, OP::Changes_When_NFKC_Casefolded)
@@ -1,7 +1,14 @@
 //! Contains the [`Grapheme`] type, which matches a
 //! [Unicode grapheme](https://www.regular-expressions.info/unicode.html#grapheme).
 
-use crate::{compile::CompileResult, options::CompileOptions, regex::Regex};
+use crate::{
+    compile::CompileResult,
+    options::{CompileOptions, RegexFlavor},
+    regex::Regex,
+    unicode_set::UnicodeSet,
+};
+
+use super::char_class::RegexCharSet;
 
 /// The dot, matching anything except line breaks
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -9,7 +16,16 @@ use crate::{compile::CompileResult, options::CompileOptions, regex::Regex};
 pub(crate) struct Dot {}
 
 impl Dot {
-    pub(crate) fn compile(&self, _: CompileOptions) -> CompileResult {
+    pub(crate) fn compile(&self, options: CompileOptions) -> CompileResult {
+        // PostgreSQL needs `[^\n]`, which is equivalent to `.` in the flavors
+        // where the dot only excludes `\n`
+        if !options.additional_flavors.is_empty()
+            && options.all_flavors().any(|(flavor, _)| flavor == RegexFlavor::Postgres)
+        {
+            let mut set = UnicodeSet::new();
+            set.add_char('\n');
+            return Ok(Regex::CharSet(RegexCharSet::new(set).negate()));
+        }
         Ok(Regex::Dot)
     }
 }
@@ -18,7 +18,11 @@ pub(crate) struct Grapheme {}
 
 impl Grapheme {
     pub(crate) fn compile(&self, options: CompileOptions) -> CompileResult {
-        if matches!(options.flavor, RegexFlavor::Pcre | RegexFlavor::Java | RegexFlavor::Ruby) {
+        if matches!(
+            options.flavor,
+            RegexFlavor::Pcre | RegexFlavor::Java | RegexFlavor::Ruby | RegexFlavor::PythonRegex
+        ) {
+            options.require_version(Feature::Grapheme, Span::empty())?;
             Ok(Regex::Grapheme)
         } else {
             Err(CompileErrorKind::Unsupported(Feature::Grapheme, options.flavor).at(Span::empty()))
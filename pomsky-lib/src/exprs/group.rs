@@ -1,8 +1,14 @@
-use pomsky_syntax::exprs::{Capture, Group, GroupKind};
+use alloc::{string::String, vec::Vec};
+
+use pomsky_syntax::{
+    Span,
+    exprs::{Capture, Group, GroupKind},
+};
 
 use crate::{
     compile::{CompileResult, CompileState},
-    options::{CompileOptions, RegexFlavor},
+    diagnose::{CompileError, CompileErrorKind, NonPortable},
+    options::{CompileOptions, FlavorVersion, RegexFlavor},
     regex::Regex,
 };
 
@@ -18,24 +24,75 @@ impl Compile for Group {
             state.next_idx += 1;
         }
 
+        let kind = match &self.kind {
+            GroupKind::Capturing(Capture { name: Some(name) }) => {
+                RegexGroupKind::NamedCapture(name.clone(), name_syntax(options, self.span)?)
+            }
+            GroupKind::Capturing(Capture { name: None }) => RegexGroupKind::Capture,
+            GroupKind::Atomic => RegexGroupKind::Atomic,
+            GroupKind::Normal | GroupKind::Implicit => RegexGroupKind::Normal,
+        };
+
         Ok(Regex::Group(RegexGroup {
             parts: self
                 .parts
                 .iter()
                 .map(|part| part.compile(options, state))
                 .collect::<Result<_, _>>()?,
-            kind: match &self.kind {
-                GroupKind::Capturing(Capture { name: Some(name) }) => {
-                    RegexGroupKind::NamedCapture(name.clone())
-                }
-                GroupKind::Capturing(Capture { name: None }) => RegexGroupKind::Capture,
-                GroupKind::Atomic => RegexGroupKind::Atomic,
-                GroupKind::Normal | GroupKind::Implicit => RegexGroupKind::Normal,
-            },
+            kind,
         }))
     }
 }
 
+/// Chooses the syntax for a named capturing group. When targeting several
+/// flavors, `(?<name>...)` is preferred, since most flavors support it.
+fn name_syntax(options: CompileOptions, span: Span) -> Result<GroupNameSyntax, CompileError> {
+    // https://www.regular-expressions.info/named.html
+    let default = match options.flavor {
+        RegexFlavor::Python | RegexFlavor::PythonRegex | RegexFlavor::Pcre | RegexFlavor::Rust => {
+            GroupNameSyntax::P
+        }
+        RegexFlavor::DotNet
+        | RegexFlavor::Java
+        | RegexFlavor::Ruby
+        | RegexFlavor::JavaScript
+        | RegexFlavor::RE2 => GroupNameSyntax::AngleBrackets,
+        RegexFlavor::Postgres => {
+            unreachable!("named groups are rejected during validation for PostgreSQL")
+        }
+    };
+    if options.additional_flavors.is_empty() {
+        return Ok(default);
+    }
+
+    let mut only_p = None;
+    let mut only_angle_brackets = None;
+    for (flavor, version) in options.all_flavors() {
+        match flavor {
+            RegexFlavor::Python => only_p = only_p.or(Some(flavor)),
+            // `(?<name>...)` is supported since regex 1.9
+            RegexFlavor::Rust if version.is_some_and(|v| v < FlavorVersion::new(1, 9)) => {
+                only_p = only_p.or(Some(flavor));
+            }
+            RegexFlavor::DotNet
+            | RegexFlavor::Java
+            | RegexFlavor::Ruby
+            | RegexFlavor::JavaScript
+            | RegexFlavor::RE2 => only_angle_brackets = only_angle_brackets.or(Some(flavor)),
+            _ => {}
+        }
+    }
+    match (only_p, only_angle_brackets) {
+        (Some(a), Some(b)) => Err(CompileErrorKind::FlavorsDisagree {
+            construct: NonPortable::NamedGroup,
+            flavors: (a, b),
+        }
+        .at(span)),
+        (None, Some(_)) => Ok(GroupNameSyntax::AngleBrackets),
+        _ => Ok(default),
+    }
+}
+
 #[cfg_attr(feature = "dbg", derive(Debug))]
 pub(crate) struct RegexGroup {
     pub(crate) parts: Vec<Regex>,
@@ -46,50 +103,50 @@ pub(crate) struct RegexGroup {
 #[derive(PartialEq, Eq)]
 pub(crate) enum RegexGroupKind {
     Capture,
-    NamedCapture(String),
+    NamedCapture(String, GroupNameSyntax),
     Atomic,
     Normal,
 }
 
+#[cfg_attr(feature = "dbg", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GroupNameSyntax {
+    /// `(?P<name>...)`
+    P,
+    /// `(?<name>...)`
+    AngleBrackets,
+}
+
 impl RegexGroup {
     pub(crate) fn new(parts: Vec<Regex>, capture: RegexGroupKind) -> Self {
         Self { parts, kind: capture }
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
         match &self.kind {
-            RegexGroupKind::NamedCapture(name) => {
-                // https://www.regular-expressions.info/named.html
-                match flavor {
-                    RegexFlavor::Python | RegexFlavor::Pcre | RegexFlavor::Rust => {
-                        buf.push_str("(?P<");
-                    }
-                    RegexFlavor::DotNet
-                    | RegexFlavor::Java
-                    | RegexFlavor::Ruby
-                    | RegexFlavor::JavaScript
-                    | RegexFlavor::RE2 => {
-                        buf.push_str("(?<");
-                    }
-                }
+            RegexGroupKind::NamedCapture(name, syntax) => {
+                buf.push_str(match syntax {
+                    GroupNameSyntax::P => "(?P<",
+                    GroupNameSyntax::AngleBrackets => "(?<",
+                });
                 buf.push_str(name);
                 buf.push('>');
                 for part in &self.parts {
-                    part.codegen(buf, flavor);
+                    part.codegen(buf, flavor, portable);
                 }
                 buf.push(')');
             }
             RegexGroupKind::Capture => {
                 buf.push('(');
                 for part in &self.parts {
-                    part.codegen(buf, flavor);
+                    part.codegen(buf, flavor, portable);
                 }
                 buf.push(')');
             }
             RegexGroupKind::Atomic => {
                 buf.push_str("(?>");
                 for part in &self.parts {
-                    part.codegen(buf, flavor);
+                    part.codegen(buf, flavor, portable);
                 }
                 buf.push(')');
             }
@@ -102,7 +159,7 @@ impl RegexGroup {
                     if needs_parens {
                         buf.push_str("(?:");
                     }
-                    part.codegen(buf, flavor);
+                    part.codegen(buf, flavor, portable);
                     if needs_parens {
                         buf.push(')');
                     }
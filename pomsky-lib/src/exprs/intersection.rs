@@ -31,7 +31,11 @@ impl Compile for Intersection {
         })?;
 
         if let Regex::CompoundCharSet(_) = regex
-            && let RegexFlavor::DotNet | RegexFlavor::Python | RegexFlavor::RE2 = options.flavor
+            && let RegexFlavor::DotNet
+            | RegexFlavor::Python
+            | RegexFlavor::PythonRegex
+            | RegexFlavor::RE2
+            | RegexFlavor::Postgres = options.flavor
         {
             return Err(CompileErrorKind::Unsupported(
                 Feature::CharSetIntersection,
@@ -39,6 +43,9 @@ impl Compile for Intersection {
             )
             .at(self.span));
         }
+        if let Regex::CompoundCharSet(_) = regex {
+            options.require_version(Feature::CharSetIntersection, self.span)?;
+        }
 
         Ok(regex)
     }
@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use pomsky_syntax::exprs::Literal;
 
 use crate::{
@@ -9,7 +11,8 @@ use crate::{
 use super::Compile;
 
 impl Compile for Literal {
-    fn compile(&self, _: CompileOptions, _: &mut CompileState<'_>) -> CompileResult {
+    fn compile(&self, options: CompileOptions, state: &mut CompileState<'_>) -> CompileResult {
+        state.add_output_len(self.content.len(), options, self.span)?;
         Ok(Regex::Literal(self.content.clone()))
     }
 }
@@ -21,6 +24,7 @@ pub(crate) fn compile_char_esc_in_class(
     buf: &mut String,
     is_first: bool,
     flavor: RegexFlavor,
+    portable: bool,
 ) {
     match c {
         '\\' => buf.push_str(r#"\\"#),
@@ -30,13 +34,13 @@ pub(crate) fn compile_char_esc_in_class(
         '^' if is_first => buf.push_str(r#"\^"#),
         '&' if flavor != RegexFlavor::JavaScript => buf.push_str(r#"\&"#),
         '|' if flavor != RegexFlavor::JavaScript => buf.push_str(r#"\|"#),
-        c => compile_char(c, buf, flavor),
+        c => compile_char(c, buf, flavor, portable),
     }
 }
 
 /// Write a char to the output buffer with proper escaping. Assumes the char is
 /// not in a character class.
-pub(crate) fn codegen_char_esc(c: char, buf: &mut String, flavor: RegexFlavor) {
+pub(crate) fn codegen_char_esc(c: char, buf: &mut String, flavor: RegexFlavor, portable: bool) {
     match c {
         '\\' => buf.push_str(r#"\\"#),
         '[' => buf.push_str(r#"\["#),
@@ -52,15 +56,21 @@ pub(crate) fn codegen_char_esc(c: char, buf: &mut String, flavor: RegexFlavor) {
         '|' => buf.push_str(r#"\|"#),
         '^' => buf.push_str(r#"\^"#),
         '$' => buf.push_str(r#"\$"#),
-        c => compile_char(c, buf, flavor),
+        c => compile_char(c, buf, flavor, portable),
     }
 }
 
 /// Write a char to the output buffer. This escapes characters that are neither
 /// alphanumeric, nor printable ASCII characters. It does _not_ escape
 /// characters like `(` or `]` that have a special meaning.
-pub(crate) fn compile_char(c: char, buf: &mut String, flavor: RegexFlavor) {
-    use std::fmt::Write;
+///
+/// If `portable` is true, the regex is compiled for several flavors at once.
+/// Each flavor escapes non-ASCII characters differently, so they are written
+/// as-is, which all flavors support. The exception is .NET, which matches
+/// UTF-16 code units, so characters outside the BMP are still escaped as
+/// surrogate pairs.
+pub(crate) fn compile_char(c: char, buf: &mut String, flavor: RegexFlavor, portable: bool) {
+    use core::fmt::Write;
 
     match c {
         '\n' => buf.push_str("\\n"),
@@ -72,9 +82,15 @@ pub(crate) fn compile_char(c: char, buf: &mut String, flavor: RegexFlavor) {
         // not supported in JS:
         // '\x07' => buf.push_str("\\a"),
         ' ' => buf.push(' '),
+        // With `standard_conforming_strings` (the default since PostgreSQL 9.1), string
+        // literals don't interpret backslashes, so only the quote needs to be escaped
+        '\'' if flavor == RegexFlavor::Postgres => buf.push_str("\\u0027"),
         _ if c.is_ascii() => {
             if c.is_ascii_graphic() {
                 buf.push(c);
+            } else if flavor == RegexFlavor::Postgres {
+                // `\x` consumes all following hex digits in PostgreSQL
+                write!(buf, "\\u{:04X}", c as u8).unwrap();
             } else {
                 write!(buf, "\\x{:02X}", c as u8).unwrap();
             }
@@ -82,13 +98,20 @@ pub(crate) fn compile_char(c: char, buf: &mut String, flavor: RegexFlavor) {
         _ if c.is_alphanumeric() && c.len_utf16() == 1 => {
             buf.push(c);
         }
-        _ if c as u32 <= 0xFF && flavor != RegexFlavor::Ruby => {
+        _ if portable && (flavor != RegexFlavor::DotNet || c.len_utf16() == 1) => {
+            buf.push(c);
+        }
+        _ if c as u32 <= 0xFF && !matches!(flavor, RegexFlavor::Ruby | RegexFlavor::Postgres) => {
             write!(buf, "\\x{:02X}", c as u32).unwrap();
         }
         _ if c as u32 <= 0xFFFF && !matches!(flavor, RegexFlavor::Pcre | RegexFlavor::RE2) => {
             write!(buf, "\\u{:04X}", c as u32).unwrap();
         }
-        _ if matches!(flavor, RegexFlavor::Python) => {
+        _ if matches!(
+            flavor,
+            RegexFlavor::Python | RegexFlavor::PythonRegex | RegexFlavor::Postgres
+        ) =>
+        {
             write!(buf, "\\U{:08X}", c as u32).unwrap();
         }
         _ if matches!(flavor, RegexFlavor::DotNet) => {
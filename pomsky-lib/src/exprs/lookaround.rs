@@ -1,3 +1,5 @@
+use alloc::{boxed::Box, string::String};
+
 use pomsky_syntax::exprs::{Lookaround, LookaroundKind};
 
 use crate::{
@@ -70,14 +72,14 @@ impl RegexLookaround {
         Ok(RegexLookaround { content, kind })
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
         buf.push_str(match self.kind {
             LookaroundKind::Ahead => "(?=",
             LookaroundKind::Behind => "(?<=",
             LookaroundKind::AheadNegative => "(?!",
             LookaroundKind::BehindNegative => "(?<!",
         });
-        self.content.codegen(buf, flavor);
+        self.content.codegen(buf, flavor, portable);
         buf.push(')');
     }
 }
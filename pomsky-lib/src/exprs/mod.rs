@@ -1,15 +1,21 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+
 use crate::{
     capturing_groups::CapturingGroupsCollector,
     compile::{CompileResult, CompileState},
-    diagnose::{CompileErrorKind, Diagnostic},
-    options::CompileOptions,
-    regex::Count,
+    diagnose::{AllowedLints, CompileErrorKind, Diagnostic, DiagnosticCode},
+    explain::{Explainer, Explanation},
+    format::{FormatOptions, PrettyPrinter},
+    ir,
+    options::{CompileOptions, Limits, RegexFlavor},
+    regex::{Count, Regex},
     validation::Validator,
-    visitor::RuleVisitor,
+    visitor::{RuleVisitor, RuleVisitorMut},
 };
 
 pub(crate) mod alternation;
 pub(crate) mod boundary;
+pub(crate) mod builder;
 pub(crate) mod char_class;
 pub(crate) mod codepoint;
 pub(crate) mod dot;
@@ -18,6 +24,7 @@ pub(crate) mod group;
 pub(crate) mod intersection;
 pub(crate) mod literal;
 pub(crate) mod lookaround;
+pub(crate) mod portable;
 pub(crate) mod range;
 pub(crate) mod recursion;
 pub(crate) mod reference;
@@ -39,27 +46,217 @@ pub(crate) trait Compile {
 }
 
 /// A parsed pomsky expression, which might contain more sub-expressions.
+///
+/// With the `serde` feature, it can be serialized. The serialized form has the
+/// structure of the syntax tree in `pomsky_syntax::exprs`, including spans.
 #[derive(Clone)]
 #[cfg_attr(not(feature = "dbg"), derive(Debug))]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Expr(Rule);
 
 impl Expr {
     /// Parse a `Expr` without generating code.
     ///
     /// The parsed `Expr` can be displayed with `Debug` if the `dbg` feature is
-    /// enabled.
+    /// enabled. Warnings silenced with an `allow` statement are not returned,
+    /// but [`CompileOptions::lints`] are not applied, since they are only
+    /// passed to the compiler.
     pub fn parse(input: &str) -> (Option<Self>, impl Iterator<Item = Diagnostic> + '_) {
-        let (rule, diagnostics) = pomsky_syntax::parse(input, 256);
-        (rule.map(Expr), diagnostics.into_iter().map(|d| Diagnostic::from_parser(&d, input)))
+        Self::parse_with_limits(input, Limits::default())
+    }
+
+    /// Parse a `Expr` like [`Expr::parse`], but with a custom nesting limit
+    /// from [`Limits::max_nesting`].
+    pub fn parse_with_limits(
+        input: &str,
+        limits: Limits,
+    ) -> (Option<Self>, impl Iterator<Item = Diagnostic> + '_) {
+        let (rule, diagnostics) = pomsky_syntax::parse(input, limits.max_nesting);
+        let allowed = rule.as_ref().map(AllowedLints::collect);
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|d| Diagnostic::from_parser(&d, input))
+            .filter(move |d| !allowed.as_ref().is_some_and(|a| a.is_allowed(d)));
+        (rule.map(Expr), diagnostics)
+    }
+
+    /// Formats the expression in the canonical style. `input` must be the
+    /// source code it was parsed from, or an empty string if it was built
+    /// programmatically.
+    pub fn format(&self, input: &str, options: &FormatOptions) -> String {
+        PrettyPrinter::new(input, options).print(&self.0)
+    }
+
+    /// Explains the expression in English. `input` must be the source code
+    /// it was parsed from, or an empty string if it was built
+    /// programmatically. See [`explain`](crate::explain::explain) for details.
+    pub fn explain(&self, input: &str, flavor: RegexFlavor) -> Explanation {
+        let no_span = Span::empty();
+
+        let start = Rule::Boundary(Boundary::new(BoundaryKind::Start, true, no_span));
+        let end = Rule::Boundary(Boundary::new(BoundaryKind::End, true, no_span));
+        let grapheme = Rule::Grapheme;
+        let codepoint = Rule::Codepoint;
+
+        let builtins = vec![
+            ("Start", &start),
+            ("End", &end),
+            ("Grapheme", &grapheme),
+            ("G", &grapheme),
+            ("Codepoint", &codepoint),
+            ("C", &codepoint),
+        ];
+        Explainer::new(input, flavor, &self.0, builtins).explain(&self.0)
+    }
+
+    /// Walks the syntax tree with the given [visitor](crate::visitor). The
+    /// traversal stops at the first error returned by the visitor.
+    ///
+    /// ```
+    /// use pomsky::{Expr, visitor::RuleVisitor};
+    /// use pomsky_syntax::exprs::{Capture, Group, GroupKind};
+    ///
+    /// #[derive(Default)]
+    /// struct UnnamedGroups(usize);
+    ///
+    /// impl RuleVisitor<()> for UnnamedGroups {
+    ///     fn visit_group(&mut self, group: &Group) -> Result<(), ()> {
+    ///         if let GroupKind::Capturing(Capture { name: None }) = group.kind {
+    ///             self.0 += 1;
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let (expr, _) = Expr::parse(":('a') :name('b') :('c')");
+    /// let mut visitor = UnnamedGroups::default();
+    /// expr.unwrap().visit(&mut visitor).unwrap();
+    /// assert_eq!(visitor.0, 2);
+    /// ```
+    pub fn visit<V: RuleVisitor<E>, E>(&self, visitor: &mut V) -> Result<(), E> {
+        crate::visitor::visit(&self.0, visitor)
+    }
+
+    /// Walks the syntax tree with the given [mutating
+    /// visitor](crate::visitor::RuleVisitorMut), which can modify it in place.
+    pub fn visit_mut<V: RuleVisitorMut<E>, E>(&mut self, visitor: &mut V) -> Result<(), E> {
+        crate::visitor::visit_mut(&mut self.0, visitor)
     }
 
     /// Compile a `Expr` that has been parsed, to a regex
+    ///
+    /// If [`CompileOptions::additional_flavors`] is not empty, the regex must
+    /// be valid and identical in all of the flavors, otherwise compilation
+    /// fails.
     pub fn compile(
         &self,
         input: &str,
         options: CompileOptions,
     ) -> (Option<String>, Vec<Diagnostic>) {
+        let (output, diagnostics) = self.compile_impl(input, options);
+        (output.map(|(regex, _)| regex), diagnostics)
+    }
+
+    /// Compile a `Expr` that has been parsed, to a regex and its intermediate
+    /// representation.
+    ///
+    /// The [IR](crate::ir) describes the regex after variables were inlined
+    /// and the flavor's constraints were applied. If
+    /// [`CompileOptions::additional_flavors`] is not empty, it is the IR of the
+    /// primary flavor.
+    pub fn compile_with_ir(
+        &self,
+        input: &str,
+        options: CompileOptions,
+    ) -> (Option<(String, ir::Regex)>, Vec<Diagnostic>) {
+        let (output, diagnostics) = self.compile_impl(input, options);
+        (output.map(|(regex, ir)| (regex, ir::Regex::from(&ir))), diagnostics)
+    }
+
+    /// Compiles the expression, then applies `allow` statements and
+    /// [`CompileOptions::lints`] to the warnings. Compilation fails if a
+    /// warning is denied.
+    fn compile_impl(
+        &self,
+        input: &str,
+        options: CompileOptions,
+    ) -> (Option<(String, Regex)>, Vec<Diagnostic>) {
+        let (mut output, mut diagnostics) = self.compile_flavors(input, options);
+        let allowed = AllowedLints::collect(&self.0);
+        diagnostics.retain(|d| !allowed.is_allowed(d));
+        if options.lints.apply(&mut diagnostics) {
+            output = None;
+        }
+        (output, diagnostics)
+    }
+
+    fn compile_flavors(
+        &self,
+        input: &str,
+        options: CompileOptions,
+    ) -> (Option<(String, Regex)>, Vec<Diagnostic>) {
+        if options.additional_flavors.is_empty() {
+            return self.compile_single(input, options);
+        }
+
+        // every compilation knows about all targeted flavors, so flavor-dependent
+        // constructs are rejected or rewritten the same way in each of them
+        let targets = options.additional_flavors.add(options.flavor, options.flavor_version);
+        let primary = CompileOptions { additional_flavors: targets, ..options };
+        let others = targets
+            .iter()
+            .filter(|&(flavor, _)| flavor != options.flavor)
+            .map(|(flavor, flavor_version)| CompileOptions { flavor, flavor_version, ..primary });
+
+        let mut output: Option<(RegexFlavor, String, Regex)> = None;
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        let mut failed = false;
+
+        for options in core::iter::once(primary).chain(others) {
+            let (regex, new_diagnostics) = self.compile_single(input, options);
+            for d in new_diagnostics {
+                // a non-portable construct is reported by every flavor; keep the first
+                let is_duplicate = |prev: &Diagnostic| {
+                    prev.span == d.span
+                        && (prev.msg == d.msg
+                            || prev.code == d.code
+                                && d.code == Some(DiagnosticCode::IncompatibleFlavors))
+                };
+                if !diagnostics.iter().any(is_duplicate) {
+                    diagnostics.push(d);
+                }
+            }
+
+            match (regex, &output) {
+                (None, _) => failed = true,
+                (Some((regex, ir)), None) => output = Some((options.flavor, regex, ir)),
+                (Some((regex, _)), Some((first_flavor, first_regex, _))) => {
+                    if !failed && regex != *first_regex {
+                        let error = CompileErrorKind::IncompatibleFlavors {
+                            flavors: (*first_flavor, options.flavor),
+                            regexes: Box::new((first_regex.clone(), regex)),
+                        }
+                        .at(self.0.span());
+                        diagnostics.push(error.diagnostic(input));
+                        failed = true;
+                    }
+                }
+            }
+        }
+
+        if failed {
+            (None, diagnostics)
+        } else {
+            (output.map(|(_, regex, ir)| (regex, ir)), diagnostics)
+        }
+    }
+
+    fn compile_single(
+        &self,
+        input: &str,
+        options: CompileOptions,
+    ) -> (Option<(String, Regex)>, Vec<Diagnostic>) {
         let mut validator = Validator::new(options);
         if let Err(e) = validator.visit_rule(&self.0) {
             return (None, vec![e.diagnostic(input)]);
@@ -101,9 +298,15 @@ impl Expr {
 
         let mut buf = String::new();
         if count != Count::Zero {
-            compiled.codegen(&mut buf, options.flavor);
+            compiled.codegen(&mut buf, options.flavor, !options.additional_flavors.is_empty());
+        }
+        if let Some(max) = options.limits.max_output_len
+            && buf.len() > max as usize
+        {
+            let error = CompileErrorKind::OutputIsTooBig(max).at(self.0.span());
+            return (None, vec![error.diagnostic(input)]);
         }
-        (Some(buf), state.diagnostics)
+        (Some((buf, compiled)), state.diagnostics)
     }
 
     /// Extracts top-level all unit tests from the Pomsky expression
@@ -133,11 +336,25 @@ impl Expr {
     }
 
     /// Parse a string to a `Expr` and compile it to a regex.
+    ///
+    /// [`CompileOptions::lints`] are applied to all warnings, so compilation
+    /// fails if a warning is denied.
     pub fn parse_and_compile(
         input: &str,
         options: CompileOptions,
     ) -> (Option<String>, Vec<Diagnostic>, Vec<Test>) {
-        match Self::parse(input) {
+        let (mut output, mut diagnostics, tests) = Self::parse_and_compile_impl(input, options);
+        if options.lints.apply(&mut diagnostics) {
+            output = None;
+        }
+        (output, diagnostics, tests)
+    }
+
+    fn parse_and_compile_impl(
+        input: &str,
+        options: CompileOptions,
+    ) -> (Option<String>, Vec<Diagnostic>, Vec<Test>) {
+        match Self::parse_with_limits(input, options.limits) {
             (Some(parsed), warnings1) => match parsed.compile(input, options) {
                 (Some(compiled), warnings2) => {
                     let mut diagnostics =
@@ -159,6 +376,13 @@ impl Expr {
     }
 }
 
+impl From<Rule> for Expr {
+    /// Wraps a syntax tree, e.g. one returned by [`pomsky_syntax::parse`].
+    fn from(rule: Rule) -> Self {
+        Expr(rule)
+    }
+}
+
 #[cfg(feature = "dbg")]
 impl core::fmt::Debug for Expr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
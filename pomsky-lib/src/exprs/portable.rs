@@ -0,0 +1,159 @@
+//! Checks that an expression compiled for several flavors at once means the
+//! same thing in all of them. See [`CompileOptions::additional_flavors`].
+
+use alloc::{boxed::Box, string::String};
+
+use pomsky_syntax::exprs::{BoundaryKind, CharClass, GroupItem, GroupName, Rule};
+
+use crate::{
+    compile::{CompileResult, CompileState},
+    diagnose::{CompileError, CompileErrorKind, NonPortable},
+    options::{CompileOptions, RegexFlavor},
+    regex::Regex,
+};
+
+use super::{Compile, codepoint::Codepoint, dot::Dot, grapheme::Grapheme};
+
+/// Checks that a construct that was compiled to `regex` for the targeted flavor
+/// compiles to the same regex, and matches the same things, in the additional
+/// flavors.
+///
+/// Only constructs without sub-expressions are checked here, since their output
+/// depends on the flavor. Named capturing groups pick a syntax supported by all
+/// flavors when they are compiled, and non-ASCII characters are written as-is
+/// instead of escaping them.
+pub(crate) fn check<'c>(
+    rule: &'c Rule,
+    regex: &Regex,
+    options: CompileOptions,
+    state: &mut CompileState<'c>,
+) -> Result<(), CompileError> {
+    let construct = match rule {
+        Rule::Dot(_) => Some(NonPortable::Dot),
+        Rule::CharClass(c) if c.unicode_aware && contains_shorthand(c) => {
+            Some(NonPortable::Shorthand)
+        }
+        Rule::Boundary(b)
+            if matches!(
+                b.kind,
+                BoundaryKind::Word
+                    | BoundaryKind::NotWord
+                    | BoundaryKind::WordStart
+                    | BoundaryKind::WordEnd
+            ) =>
+        {
+            Some(NonPortable::WordBoundary)
+        }
+        Rule::Literal(_)
+        | Rule::CharClass(_)
+        | Rule::Boundary(_)
+        | Rule::Grapheme
+        | Rule::Codepoint
+        | Rule::Reference(_)
+        | Rule::Recursion(_) => None,
+        _ => return Ok(()),
+    };
+
+    let flavor = options.flavor;
+    if let Some(construct) = construct
+        && let Some((other, _)) =
+            options.all_flavors().find(|&(other, _)| construct_differs(construct, flavor, other))
+    {
+        return Err(CompileErrorKind::FlavorsDisagree { construct, flavors: (flavor, other) }
+            .at(rule.span()));
+    }
+
+    let mut expected = String::new();
+    regex.codegen(&mut expected, flavor, true);
+
+    for (other, flavor_version) in options.all_flavors().skip(1) {
+        let other_options = CompileOptions { flavor: other, flavor_version, ..options };
+
+        // diagnostics are reported by the compilation for the other flavor,
+        // and the output length is only counted once
+        let diagnostics_len = state.diagnostics.len();
+        let output_len = state.output_len;
+        let compiled = compile_leaf(rule, other_options, state);
+        state.diagnostics.truncate(diagnostics_len);
+        state.output_len = output_len;
+
+        // errors are reported by the compilation for the other flavor as well
+        let Ok(compiled) = compiled else { continue };
+        let mut actual = String::new();
+        compiled.codegen(&mut actual, other, true);
+
+        if actual != expected {
+            return Err(CompileErrorKind::IncompatibleFlavors {
+                flavors: (flavor, other),
+                regexes: Box::new((expected, actual)),
+            }
+            .at(rule.span()));
+        }
+    }
+    Ok(())
+}
+
+/// Compiles a construct checked by [`check`] without going through
+/// [`Rule::compile`], so it isn't checked again or counted twice
+fn compile_leaf<'c>(
+    rule: &'c Rule,
+    options: CompileOptions,
+    state: &mut CompileState<'c>,
+) -> CompileResult {
+    match rule {
+        Rule::Literal(l) => l.compile(options, state),
+        Rule::CharClass(c) => c.compile(options, state),
+        Rule::Boundary(b) => b.compile(options, state),
+        Rule::Reference(r) => r.compile(options, state),
+        Rule::Recursion(r) => r.compile(options, state),
+        Rule::Grapheme => Grapheme {}.compile(options),
+        Rule::Codepoint => Codepoint {}.compile(options),
+        Rule::Dot(_) => Dot {}.compile(options),
+        _ => unreachable!("only leaf constructs are checked"),
+    }
+}
+
+fn contains_shorthand(class: &CharClass) -> bool {
+    class.inner.iter().any(|item| {
+        matches!(
+            item,
+            GroupItem::Named { name: GroupName::Word | GroupName::Digit | GroupName::Space, .. }
+        )
+    })
+}
+
+/// Returns whether the construct matches different things in the two flavors
+fn construct_differs(construct: NonPortable, a: RegexFlavor, b: RegexFlavor) -> bool {
+    match construct {
+        NonPortable::Dot => dot_excludes(a) != dot_excludes(b),
+        NonPortable::Shorthand | NonPortable::WordBoundary => {
+            shorthands_are_unicode_aware(a) != shorthands_are_unicode_aware(b)
+        }
+        NonPortable::NamedGroup => false,
+    }
+}
+
+/// The line breaks not matched by the dot
+/// (see <https://www.regular-expressions.info/dot.html>)
+fn dot_excludes(flavor: RegexFlavor) -> &'static str {
+    match flavor {
+        RegexFlavor::JavaScript => "\n\r\u{2028}\u{2029}",
+        RegexFlavor::Java => "\n\r\u{85}\u{2028}\u{2029}",
+        _ => "\n",
+    }
+}
+
+/// Whether `\w`, `\d` and `\s` match non-ASCII characters, which also affects
+/// word boundaries. In JavaScript and RE2, pomsky emulates them with Unicode
+/// properties where needed.
+fn shorthands_are_unicode_aware(flavor: RegexFlavor) -> bool {
+    match flavor {
+        RegexFlavor::Pcre | RegexFlavor::Java | RegexFlavor::Ruby | RegexFlavor::Postgres => false,
+        RegexFlavor::Python
+        | RegexFlavor::PythonRegex
+        | RegexFlavor::DotNet
+        | RegexFlavor::JavaScript
+        | RegexFlavor::Rust
+        | RegexFlavor::RE2 => true,
+    }
+}
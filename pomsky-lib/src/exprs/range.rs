@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use core::cmp::Ordering;
 
 use pomsky_syntax::exprs::{Range, RepetitionKind};
 
@@ -338,8 +339,8 @@ enum Rule {
 }
 
 #[cfg(any())]
-impl std::fmt::Debug for Rule {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Rule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Empty => write!(f, "Empty"),
             Self::Class(Class { start, end }) => write!(f, "[{start}-{end}]"),
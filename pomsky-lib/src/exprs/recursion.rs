@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use pomsky_syntax::exprs::Recursion;
 
 use crate::{
@@ -14,6 +16,9 @@ impl Compile for Recursion {
     }
 }
 
-pub(crate) fn codegen(buf: &mut String, _flavor: RegexFlavor) {
-    buf.push_str("\\g<0>")
+pub(crate) fn codegen(buf: &mut String, flavor: RegexFlavor) {
+    match flavor {
+        RegexFlavor::PythonRegex => buf.push_str("(?R)"),
+        _ => buf.push_str("\\g<0>"),
+    }
 }
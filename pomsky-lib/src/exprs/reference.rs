@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use pomsky_syntax::exprs::{Reference, ReferenceTarget};
 
 use crate::{
@@ -102,7 +104,10 @@ impl Compile for Reference {
                 Err(CompileErrorKind::Unsupported(direction.into(), options.flavor).at(self.span))
             }
 
-            RegexFlavor::JavaScript | RegexFlavor::Python | RegexFlavor::Ruby
+            RegexFlavor::JavaScript
+            | RegexFlavor::Python
+            | RegexFlavor::Ruby
+            | RegexFlavor::Postgres
                 if direction == ReferenceDirection::Forwards =>
             {
                 Err(CompileErrorKind::Unsupported(Feature::ForwardReference, options.flavor)
@@ -137,7 +142,7 @@ pub(crate) enum RegexReference {
 
 impl RegexReference {
     pub(crate) fn codegen(&self, buf: &mut String) {
-        use std::fmt::Write;
+        use core::fmt::Write;
 
         match self {
             &RegexReference::Number(number) => {
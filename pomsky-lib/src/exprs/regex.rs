@@ -9,7 +9,8 @@ use crate::{
 use super::Compile;
 
 impl Compile for RegexLiteral {
-    fn compile(&self, _: CompileOptions, _: &mut CompileState<'_>) -> CompileResult {
+    fn compile(&self, options: CompileOptions, state: &mut CompileState<'_>) -> CompileResult {
+        state.add_output_len(self.content.len(), options, self.span)?;
         Ok(Regex::Unescaped(self.content.clone()))
     }
 }
@@ -1,3 +1,5 @@
+use alloc::{boxed::Box, string::String};
+
 use pomsky_syntax::exprs::{Quantifier, Repetition, RepetitionKind};
 
 use crate::{
@@ -49,8 +51,8 @@ impl RegexRepetition {
         Self { content, kind, quantifier }
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
-        use std::fmt::Write;
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
+        use core::fmt::Write;
 
         if let Regex::Literal(l) = &self.content
             && l.is_empty()
@@ -60,10 +62,10 @@ impl RegexRepetition {
 
         if self.content.needs_parens_before_repetition(flavor) {
             buf.push_str("(?:");
-            self.content.codegen(buf, flavor);
+            self.content.codegen(buf, flavor, portable);
             buf.push(')');
         } else {
-            self.content.codegen(buf, flavor);
+            self.content.codegen(buf, flavor, portable);
         }
 
         let omit_lazy = match self.kind {
@@ -1,13 +1,15 @@
-use pomsky_syntax::exprs::Rule;
+use pomsky_syntax::{diagnose::ParseErrorKind, exprs::Rule};
 
 use crate::{
     compile::{CompileResult, CompileState},
+    diagnose::CompileErrorKind,
     options::CompileOptions,
     regex::Regex,
 };
 
 use super::{
-    Compile, char_class::check_char_class_empty, codepoint::Codepoint, dot::Dot, grapheme::Grapheme,
+    Compile, char_class::check_char_class_empty, codepoint::Codepoint, dot::Dot,
+    grapheme::Grapheme, portable,
 };
 
 impl Compile for Rule {
@@ -16,13 +18,20 @@ impl Compile for Rule {
         options: CompileOptions,
         state: &mut CompileState<'c>,
     ) -> CompileResult {
-        match self {
+        state.compiled_nodes += 1;
+        if let Some(max) = options.limits.max_compiled_nodes
+            && state.compiled_nodes > max
+        {
+            return Err(CompileErrorKind::TooManyCompiledNodes(max).at(self.span()));
+        }
+
+        let regex = match self {
             Rule::Literal(l) => l.compile(options, state),
             Rule::CharClass(c) => c.compile(options, state),
             Rule::Group(g) => g.compile(options, state),
             Rule::Grapheme => Grapheme {}.compile(options),
             Rule::Codepoint => Codepoint {}.compile(options),
-            Rule::Dot => Dot {}.compile(options),
+            Rule::Dot(_) => Dot {}.compile(options),
             Rule::Alternation(a) => a.compile(options, state),
             Rule::Intersection(a) => a.compile(options, state),
             Rule::Repetition(r) => r.compile(options, state),
@@ -37,6 +46,9 @@ impl Compile for Rule {
             Rule::Regex(r) => r.compile(options, state),
             Rule::StmtExpr(m) => m.compile(options, state),
             Rule::Recursion(r) => r.compile(options, state),
+            &Rule::Error(span) => {
+                Err(CompileErrorKind::ParseError(ParseErrorKind::Expected("expression")).at(span))
+            }
             Rule::Negation(n) => {
                 let span = n.rule.span();
                 let regex = n
@@ -48,6 +60,11 @@ impl Compile for Rule {
                 }
                 Ok(regex)
             }
+        }?;
+
+        if !options.additional_flavors.is_empty() {
+            portable::check(self, &regex, options, state)?;
         }
+        Ok(regex)
     }
 }
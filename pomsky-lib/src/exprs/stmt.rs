@@ -14,7 +14,9 @@ impl Compile for StmtExpr {
         state: &mut CompileState<'c>,
     ) -> CompileResult {
         match &self.stmt {
-            Stmt::Enable(..) | Stmt::Disable(..) => self.rule.compile(options, state),
+            Stmt::Enable(..) | Stmt::Disable(..) | Stmt::Allow(_) => {
+                self.rule.compile(options, state)
+            }
             Stmt::Let(r#let) => {
                 state.variables.push((&r#let.name, &r#let.rule));
                 let res = self.rule.compile(options, state)?;
@@ -33,6 +33,12 @@ impl Compile for Variable {
                 _ => {}
             }
 
+            if let Some(max) = options.limits.max_variable_depth
+                && state.current_vars.len() >= max as usize
+            {
+                return Err(CompileErrorKind::VariablesTooDeep(max).at(self.span));
+            }
+
             state.current_vars.insert(i);
             let res = rule.compile(options, state)?;
             state.current_vars.remove(&i);
@@ -1,6 +1,6 @@
 //! Contains pomsky features that can be individually enabled and disabled.
 
-use std::fmt;
+use core::fmt;
 
 use pomsky_syntax::Span;
 
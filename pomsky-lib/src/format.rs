@@ -0,0 +1,28 @@
+//! Formatting of pomsky expressions in a canonical style
+//!
+//! Comments and string literals are preserved, and blank lines between
+//! statements are kept (but at most one). Lines are broken when they exceed
+//! [`FormatOptions::max_width`].
+
+use alloc::{string::String, vec::Vec};
+
+pub use pomsky_syntax::format::{FormatOptions, PrettyPrinter};
+
+use crate::diagnose::Diagnostic;
+
+/// Parses and formats a pomsky expression. Returns `None` if it contains a
+/// syntax error.
+///
+/// ### Example
+///
+/// ```
+/// use pomsky::format::{FormatOptions, format};
+///
+/// let input = "let  digit=[ '0'-'9' ] ;\n\n\n:(digit+)# number";
+/// let (formatted, _) = format(input, &FormatOptions::default());
+/// assert_eq!(formatted.as_deref(), Some("let digit = ['0'-'9'];\n\n:(digit+) # number\n"));
+/// ```
+pub fn format(input: &str, options: &FormatOptions) -> (Option<String>, Vec<Diagnostic>) {
+    let (formatted, diagnostics) = pomsky_syntax::format::format(input, options);
+    (formatted, diagnostics.iter().map(|d| Diagnostic::from_parser(d, input)).collect())
+}
@@ -0,0 +1,330 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use super::parse::{
+    Boundary, Class, ClassItem, GroupKind, Lookaround, Node, Quantifier, Reference, Repetition,
+};
+
+/// Minimum length of a repeated expression that is extracted into a variable
+const MIN_VARIABLE_LEN: usize = 12;
+
+/// How tightly an emitted pomsky expression binds, used to add parentheses
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+    Alternation,
+    Concat,
+    Repetition,
+    Atom,
+}
+
+/// Emits a pomsky expression for the parsed regex. Expressions that appear
+/// more than once are extracted into `let` statements.
+pub(super) fn emit(root: &Node<'_>) -> String {
+    let vars = extract_variables(root);
+    let emitter = Emitter { vars: &vars };
+
+    let mut buf = String::new();
+    // variables are declared in reverse order, since a variable extracted later
+    // may be used by one extracted earlier, but not the other way around
+    for (i, (node, _)) in vars.iter().enumerate().rev() {
+        let (body, _) = emitter.emit_node(node, false);
+        let _ = writeln!(buf, "let {} = {body};", emitter.var_name(i));
+    }
+    if !vars.is_empty() {
+        buf.push('\n');
+    }
+    buf += &emitter.emit_node(root, true).0;
+    buf
+}
+
+struct Emitter<'a, 'n, 'i> {
+    vars: &'a [(&'n Node<'i>, String)],
+}
+
+impl Emitter<'_, '_, '_> {
+    fn var_name(&self, index: usize) -> String {
+        format!("part{}", self.vars.len() - index)
+    }
+
+    /// Emits a node. If `substitute` is true and the node was extracted into a
+    /// variable, the variable name is emitted instead.
+    fn emit_node(&self, node: &Node<'_>, substitute: bool) -> (String, Prec) {
+        if substitute && !self.vars.is_empty() {
+            let canonical = Emitter { vars: &[] }.emit_node(node, false).0;
+            if let Some(i) = self.vars.iter().position(|(_, text)| *text == canonical) {
+                return (self.var_name(i), Prec::Atom);
+            }
+        }
+
+        match node {
+            Node::Literal(c) => (emit_char(*c), Prec::Atom),
+            Node::Class(class) => (emit_class(class), Prec::Atom),
+            Node::Dot => (".".into(), Prec::Atom),
+            Node::Boundary(b) => {
+                let s = match b {
+                    Boundary::Start => "^",
+                    Boundary::End => "$",
+                    Boundary::Word => "%",
+                    Boundary::NotWord => "!%",
+                    Boundary::WordStart => "<",
+                    Boundary::WordEnd => ">",
+                };
+                (s.into(), Prec::Atom)
+            }
+            Node::Group(kind, inner) => {
+                let inner = self.emit_node(inner, true).0;
+                let s = match kind {
+                    GroupKind::Capturing => format!(":({inner})"),
+                    GroupKind::Named(name) => format!(":{name}({inner})"),
+                    GroupKind::Atomic => format!("atomic({inner})"),
+                };
+                (s, Prec::Atom)
+            }
+            Node::Lookaround(kind, inner) => {
+                let inner = self.emit_node(inner, true).0;
+                let op = match kind {
+                    Lookaround::Ahead => ">>",
+                    Lookaround::AheadNegative => "!>>",
+                    Lookaround::Behind => "<<",
+                    Lookaround::BehindNegative => "!<<",
+                };
+                (format!("({op} {inner})"), Prec::Atom)
+            }
+            Node::Alternation(alts) => {
+                let alts: Vec<_> = alts.iter().map(|alt| self.emit_node(alt, true).0).collect();
+                (alts.join(" | "), Prec::Alternation)
+            }
+            Node::Concat(items) => self.emit_concat(items),
+            Node::Repetition(rep) => self.emit_repetition(rep),
+            Node::Reference(r) => {
+                let s = match r {
+                    Reference::Number(n) => format!("::{n}"),
+                    Reference::Relative(n) if *n > 0 => format!("::+{n}"),
+                    Reference::Relative(n) => format!("::{n}"),
+                    Reference::Name(name) => format!("::{name}"),
+                };
+                (s, Prec::Atom)
+            }
+            Node::Recursion => ("recursion".into(), Prec::Atom),
+            Node::Grapheme => ("Grapheme".into(), Prec::Atom),
+            Node::Raw(regex) => (format!("regex {}", quote(regex)), Prec::Atom),
+            Node::Comment(comment) => {
+                // a pomsky comment ends at the line break, so every line needs a `#`
+                let mut s = String::new();
+                for line in comment.lines() {
+                    let line = line.trim_end();
+                    s.push('#');
+                    if !line.is_empty() {
+                        s.push(' ');
+                        s.push_str(line);
+                    }
+                    s.push('\n');
+                }
+                (s, Prec::Atom)
+            }
+        }
+    }
+
+    fn emit_concat(&self, items: &[Node<'_>]) -> (String, Prec) {
+        if items.is_empty() {
+            return ("''".into(), Prec::Atom);
+        }
+
+        let mut parts = vec![];
+        let mut literal = String::new();
+        for item in items {
+            match item {
+                Node::Literal(c) if !needs_code_point(*c) => literal.push(*c),
+                _ => {
+                    if !literal.is_empty() {
+                        parts.push(quote(&literal));
+                        literal.clear();
+                    }
+                    let (s, prec) = self.emit_node(item, true);
+                    parts.push(if prec < Prec::Concat { format!("({s})") } else { s });
+                }
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(quote(&literal));
+        }
+
+        let prec = if parts.len() == 1 { Prec::Atom } else { Prec::Concat };
+        (parts.join(" "), prec)
+    }
+
+    fn emit_repetition(&self, rep: &Repetition<'_>) -> (String, Prec) {
+        let (inner, prec) = self.emit_node(&rep.inner, true);
+        let inner = if prec < Prec::Atom { format!("({inner})") } else { inner };
+
+        let quantifier = match (rep.min, rep.max) {
+            (0, None) => "*".to_string(),
+            (1, None) => "+".to_string(),
+            (0, Some(1)) => "?".to_string(),
+            (min, Some(max)) if min == max => format!("{{{min}}}"),
+            (min, None) => format!("{{{min},}}"),
+            (min, Some(max)) => format!("{{{min},{max}}}"),
+        };
+
+        match rep.quantifier {
+            Quantifier::Greedy => (format!("{inner}{quantifier}"), Prec::Repetition),
+            Quantifier::Lazy => (format!("{inner}{quantifier} lazy"), Prec::Repetition),
+            Quantifier::Possessive => (format!("atomic({inner}{quantifier})"), Prec::Atom),
+        }
+    }
+}
+
+/// Selects expressions that appear more than once, longest first
+fn extract_variables<'n, 'i>(root: &'n Node<'i>) -> Vec<(&'n Node<'i>, String)> {
+    let mut vars: Vec<(&'n Node<'i>, String)> = vec![];
+
+    loop {
+        let mut counts = vec![];
+        count_occurrences(root, &vars, &mut counts);
+        for (node, _) in &vars {
+            for_each_child(node, |child| count_occurrences(child, &vars, &mut counts));
+        }
+
+        let mut best: Option<&(String, &Node<'_>, usize)> = None;
+        for candidate in counts.iter().filter(|(_, _, count)| *count >= 2) {
+            if best.is_none_or(|best| candidate.0.len() > best.0.len()) {
+                best = Some(candidate);
+            }
+        }
+        match best {
+            Some((text, node, _)) => vars.push((node, text.clone())),
+            None => return vars,
+        }
+    }
+}
+
+fn count_occurrences<'n, 'i>(
+    node: &'n Node<'i>,
+    vars: &[(&'n Node<'i>, String)],
+    counts: &mut Vec<(String, &'n Node<'i>, usize)>,
+) {
+    let text = Emitter { vars: &[] }.emit_node(node, false).0;
+    if vars.iter().any(|(_, var)| *var == text) {
+        return;
+    }
+
+    if text.len() >= MIN_VARIABLE_LEN && can_be_variable(node) {
+        match counts.iter_mut().find(|(t, _, _)| *t == text) {
+            Some((_, _, count)) => *count += 1,
+            None => counts.push((text, node, 1)),
+        }
+    }
+    for_each_child(node, |child| count_occurrences(child, vars, counts));
+}
+
+/// Whether the node may be extracted into a variable. Variables may not
+/// contain capturing groups or references.
+fn can_be_variable(node: &Node<'_>) -> bool {
+    fn is_allowed(node: &Node<'_>) -> bool {
+        let mut allowed = !matches!(
+            node,
+            Node::Group(GroupKind::Capturing | GroupKind::Named(_), _)
+                | Node::Reference(_)
+                | Node::Recursion
+                | Node::Comment(_)
+                | Node::Raw(_)
+        );
+        for_each_child(node, |child| allowed &= is_allowed(child));
+        allowed
+    }
+
+    matches!(
+        node,
+        Node::Class(_)
+            | Node::Group(..)
+            | Node::Lookaround(..)
+            | Node::Alternation(_)
+            | Node::Concat(_)
+            | Node::Repetition(_)
+    ) && is_allowed(node)
+}
+
+fn for_each_child<'n, 'i>(node: &'n Node<'i>, mut f: impl FnMut(&'n Node<'i>)) {
+    match node {
+        Node::Group(_, inner) | Node::Lookaround(_, inner) => f(inner),
+        Node::Alternation(nodes) | Node::Concat(nodes) => nodes.iter().for_each(f),
+        Node::Repetition(rep) => f(&rep.inner),
+        _ => {}
+    }
+}
+
+fn emit_class(class: &Class) -> String {
+    match class.items.as_slice() {
+        [] if class.negated => return "C".into(),
+        [] => return "regex '[]'".into(),
+        [ClassItem::Named { name, negated: true }] if !class.negated => {
+            return format!("![{name}]");
+        }
+        _ => {}
+    }
+
+    let mut parts = vec![];
+    let mut chars = String::new();
+    for item in &class.items {
+        match item {
+            ClassItem::Char(c) if !needs_code_point(*c) => {
+                chars.push(*c);
+                continue;
+            }
+            ClassItem::Char(c) => parts.push(emit_class_char(*c)),
+            ClassItem::Range(first, last) => {
+                parts.push(format!("{}-{}", emit_class_char(*first), emit_class_char(*last)));
+            }
+            ClassItem::Named { name, negated } => {
+                parts.push(if *negated { format!("!{name}") } else { name.to_string() });
+            }
+        }
+    }
+    if !chars.is_empty() {
+        parts.insert(0, quote(&chars));
+    }
+
+    let negation = if class.negated { "!" } else { "" };
+    format!("{negation}[{}]", parts.join(" "))
+}
+
+fn emit_class_char(c: char) -> String {
+    match c {
+        '\n' => "n".into(),
+        '\r' => "r".into(),
+        '\t' => "t".into(),
+        c => emit_char(c),
+    }
+}
+
+/// Whether a character should be written as a code point rather than in a
+/// string, because it is invisible
+fn needs_code_point(c: char) -> bool {
+    c.is_control() || (c.is_whitespace() && c != ' ')
+}
+
+fn emit_char(c: char) -> String {
+    if needs_code_point(c) { format!("U+{:X}", c as u32) } else { quote(&c.to_string()) }
+}
+
+fn quote(s: &str) -> String {
+    if !s.contains('\'') {
+        return format!("'{s}'");
+    }
+
+    let mut buf = String::with_capacity(s.len() + 2);
+    buf.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            buf.push('\\');
+        }
+        buf.push(c);
+    }
+    buf.push('"');
+    buf
+}
@@ -0,0 +1,33 @@
+//! Conversion of existing regexes to pomsky expressions
+
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::{diagnose::Diagnostic, options::RegexFlavor};
+
+mod emit;
+mod parse;
+
+/// Converts a regex in the given flavor to an equivalent pomsky expression.
+///
+/// Expressions that appear more than once are extracted into `let`
+/// statements. Constructs that can't be expressed in pomsky, such as inline
+/// modifiers, are embedded verbatim with the `regex` keyword, and a warning is
+/// emitted for them. The spans of all diagnostics refer to the regex.
+///
+/// ### Example
+///
+/// ```
+/// use pomsky::{import::import, options::RegexFlavor};
+///
+/// let (pomsky, _warnings) = import(r"(?<year>\d{4})-(?<month>\d{2})", RegexFlavor::Pcre);
+/// assert_eq!(pomsky.as_deref(), Some(":year([digit]{4}) '-' :month([digit]{2})"));
+/// ```
+pub fn import(regex: &str, flavor: RegexFlavor) -> (Option<String>, Vec<Diagnostic>) {
+    match parse::Parser::new(regex, flavor).parse() {
+        Ok((node, warnings)) => {
+            let warnings = warnings.iter().map(|w| w.diagnostic()).collect();
+            (Some(emit::emit(&node)), warnings)
+        }
+        Err(error) => (None, vec![error.diagnostic()]),
+    }
+}
@@ -0,0 +1,976 @@
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use pomsky_syntax::{Span, diagnose::LexErrorMsg, list_shorthands};
+
+use crate::{
+    diagnose::{ImportError, ImportErrorKind, ImportWarning, ImportWarningKind},
+    options::RegexFlavor,
+};
+
+type PResult<T> = Result<T, ImportError>;
+
+/// The largest repetition bound and group number pomsky accepts, which is
+/// also the limit in PCRE
+const MAX_NUMBER: u32 = 65_535;
+
+/// A node of a parsed regex.
+///
+/// Non-capturing groups are not represented, since pomsky adds parentheses
+/// where they are needed.
+#[derive(Debug, Clone)]
+pub(super) enum Node<'i> {
+    Literal(char),
+    Class(Class),
+    Dot,
+    Boundary(Boundary),
+    Group(GroupKind, Box<Node<'i>>),
+    Lookaround(Lookaround, Box<Node<'i>>),
+    Alternation(Vec<Node<'i>>),
+    Concat(Vec<Node<'i>>),
+    Repetition(Box<Repetition<'i>>),
+    Reference(Reference),
+    Recursion,
+    Grapheme,
+    Raw(&'i str),
+    Comment(&'i str),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Class {
+    pub(super) negated: bool,
+    pub(super) items: Vec<ClassItem>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum ClassItem {
+    Char(char),
+    Range(char, char),
+    Named { name: Cow<'static, str>, negated: bool },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Boundary {
+    Start,
+    End,
+    Word,
+    NotWord,
+    WordStart,
+    WordEnd,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum GroupKind {
+    Capturing,
+    Named(String),
+    Atomic,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Lookaround {
+    Ahead,
+    AheadNegative,
+    Behind,
+    BehindNegative,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Repetition<'i> {
+    pub(super) inner: Node<'i>,
+    pub(super) min: u32,
+    pub(super) max: Option<u32>,
+    pub(super) quantifier: Quantifier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Quantifier {
+    Greedy,
+    Lazy,
+    Possessive,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Reference {
+    Number(u32),
+    Relative(i32),
+    Name(String),
+}
+
+/// The result of parsing a backslash escape
+enum Escape<'i> {
+    Char(char),
+    Class(ClassItem),
+    Node(Node<'i>),
+    Literal(&'i str),
+    Unsupported(&'static str),
+}
+
+pub(super) struct Parser<'i> {
+    input: &'i str,
+    pos: usize,
+    flavor: RegexFlavor,
+    warnings: Vec<ImportWarning>,
+    renamed_groups: Vec<String>,
+    /// The name in the regex and the name in pomsky of every named group
+    group_names: Vec<(String, String)>,
+}
+
+impl<'i> Parser<'i> {
+    pub(super) fn new(input: &'i str, flavor: RegexFlavor) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            flavor,
+            warnings: vec![],
+            renamed_groups: vec![],
+            group_names: vec![],
+        }
+    }
+
+    pub(super) fn parse(mut self) -> PResult<(Node<'i>, Vec<ImportWarning>)> {
+        let node = self.parse_alternation()?;
+        if self.rest().starts_with(')') {
+            return Err(ImportErrorKind::UnopenedGroup.at(Span::new(self.pos, self.pos + 1)));
+        }
+        Ok((node, self.warnings))
+    }
+
+    fn rest(&self) -> &'i str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matches = self.rest().starts_with(c);
+        if matches {
+            self.pos += c.len_utf8();
+        }
+        matches
+    }
+
+    /// Consumes a number. If it is too large, the error span begins at `start`
+    fn eat_digits(&mut self, start: usize) -> PResult<Option<u32>> {
+        let len = self.rest().find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest().len());
+        let digits = &self.rest()[..len];
+        self.pos += len;
+        if len == 0 {
+            return Ok(None);
+        }
+        match digits.parse() {
+            Ok(n) if n <= MAX_NUMBER => Ok(Some(n)),
+            _ => Err(ImportErrorKind::NumberTooLarge.at(Span::new(start, self.pos))),
+        }
+    }
+
+    fn slice_from(&self, start: usize) -> &'i str {
+        &self.input[start..self.pos]
+    }
+
+    fn warn(&mut self, kind: ImportWarningKind, start: usize) {
+        self.warnings.push(kind.at(Span::new(start, self.pos)));
+    }
+
+    fn unsupported(&mut self, construct: &'static str, start: usize) -> Node<'i> {
+        self.warn(ImportWarningKind::Unsupported(construct), start);
+        Node::Raw(self.slice_from(start))
+    }
+
+    fn parse_alternation(&mut self) -> PResult<Node<'i>> {
+        let start = self.pos;
+        let warnings_before = self.warnings.len();
+
+        let mut alternatives = vec![];
+        let mut has_modifier = false;
+        loop {
+            let (sequence, modifier) = self.parse_sequence()?;
+            alternatives.push(sequence);
+            has_modifier |= modifier;
+            if !self.eat('|') {
+                break;
+            }
+        }
+
+        // an inline modifier such as `(?i)` affects everything up to the end of
+        // the enclosing group, so all of it must be embedded verbatim
+        if has_modifier {
+            self.warnings.truncate(warnings_before);
+            return Ok(self.unsupported("An inline modifier", start));
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    fn parse_sequence(&mut self) -> PResult<(Node<'i>, bool)> {
+        let mut items = vec![];
+        let mut has_modifier = false;
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            if self.eat_modifier() {
+                has_modifier = true;
+                continue;
+            }
+
+            let atom = self.parse_atom()?;
+            match self.parse_quantifiers(atom)? {
+                Node::Concat(nodes) => items.extend(nodes),
+                node => items.push(node),
+            }
+        }
+
+        let node = if items.len() == 1 { items.pop().unwrap() } else { Node::Concat(items) };
+        Ok((node, has_modifier))
+    }
+
+    /// Consumes an inline modifier that isn't scoped to a group, e.g. `(?i)`
+    fn eat_modifier(&mut self) -> bool {
+        let Some(rest) = self.rest().strip_prefix("(?") else { return false };
+        let len = rest.find(|c: char| !c.is_ascii_alphabetic() && c != '-' && c != '^');
+        match len {
+            Some(len) if len > 0 && rest[len..].starts_with(')') && &rest[..len] != "R" => {
+                self.pos += len + 3;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_atom(&mut self) -> PResult<Node<'i>> {
+        let start = self.pos;
+        let c = self.peek().unwrap();
+
+        Ok(match c {
+            '(' => self.parse_group()?,
+            '[' => self.parse_class()?,
+            '\\' => match self.parse_escape(false)? {
+                Escape::Char(c) => Node::Literal(c),
+                Escape::Class(item) => Node::Class(Class { negated: false, items: vec![item] }),
+                Escape::Node(node) => node,
+                Escape::Literal(s) => Node::Concat(s.chars().map(Node::Literal).collect()),
+                Escape::Unsupported(construct) => self.unsupported(construct, start),
+            },
+            '.' => {
+                self.pos += 1;
+                Node::Dot
+            }
+            '^' => {
+                self.pos += 1;
+                Node::Boundary(Boundary::Start)
+            }
+            '$' => {
+                self.pos += 1;
+                Node::Boundary(Boundary::End)
+            }
+            '*' | '+' | '?' => {
+                return Err(ImportErrorKind::NothingToRepeat.at(Span::new(start, start + 1)));
+            }
+            '{' if self.quantifier_braces().is_some() => {
+                return Err(ImportErrorKind::NothingToRepeat.at(Span::new(start, start + 1)));
+            }
+            c => {
+                self.pos += c.len_utf8();
+                Node::Literal(c)
+            }
+        })
+    }
+
+    /// Returns the length and bounds of a quantifier in braces, e.g. `{2,5}`,
+    /// or `None` if the brace doesn't start a quantifier
+    fn quantifier_braces(&self) -> Option<PResult<(usize, u32, Option<u32>)>> {
+        let rest = self.rest().strip_prefix('{')?;
+        let end = rest.find('}')?;
+        let (min, max) = match rest[..end].split_once(',') {
+            Some((min, max)) => (min, Some(max)),
+            None => (&rest[..end], None),
+        };
+        let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        let (min, max) = match max {
+            None if is_number(min) => (min, Some(min)),
+            Some("") if is_number(min) => (min, None),
+            Some(max) if is_number(max) && (min.is_empty() || is_number(min)) => (min, Some(max)),
+            _ => return None,
+        };
+
+        let span = Span::new(self.pos, self.pos + end + 2);
+        let parse = |s: &str| match s.parse() {
+            Ok(n) if n <= MAX_NUMBER => Ok(n),
+            _ if s.is_empty() => Ok(0),
+            _ => Err(ImportErrorKind::NumberTooLarge.at(span)),
+        };
+        Some((|| {
+            let min = parse(min)?;
+            let max = max.map(parse).transpose()?;
+            if max.is_some_and(|max| min > max) {
+                return Err(ImportErrorKind::QuantifierNotIncreasing.at(span));
+            }
+            Ok((end + 2, min, max))
+        })())
+    }
+
+    fn parse_quantifiers(&mut self, mut node: Node<'i>) -> PResult<Node<'i>> {
+        if let Node::Comment(_) = node {
+            return Ok(node);
+        }
+
+        let mut repeated = false;
+        loop {
+            // most flavors reject a quantifier after another one, e.g. `a**`
+            if repeated
+                && self.flavor != RegexFlavor::Ruby
+                && (self.rest().starts_with(['*', '+', '?']) || self.quantifier_braces().is_some())
+            {
+                return Err(ImportErrorKind::NothingToRepeat.at(Span::new(self.pos, self.pos + 1)));
+            }
+
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    (0, None)
+                }
+                Some('+') => {
+                    self.pos += 1;
+                    (1, None)
+                }
+                Some('?') => {
+                    self.pos += 1;
+                    (0, Some(1))
+                }
+                Some('{') => match self.quantifier_braces() {
+                    Some(result) => {
+                        let (len, min, max) = result?;
+                        self.pos += len;
+                        (min, max)
+                    }
+                    None => return Ok(node),
+                },
+                _ => return Ok(node),
+            };
+
+            let quantifier = if self.eat('?') {
+                Quantifier::Lazy
+            } else if self.eat('+') {
+                Quantifier::Possessive
+            } else {
+                Quantifier::Greedy
+            };
+            node = Node::Repetition(Box::new(Repetition { inner: node, min, max, quantifier }));
+            repeated = true;
+        }
+    }
+
+    fn parse_group(&mut self) -> PResult<Node<'i>> {
+        let start = self.pos;
+
+        if self.rest().starts_with("(*") {
+            let Some(len) = self.rest().find(')') else {
+                return Err(ImportErrorKind::UnclosedGroup.at(Span::new(start, start + 1)));
+            };
+            self.pos += len + 1;
+            return Ok(self.unsupported("A backtracking control verb", start));
+        }
+
+        let Some((len, kind)) = pomsky_syntax::parse_special_group(self.rest()) else {
+            self.pos += 1;
+            let inner = self.parse_group_rest(start)?;
+            return Ok(Node::Group(GroupKind::Capturing, Box::new(inner)));
+        };
+        let prefix = &self.rest()[..len];
+
+        Ok(match kind {
+            LexErrorMsg::GroupNonCapturing => {
+                self.pos += len;
+                self.parse_group_rest(start)?
+            }
+            LexErrorMsg::GroupLookahead
+            | LexErrorMsg::GroupLookaheadNeg
+            | LexErrorMsg::GroupLookbehind
+            | LexErrorMsg::GroupLookbehindNeg => {
+                let lookaround = match kind {
+                    LexErrorMsg::GroupLookahead => Lookaround::Ahead,
+                    LexErrorMsg::GroupLookaheadNeg => Lookaround::AheadNegative,
+                    LexErrorMsg::GroupLookbehind => Lookaround::Behind,
+                    _ => Lookaround::BehindNegative,
+                };
+                self.pos += len;
+                let inner = self.parse_group_rest(start)?;
+                Node::Lookaround(lookaround, Box::new(inner))
+            }
+            LexErrorMsg::GroupAtomic => {
+                self.pos += len;
+                let inner = self.parse_group_rest(start)?;
+                Node::Group(GroupKind::Atomic, Box::new(inner))
+            }
+            LexErrorMsg::GroupNamedCapture => {
+                let name = prefix.trim_start_matches("(?P").trim_start_matches("(?");
+                self.named_group(start, len, &name[1..name.len() - 1])?
+            }
+            LexErrorMsg::GroupPcreBackreference => {
+                self.pos += len;
+                let name = self.reference_name(&prefix[4..len - 1], start);
+                Node::Reference(Reference::Name(name))
+            }
+            LexErrorMsg::GroupComment => {
+                self.pos += len;
+                Node::Comment(prefix[3..len - 1].trim())
+            }
+            LexErrorMsg::GroupConditional => {
+                self.pos += len - 1;
+                if self.rest().starts_with("(?") {
+                    self.parse_group()?;
+                } else {
+                    match self.rest().find(')') {
+                        Some(len) => self.pos += len + 1,
+                        None => {
+                            return Err(
+                                ImportErrorKind::UnclosedGroup.at(Span::new(start, start + 1))
+                            );
+                        }
+                    }
+                }
+                self.raw_group(start, 0, "A conditional")?
+            }
+            LexErrorMsg::GroupBranchReset => self.raw_group(start, len, "A branch reset group")?,
+            LexErrorMsg::GroupSubroutineCall => self.raw_group(start, len, "A subroutine call")?,
+            _ => {
+                let rest = &self.rest()[2..];
+                if rest.starts_with("R)") || rest.starts_with("0)") {
+                    self.pos += 4;
+                    Node::Recursion
+                } else if let Some((len, name)) = named_group_prefix(rest) {
+                    // the lexer doesn't recognize group names with underscores
+                    self.named_group(start, len + 2, name)?
+                } else if let Some(name) = rest.strip_prefix("P=").and_then(|r| r.split_once(')')) {
+                    self.pos += name.0.len() + 5;
+                    Node::Reference(Reference::Name(self.reference_name(name.0, start)))
+                } else if rest
+                    .trim_start_matches(['-', '+'])
+                    .starts_with(|c: char| c.is_ascii_digit())
+                {
+                    self.raw_group(start, 2, "A subroutine call")?
+                } else if rest
+                    .starts_with(|c: char| c.is_ascii_alphabetic() || c == '^' || c == '-')
+                {
+                    self.raw_group(start, 2, "An inline modifier")?
+                } else {
+                    self.raw_group(start, 2, "This group")?
+                }
+            }
+        })
+    }
+
+    fn named_group(&mut self, start: usize, prefix_len: usize, name: &str) -> PResult<Node<'i>> {
+        if name.contains('-') || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.raw_group(start, prefix_len, "A balancing group");
+        }
+        self.pos += prefix_len;
+        let source_name = name;
+        let mut name = self.group_name(name, start);
+        if self.group_names.iter().any(|(_, n)| *n == name) {
+            let unique = (2..)
+                .map(|i| format!("{name}{i}"))
+                .find(|n| !self.group_names.iter().any(|(_, other)| other == n))
+                .unwrap();
+            self.warn(ImportWarningKind::DuplicateGroupName { name, to: unique.clone() }, start);
+            name = unique;
+        }
+        self.group_names.push((source_name.to_string(), name.clone()));
+        let inner = self.parse_group_rest(start)?;
+        Ok(Node::Group(GroupKind::Named(name), Box::new(inner)))
+    }
+
+    /// Parses the contents of a group and its closing parenthesis
+    fn parse_group_rest(&mut self, start: usize) -> PResult<Node<'i>> {
+        let inner = self.parse_alternation()?;
+        if !self.eat(')') {
+            return Err(ImportErrorKind::UnclosedGroup.at(Span::new(start, start + 1)));
+        }
+        Ok(inner)
+    }
+
+    /// Skips a group that can't be converted, and returns it verbatim
+    fn raw_group(
+        &mut self,
+        start: usize,
+        prefix_len: usize,
+        construct: &'static str,
+    ) -> PResult<Node<'i>> {
+        self.pos += prefix_len;
+        let warnings_before = self.warnings.len();
+        self.parse_group_rest(start)?;
+        self.warnings.truncate(warnings_before);
+        Ok(self.unsupported(construct, start))
+    }
+
+    /// Converts a group name to one that is allowed in pomsky. Names with
+    /// underscores are converted to camelCase, and reserved words get a `2`
+    /// appended.
+    fn group_name(&mut self, name: &str, start: usize) -> String {
+        if name.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            if !pomsky_syntax::is_reserved_word(name) {
+                return name.to_string();
+            }
+            let to = format!("{name}2");
+            if !self.renamed_groups.iter().any(|n| n == name) {
+                self.renamed_groups.push(name.to_string());
+                let kind =
+                    ImportWarningKind::ReservedGroupName { name: name.to_string(), to: to.clone() };
+                self.warn(kind, start);
+            }
+            return to;
+        }
+
+        let mut result = String::new();
+        let mut upper = false;
+        for c in name.chars() {
+            if c == '_' {
+                upper = !result.is_empty();
+            } else if c.is_ascii_alphanumeric() {
+                result.push(if upper { c.to_ascii_uppercase() } else { c });
+                upper = false;
+            } else {
+                result.extend(format!("U{:X}", c as u32).chars());
+            }
+        }
+        if !result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            result.insert(0, 'g');
+        }
+        if pomsky_syntax::is_reserved_word(&result) {
+            result.push('2');
+        }
+
+        if !self.renamed_groups.iter().any(|n| n == name) {
+            self.renamed_groups.push(name.to_string());
+            let kind =
+                ImportWarningKind::RenamedGroup { from: name.to_string(), to: result.clone() };
+            self.warn(kind, start);
+        }
+        result
+    }
+
+    /// Returns the pomsky name of the group a named backreference refers to.
+    ///
+    /// If several groups have this name, PCRE uses the first of them that
+    /// matched, and other flavors the last one. It is assumed that all of them
+    /// matched, and a warning is emitted, since this may not be the case.
+    fn reference_name(&mut self, name: &str, start: usize) -> String {
+        let mut groups =
+            self.group_names.iter().filter(|(source, _)| source == name).map(|(_, n)| n);
+        let Some(first) = groups.next().cloned() else {
+            return self.group_name(name, start);
+        };
+        let Some(last) = groups.next_back().cloned() else {
+            return first;
+        };
+
+        let is_first = self.flavor == RegexFlavor::Pcre;
+        let to = if is_first { first } else { last };
+        let kind = ImportWarningKind::AmbiguousReference {
+            name: name.to_string(),
+            to: to.clone(),
+            first: is_first,
+        };
+        self.warn(kind, start);
+        to
+    }
+
+    fn parse_class(&mut self) -> PResult<Node<'i>> {
+        let start = self.pos;
+        self.pos += 1;
+        let negated = self.eat('^');
+        let mut items = vec![];
+        let mut first = true;
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(ImportErrorKind::UnclosedClass.at(Span::new(start, start + 1)));
+            };
+            if c == ']' && !(first && self.flavor != RegexFlavor::JavaScript) {
+                self.pos += 1;
+                break;
+            }
+            first = false;
+
+            if self.rest().starts_with("[:") {
+                match self.parse_posix_class() {
+                    Some(item) => items.push(item),
+                    None => return self.raw_class(start, "This POSIX class"),
+                }
+                continue;
+            }
+            if c == '[' && self.has_nested_classes() {
+                return self.raw_class(start, "A nested character class");
+            }
+            if self.rest().starts_with("&&") && self.has_nested_classes() {
+                return self.raw_class(start, "A character class intersection");
+            }
+
+            let item_start = self.pos;
+            let first_char = match self.parse_class_char()? {
+                Ok(c) => c,
+                Err(Escape::Class(item)) => {
+                    items.push(item);
+                    continue;
+                }
+                Err(Escape::Literal(s)) => {
+                    items.extend(s.chars().map(ClassItem::Char));
+                    continue;
+                }
+                Err(_) => return self.raw_class(start, "A character class with this escape"),
+            };
+
+            let rest = self.rest();
+            if rest.starts_with('-') && rest.len() > 1 && !rest[1..].starts_with(']') {
+                self.pos += 1;
+                match self.parse_class_char()? {
+                    Ok(last) => {
+                        if last < first_char {
+                            let span = Span::new(item_start, self.pos);
+                            return Err(ImportErrorKind::RangeNotIncreasing.at(span));
+                        }
+                        items.push(ClassItem::Range(first_char, last));
+                    }
+                    Err(Escape::Class(item)) => {
+                        items.extend([ClassItem::Char(first_char), ClassItem::Char('-'), item]);
+                    }
+                    Err(_) => return self.raw_class(start, "A character class with this escape"),
+                }
+            } else {
+                items.push(ClassItem::Char(first_char));
+            }
+        }
+
+        Ok(Node::Class(Class { negated, items }))
+    }
+
+    /// Whether `[` and `&&` have a special meaning in character classes
+    fn has_nested_classes(&self) -> bool {
+        matches!(self.flavor, RegexFlavor::Java | RegexFlavor::Ruby | RegexFlavor::PythonRegex)
+    }
+
+    /// Parses a character in a character class. If it isn't a single
+    /// character, the escape is returned as error.
+    fn parse_class_char(&mut self) -> PResult<Result<char, Escape<'i>>> {
+        let c = self.peek().unwrap();
+        if c == '\\' {
+            return Ok(match self.parse_escape(true)? {
+                Escape::Char(c) => Ok(c),
+                escape => Err(escape),
+            });
+        }
+        self.pos += c.len_utf8();
+        Ok(Ok(c))
+    }
+
+    fn parse_posix_class(&mut self) -> Option<ClassItem> {
+        let rest = &self.rest()[2..];
+        let end = rest.find(":]")?;
+        let (negated, name) = match rest[..end].strip_prefix('^') {
+            Some(name) => (true, name),
+            None => (false, &rest[..end]),
+        };
+        let name = match name {
+            "alnum" | "alpha" | "blank" | "cntrl" | "digit" | "graph" | "lower" | "print"
+            | "punct" | "space" | "upper" | "word" | "xdigit" => name,
+            "ascii" => return self.posix_done(end, "ascii".into(), negated),
+            _ => return None,
+        };
+        self.posix_done(end, format!("ascii_{name}").into(), negated)
+    }
+
+    fn posix_done(
+        &mut self,
+        end: usize,
+        name: Cow<'static, str>,
+        negated: bool,
+    ) -> Option<ClassItem> {
+        self.pos += end + 4;
+        Some(ClassItem::Named { name, negated })
+    }
+
+    /// Skips a character class that can't be converted, and returns it verbatim
+    fn raw_class(&mut self, start: usize, construct: &'static str) -> PResult<Node<'i>> {
+        self.pos = start + 1;
+        let mut depth = 1;
+        self.eat('^');
+        if self.flavor != RegexFlavor::JavaScript {
+            self.eat(']');
+        }
+
+        while depth > 0 {
+            let Some(c) = self.peek() else {
+                return Err(ImportErrorKind::UnclosedClass.at(Span::new(start, start + 1)));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '\\' => {
+                    if let Some(c) = self.peek() {
+                        self.pos += c.len_utf8();
+                    }
+                }
+                '[' if self.has_nested_classes() || self.rest().starts_with(':') => {
+                    depth += 1;
+                    self.eat('^');
+                    self.eat(']');
+                }
+                ']' => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(self.unsupported(construct, start))
+    }
+
+    fn parse_escape(&mut self, in_class: bool) -> PResult<Escape<'i>> {
+        let start = self.pos;
+        let Some((len, kind)) = pomsky_syntax::parse_backslash(self.rest()) else {
+            return Err(ImportErrorKind::TrailingBackslash.at(Span::new(start, self.input.len())));
+        };
+        let text = &self.rest()[..len];
+        self.pos += len;
+
+        match kind {
+            LexErrorMsg::BackslashUnicode => self.code_point(&text[3..len - 1], start),
+            LexErrorMsg::BackslashU4 | LexErrorMsg::BackslashX2 => {
+                self.code_point(&text[2..], start)
+            }
+            LexErrorMsg::BackslashGK => self.parse_reference(text, start),
+            LexErrorMsg::BackslashProperty => Ok(self.parse_property(text)),
+            // the lexer doesn't recognize properties such as `\p{Script=Latin}`
+            _ if matches!(text, "\\p" | "\\P") && self.rest().starts_with('{') => {
+                match self.rest().find('}') {
+                    Some(len) => {
+                        self.pos += len + 1;
+                        Ok(self.parse_property(self.slice_from(start)))
+                    }
+                    None => Ok(Escape::Unsupported("This Unicode property")),
+                }
+            }
+            _ => {
+                let c = text[1..].chars().next().unwrap();
+                self.parse_simple_escape(c, in_class, start)
+            }
+        }
+    }
+
+    fn code_point(&self, hex: &str, start: usize) -> PResult<Escape<'i>> {
+        u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .map(Escape::Char)
+            .ok_or_else(|| ImportErrorKind::InvalidCodePoint.at(Span::new(start, self.pos)))
+    }
+
+    fn parse_simple_escape(
+        &mut self,
+        c: char,
+        in_class: bool,
+        start: usize,
+    ) -> PResult<Escape<'i>> {
+        use RegexFlavor as F;
+
+        let flavor = self.flavor;
+        let shorthand = |name: &'static str, negated| {
+            Escape::Class(ClassItem::Named { name: Cow::Borrowed(name), negated })
+        };
+        let boundary = |b| {
+            if in_class {
+                Escape::Unsupported("This escape")
+            } else {
+                Escape::Node(Node::Boundary(b))
+            }
+        };
+        let ascii_shorthands = matches!(flavor, F::JavaScript | F::RE2);
+
+        Ok(match c {
+            'd' | 'D' if ascii_shorthands => shorthand("ascii_digit", c == 'D'),
+            'w' | 'W' if ascii_shorthands => shorthand("ascii_word", c == 'W'),
+            'd' | 'D' => shorthand("digit", c == 'D'),
+            'w' | 'W' => shorthand("word", c == 'W'),
+            's' | 'S' if flavor == F::RE2 => shorthand("ascii_space", c == 'S'),
+            's' | 'S' => shorthand("space", c == 'S'),
+            'h' | 'H' if flavor == F::Ruby => shorthand("ascii_xdigit", c == 'H'),
+            'h' | 'H' if matches!(flavor, F::Pcre | F::Java) => shorthand("horiz_space", c == 'H'),
+            'v' | 'V' if matches!(flavor, F::Pcre | F::Java) => shorthand("vert_space", c == 'V'),
+
+            'n' => Escape::Char('\n'),
+            'r' => Escape::Char('\r'),
+            't' => Escape::Char('\t'),
+            'f' => Escape::Char('\u{0C}'),
+            'v' => Escape::Char('\u{0B}'),
+            'a' => Escape::Char('\u{07}'),
+            'e' => Escape::Char('\u{1B}'),
+            'b' if in_class || flavor == F::Postgres => Escape::Char('\u{08}'),
+
+            'b' => boundary(Boundary::Word),
+            'B' if flavor != F::Postgres => boundary(Boundary::NotWord),
+            'y' if flavor == F::Postgres => boundary(Boundary::Word),
+            'Y' if flavor == F::Postgres => boundary(Boundary::NotWord),
+            'm' if matches!(flavor, F::Postgres | F::PythonRegex) => boundary(Boundary::WordStart),
+            'M' if matches!(flavor, F::Postgres | F::PythonRegex) => boundary(Boundary::WordEnd),
+            'A' => boundary(Boundary::Start),
+            'X' if !in_class => Escape::Node(Node::Grapheme),
+
+            'Q' => {
+                let rest = self.rest();
+                let len = rest.find("\\E").unwrap_or(rest.len());
+                self.pos += (len + 2).min(rest.len());
+                Escape::Literal(&rest[..len])
+            }
+            'c' => match self.peek() {
+                Some(c) if c.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    Escape::Char(((c.to_ascii_uppercase() as u8) ^ 0x40) as char)
+                }
+                _ => Escape::Unsupported("This escape"),
+            },
+            'x' | 'u' => {
+                return Err(ImportErrorKind::InvalidCodePoint.at(Span::new(start, self.pos)));
+            }
+            '0' => {
+                let rest = self.rest();
+                let len = rest.find(|c: char| !matches!(c, '0'..='7')).unwrap_or(rest.len()).min(2);
+                self.pos += len;
+                let n = u32::from_str_radix(&rest[..len], 8).unwrap_or(0);
+                Escape::Char(char::from_u32(n).unwrap())
+            }
+            '1'..='9' if in_class => Escape::Unsupported("This escape"),
+            '1'..='9' => {
+                self.pos -= 1;
+                let n = self.eat_digits(start)?.unwrap();
+                Escape::Node(Node::Reference(Reference::Number(n)))
+            }
+
+            c if c.is_ascii_alphanumeric() => Escape::Unsupported("This escape"),
+            c => Escape::Char(c),
+        })
+    }
+
+    /// Parses `\k<name>`, `\g{1}`, `\g-1` etc.
+    fn parse_reference(&mut self, text: &str, start: usize) -> PResult<Escape<'i>> {
+        let mut inner = &text[2..];
+        let is_call =
+            text.starts_with("\\g") && (inner.starts_with('<') || inner.starts_with('\''));
+
+        if let Some(stripped) = inner.strip_prefix(['<', '{', '\'']) {
+            inner = &stripped[..stripped.len() - 1];
+        } else {
+            // the recognizer only consumes a single digit
+            let extra =
+                self.rest().find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest().len());
+            self.pos += extra;
+            inner = &self.input[start + 2..self.pos];
+        }
+
+        if is_call {
+            return Ok(if inner == "0" {
+                Escape::Node(Node::Recursion)
+            } else {
+                Escape::Unsupported("A subroutine call")
+            });
+        }
+
+        let reference = if inner.bytes().all(|b| b.is_ascii_digit()) && !inner.is_empty() {
+            match inner.parse::<u32>() {
+                Ok(n) if n <= MAX_NUMBER => Reference::Number(n),
+                _ => return Err(ImportErrorKind::NumberTooLarge.at(Span::new(start, self.pos))),
+            }
+        } else if let Some(n) = inner.strip_prefix('-').and_then(|n| n.parse::<i32>().ok()) {
+            Reference::Relative(-n)
+        } else if let Some(n) = inner.strip_prefix('+').and_then(|n| n.parse::<i32>().ok()) {
+            Reference::Relative(n)
+        } else if inner.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            Reference::Name(self.reference_name(inner, start))
+        } else {
+            return Ok(Escape::Unsupported("This backreference"));
+        };
+        Ok(Escape::Node(Node::Reference(reference)))
+    }
+
+    /// Parses `\pL`, `\p{Greek}`, `\P{^Script=Greek}` etc.
+    fn parse_property(&self, text: &str) -> Escape<'i> {
+        let mut negated = text.starts_with("\\P");
+        let mut name = &text[2..];
+        if let Some(inner) = name.strip_prefix('{') {
+            name = &inner[..inner.len() - 1];
+        }
+        if let Some(inner) = name.strip_prefix('^') {
+            name = inner;
+            negated = !negated;
+        }
+
+        match self.property_name(name) {
+            Some(name) => Escape::Class(ClassItem::Named { name, negated }),
+            None => Escape::Unsupported("This Unicode property"),
+        }
+    }
+
+    fn property_name(&self, name: &str) -> Option<Cow<'static, str>> {
+        if self.flavor == RegexFlavor::Java {
+            let posix = match name {
+                "Lower" => "ascii_lower",
+                "Upper" => "ascii_upper",
+                "ASCII" => "ascii",
+                "Alpha" => "ascii_alpha",
+                "Digit" => "ascii_digit",
+                "Alnum" => "ascii_alnum",
+                "Punct" => "ascii_punct",
+                "Graph" => "ascii_graph",
+                "Print" => "ascii_print",
+                "Blank" => "ascii_blank",
+                "Cntrl" => "ascii_cntrl",
+                "XDigit" => "ascii_xdigit",
+                "Space" => "ascii_space",
+                _ => "",
+            };
+            if !posix.is_empty() {
+                return Some(posix.into());
+            }
+        }
+
+        let (prefix, name) = match name.split_once(['=', ':']) {
+            Some(("sc" | "Script", name)) => ("sc:", name),
+            Some(("scx" | "Script_Extensions", name)) => ("scx:", name),
+            Some(("gc" | "General_Category", name)) => ("", name),
+            Some(("blk" | "Block", name)) => ("In", name),
+            Some(_) => return None,
+            None if self.flavor == RegexFlavor::Java => {
+                ("", name.strip_prefix("Is").unwrap_or(name))
+            }
+            None => ("", name),
+        };
+
+        let is_script = prefix == "sc:" || prefix == "scx:";
+        let lookup = if prefix == "In" { format!("In{name}") } else { name.to_string() };
+        let known = list_shorthands()
+            .any(|(n, group)| n == lookup && (!is_script || group.kind() == "script"));
+
+        known.then(|| if is_script { format!("{prefix}{name}") } else { lookup }.into())
+    }
+}
+
+/// Recognizes the name of a named group after `(?`, e.g. `<name>` or `P<name>`
+fn named_group_prefix(rest: &str) -> Option<(usize, &str)> {
+    let (after, close, prefix_len) = if let Some(after) = rest.strip_prefix("P<") {
+        (after, '>', 2)
+    } else if let Some(after) = rest.strip_prefix('<') {
+        (after, '>', 1)
+    } else {
+        (rest.strip_prefix('\'')?, '\'', 1)
+    };
+    let len = after.find(|c: char| !c.is_alphanumeric() && c != '_')?;
+    (len > 0 && after[len..].starts_with(close)).then(|| (prefix_len + len + 1, &after[..len]))
+}
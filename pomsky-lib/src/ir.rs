@@ -0,0 +1,443 @@
+//! A read-only view of the compiled regex, before it is turned into a string
+//!
+//! The intermediate representation is produced after variables are inlined,
+//! character sets are resolved and the flavor's constraints are applied, so
+//! it describes exactly the regex that is emitted. It can be obtained with
+//! [`Expr::compile_with_ir`](crate::Expr::compile_with_ir).
+//!
+//! ### Example
+//!
+//! ```
+//! use pomsky::{Expr, ir::{Group, GroupKind, Regex}, options::CompileOptions};
+//!
+//! let input = ":name(['a'-'z']+)";
+//! let (expr, _) = Expr::parse(input);
+//! let (Some((regex, ir)), _) = expr.unwrap().compile_with_ir(input, CompileOptions::default())
+//! else {
+//!     panic!("compilation failed");
+//! };
+//! assert_eq!(regex, "(?P<name>[a-z]+)");
+//!
+//! let Regex::Group(Group { kind: GroupKind::NamedCapture(name), parts, .. }) = &ir else {
+//!     panic!("expected a named group");
+//! };
+//! assert_eq!(name, "name");
+//! assert!(matches!(parts.as_slice(), [Regex::Repetition(_)]));
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+pub use pomsky_syntax::exprs::{
+    BoundaryKind, Category, CodeBlock, LookaroundKind, OtherProperties, RepetitionKind, Script,
+    ScriptExtension,
+};
+
+use crate::{
+    exprs::{
+        char_class::{RegexCharSet, RegexCharSetItem, RegexCompoundCharSet},
+        group::RegexGroupKind,
+        reference::RegexReference,
+        repetition::RegexQuantifier,
+    },
+    regex::{self, RegexProperty, RegexShorthand},
+};
+
+/// A node of the compiled regex
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Regex {
+    /// A literal string
+    Literal(String),
+    /// A regex string that is inserted verbatim into the output
+    Unescaped(String),
+    /// A character set
+    CharSet(CharSet),
+    /// A negated or intersected character set, e.g. `[\w&&[^a-z]]`
+    CompoundCharSet(CompoundCharSet),
+    /// A Unicode grapheme
+    Grapheme,
+    /// The dot, matching anything except `\n`
+    Dot,
+    /// A sequence of nodes, possibly wrapped in a group
+    Group(Group),
+    /// A list of alternatives; at least one of them has to match
+    Alternation(Vec<Regex>),
+    /// A repeated expression
+    Repetition(Box<Repetition>),
+    /// A boundary (start of string, end of string or word boundary)
+    Boundary(BoundaryKind),
+    /// A (positive or negative) lookahead or lookbehind
+    Lookaround(Box<Lookaround>),
+    /// A backreference or forward reference
+    Reference(Reference),
+    /// Recursively matches the entire regex
+    Recursion,
+}
+
+/// A character set, e.g. `[a-z\d]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CharSet {
+    /// Whether the set is negated, i.e. matches any character _not_ in it
+    pub negative: bool,
+    /// Inclusive character ranges, sorted and without overlaps
+    pub ranges: Vec<(char, char)>,
+    /// Shorthands and Unicode properties
+    pub properties: Vec<CharSetProperty>,
+}
+
+/// An intersection of character sets, which may be negated
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompoundCharSet {
+    /// Whether the intersection is negated
+    pub negative: bool,
+    /// The intersected sets
+    pub intersections: Vec<CharSet>,
+}
+
+/// An item in a character set that isn't a character range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CharSetProperty {
+    /// A shorthand such as `\w`
+    Shorthand(Shorthand),
+    /// A Unicode property such as `\p{Latin}`
+    Property {
+        /// Whether the property is negated, e.g. `\P{Latin}`
+        negative: bool,
+        /// The property
+        value: Property,
+    },
+}
+
+/// A character class shorthand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Shorthand {
+    /// `\w`
+    Word,
+    /// `\d`
+    Digit,
+    /// `\s`
+    Space,
+    /// `\W`
+    NotWord,
+    /// `\D`
+    NotDigit,
+    /// `\S`
+    NotSpace,
+    /// `\v`
+    VertSpace,
+    /// `\h`
+    HorizSpace,
+}
+
+/// A Unicode property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Property {
+    /// A general category, e.g. `Letter`
+    Category(Category),
+    /// A script, e.g. `Latin`, and whether it refers to the script extension
+    Script(Script, ScriptExtension),
+    /// A Unicode block, e.g. `InBasic_Latin`
+    Block(CodeBlock),
+    /// Another boolean property, e.g. `Alphabetic`
+    Other(OtherProperties),
+}
+
+/// A sequence of nodes, possibly wrapped in a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Group {
+    /// The kind of group
+    pub kind: GroupKind,
+    /// The nodes in the group
+    pub parts: Vec<Regex>,
+}
+
+/// The kind of a [`Group`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GroupKind {
+    /// A numbered capturing group
+    Capture,
+    /// A named capturing group
+    NamedCapture(String),
+    /// An atomic group
+    Atomic,
+    /// A non-capturing group, or just a sequence if no parentheses are needed
+    Normal,
+}
+
+/// A repeated expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Repetition {
+    /// The repeated expression
+    pub content: Regex,
+    /// The lower and upper bound
+    pub kind: RepetitionKind,
+    /// Whether the repetition is lazy
+    pub lazy: bool,
+}
+
+/// A lookahead or lookbehind
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Lookaround {
+    /// The kind of lookaround
+    pub kind: LookaroundKind,
+    /// The expression in the lookaround
+    pub content: Regex,
+}
+
+/// A reference to a capturing group
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Reference {
+    /// A reference to a group by its number
+    Number(u32),
+    /// A reference to a named group
+    Name(String),
+}
+
+impl From<&regex::Regex> for Regex {
+    fn from(regex: &regex::Regex) -> Self {
+        match regex {
+            regex::Regex::Literal(s) => Regex::Literal(s.clone()),
+            regex::Regex::Unescaped(s) => Regex::Unescaped(s.clone()),
+            regex::Regex::CharSet(set) => Regex::CharSet(set.into()),
+            regex::Regex::CompoundCharSet(set) => Regex::CompoundCharSet(set.into()),
+            regex::Regex::Grapheme => Regex::Grapheme,
+            regex::Regex::Dot => Regex::Dot,
+            regex::Regex::Group(g) => Regex::Group(Group {
+                kind: match &g.kind {
+                    RegexGroupKind::Capture => GroupKind::Capture,
+                    RegexGroupKind::NamedCapture(name, _) => GroupKind::NamedCapture(name.clone()),
+                    RegexGroupKind::Atomic => GroupKind::Atomic,
+                    RegexGroupKind::Normal => GroupKind::Normal,
+                },
+                parts: g.parts.iter().map(Regex::from).collect(),
+            }),
+            regex::Regex::Alternation(alt) => {
+                Regex::Alternation(alt.parts.iter().map(Regex::from).collect())
+            }
+            regex::Regex::Repetition(rep) => Regex::Repetition(Box::new(Repetition {
+                content: (&rep.content).into(),
+                kind: rep.kind,
+                lazy: rep.quantifier == RegexQuantifier::Lazy,
+            })),
+            &regex::Regex::Boundary(kind) => Regex::Boundary(kind),
+            regex::Regex::Lookaround(look) => Regex::Lookaround(Box::new(Lookaround {
+                kind: look.kind,
+                content: (&look.content).into(),
+            })),
+            regex::Regex::Reference(r) => Regex::Reference(match r {
+                &RegexReference::Number(n) => Reference::Number(n),
+                RegexReference::Name(name) => Reference::Name(name.clone()),
+            }),
+            regex::Regex::Recursion => Regex::Recursion,
+        }
+    }
+}
+
+impl From<&RegexCharSet> for CharSet {
+    fn from(set: &RegexCharSet) -> Self {
+        CharSet {
+            negative: set.negative,
+            ranges: set.set.ranges().map(|range| range.as_chars()).collect(),
+            properties: set.set.props().map(CharSetProperty::from).collect(),
+        }
+    }
+}
+
+impl From<&RegexCompoundCharSet> for CompoundCharSet {
+    fn from(set: &RegexCompoundCharSet) -> Self {
+        CompoundCharSet {
+            negative: set.negative,
+            intersections: set.intersections.iter().map(CharSet::from).collect(),
+        }
+    }
+}
+
+impl From<RegexCharSetItem> for CharSetProperty {
+    fn from(item: RegexCharSetItem) -> Self {
+        match item {
+            RegexCharSetItem::Shorthand(s) => CharSetProperty::Shorthand(match s {
+                RegexShorthand::Word => Shorthand::Word,
+                RegexShorthand::Digit => Shorthand::Digit,
+                RegexShorthand::Space => Shorthand::Space,
+                RegexShorthand::NotWord => Shorthand::NotWord,
+                RegexShorthand::NotDigit => Shorthand::NotDigit,
+                RegexShorthand::NotSpace => Shorthand::NotSpace,
+                RegexShorthand::VertSpace => Shorthand::VertSpace,
+                RegexShorthand::HorizSpace => Shorthand::HorizSpace,
+            }),
+            RegexCharSetItem::Property { negative, value } => CharSetProperty::Property {
+                negative,
+                value: match value {
+                    RegexProperty::Category(c) => Property::Category(c),
+                    RegexProperty::Script(s, e) => Property::Script(s, e),
+                    RegexProperty::Block(b) => Property::Block(b),
+                    RegexProperty::Other(o) => Property::Other(o),
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+use {
+    crate::options::{CompileOptions, RegexFlavor},
+    alloc::vec,
+};
+
+#[cfg(test)]
+fn compile(input: &str, flavor: RegexFlavor) -> Regex {
+    let (expr, _) = crate::Expr::parse(input);
+    let options = CompileOptions::default().with_flavor(flavor);
+    let (output, diagnostics) = expr.unwrap().compile_with_ir(input, options);
+    let Some((_, ir)) = output else { panic!("compilation failed: {diagnostics:?}") };
+    ir
+}
+
+#[cfg(test)]
+fn lit(s: &str) -> Regex {
+    Regex::Literal(s.into())
+}
+
+#[cfg(test)]
+fn sequence(parts: Vec<Regex>) -> Regex {
+    Regex::Group(Group { kind: GroupKind::Normal, parts })
+}
+
+#[test]
+fn ir_groups() {
+    let ir = compile(":('a') :name('b') atomic('c') ('de' | 'f')", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        sequence(vec![
+            Regex::Group(Group { kind: GroupKind::Capture, parts: vec![lit("a")] }),
+            Regex::Group(Group {
+                kind: GroupKind::NamedCapture("name".into()),
+                parts: vec![lit("b")]
+            }),
+            Regex::Group(Group { kind: GroupKind::Atomic, parts: vec![lit("c")] }),
+            Regex::Alternation(vec![lit("de"), lit("f")]),
+        ])
+    );
+}
+
+#[test]
+fn ir_repetitions() {
+    let ir = compile("'ab'{2,5} [w]* lazy ('c' 'd')+", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        sequence(vec![
+            Regex::Repetition(Box::new(Repetition {
+                content: lit("ab"),
+                kind: RepetitionKind { lower_bound: 2, upper_bound: Some(5) },
+                lazy: false,
+            })),
+            Regex::Repetition(Box::new(Repetition {
+                content: Regex::CharSet(CharSet {
+                    negative: false,
+                    ranges: vec![],
+                    properties: vec![CharSetProperty::Shorthand(Shorthand::Word)],
+                }),
+                kind: RepetitionKind { lower_bound: 0, upper_bound: None },
+                lazy: true,
+            })),
+            Regex::Repetition(Box::new(Repetition {
+                content: sequence(vec![lit("c"), lit("d")]),
+                kind: RepetitionKind { lower_bound: 1, upper_bound: None },
+                lazy: false,
+            })),
+        ])
+    );
+}
+
+#[test]
+fn ir_char_sets() {
+    let ir = compile("['a'-'f' '0'-'9' '_' Greek !Letter]", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        Regex::CharSet(CharSet {
+            negative: false,
+            ranges: vec![('0', '9'), ('_', '_'), ('a', 'f')],
+            properties: vec![
+                CharSetProperty::Property {
+                    negative: false,
+                    value: Property::Script(Script::Greek, ScriptExtension::Unspecified),
+                },
+                CharSetProperty::Property {
+                    negative: true,
+                    value: Property::Category(Category::Letter),
+                },
+            ],
+        })
+    );
+
+    let ir = compile("![s 'a'-'c']", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        Regex::CharSet(CharSet {
+            negative: true,
+            ranges: vec![('a', 'c')],
+            properties: vec![CharSetProperty::Shorthand(Shorthand::Space)],
+        })
+    );
+}
+
+#[test]
+fn ir_lookarounds() {
+    let ir = compile("(>> 'a' [d]) (!<< 'b')", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        sequence(vec![
+            Regex::Lookaround(Box::new(Lookaround {
+                kind: LookaroundKind::Ahead,
+                content: sequence(vec![
+                    lit("a"),
+                    Regex::CharSet(CharSet {
+                        negative: false,
+                        ranges: vec![],
+                        properties: vec![CharSetProperty::Shorthand(Shorthand::Digit)],
+                    }),
+                ]),
+            })),
+            Regex::Lookaround(Box::new(Lookaround {
+                kind: LookaroundKind::BehindNegative,
+                content: lit("b"),
+            })),
+        ])
+    );
+}
+
+#[test]
+fn ir_references() {
+    let capture = |kind, s| Regex::Group(Group { kind, parts: vec![lit(s)] });
+
+    let ir = compile(":('a') :name('b') ::1 ::name", RegexFlavor::Pcre);
+    assert_eq!(
+        ir,
+        sequence(vec![
+            capture(GroupKind::Capture, "a"),
+            capture(GroupKind::NamedCapture("name".into()), "b"),
+            Regex::Reference(Reference::Number(1)),
+            Regex::Reference(Reference::Number(2)),
+        ])
+    );
+
+    // Ruby requires references to named groups to be named as well
+    let ir = compile(":name('b') ::name", RegexFlavor::Ruby);
+    assert_eq!(
+        ir,
+        sequence(vec![
+            capture(GroupKind::NamedCapture("name".into()), "b"),
+            Regex::Reference(Reference::Name("name".into())),
+        ])
+    );
+}
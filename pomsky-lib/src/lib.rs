@@ -12,7 +12,7 @@
 //! use pomsky::Expr;
 //! use pomsky::options::{CompileOptions, RegexFlavor};
 //!
-//! let options = CompileOptions { flavor: RegexFlavor::Java, ..Default::default() };
+//! let options = CompileOptions::default().with_flavor(RegexFlavor::Java);
 //! let regex = match Expr::parse_and_compile("'test'", options) {
 //!     (Some(regex), _warnings, _tests) => regex,
 //!     (None, diagnostics, _tests) => {
@@ -31,7 +31,7 @@
 //! use pomsky::diagnose::Diagnostic;
 //!
 //! pub fn compile(input: &str) -> miette::Result<String> {
-//!     let options = CompileOptions { flavor: RegexFlavor::Java, ..Default::default() };
+//!     let options = CompileOptions::default().with_flavor(RegexFlavor::Java);
 //!     let compiled = match Expr::parse_and_compile(input, options) {
 //!         (Some(regex), _warnings, _tests) => regex,
 //!         (None, diagnostics, _tests) => {
@@ -48,15 +48,30 @@
 //! [book]: https://pomsky-lang.org/docs/
 //! [macro]: https://docs.rs/pomsky-macro/latest/pomsky_macro/
 //! [miette]: https://docs.rs/miette/latest/miette/
+//!
+//! ## `no_std`
+//!
+//! The `std` feature is enabled by default. When it is disabled, this crate is
+//! `no_std`, but it still requires the `alloc` crate. The `miette`,
+//! `suggestions` and `arbitrary` features require `std`.
 
+#![no_std]
 #![warn(missing_docs)]
 
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 #[macro_use]
 mod defer;
 
 pub mod diagnose;
 pub mod error;
+pub mod explain;
 pub mod features;
+pub mod format;
+pub mod import;
+pub mod ir;
 pub mod options;
 
 mod capturing_groups;
@@ -65,17 +80,17 @@ mod exprs;
 mod regex;
 mod unicode_set;
 mod validation;
-mod visitor;
 
 /// Re-exports syntax node types related to tests
 pub mod test {
     pub use pomsky_syntax::exprs::test::*;
 }
 
-pub use exprs::Expr;
+pub use exprs::{Expr, builder::ClassItem};
 pub use pomsky_syntax::{
     Span,
     diagnose::{ParseError, ParseWarning as Warning},
+    highlight, line_index, visitor,
 };
 
 pub use pomsky_syntax::list_shorthands;
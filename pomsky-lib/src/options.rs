@@ -1,15 +1,58 @@
 //! Contains parser and compiler options passed to pomsky.
 
-use crate::features::PomskyFeatures;
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
 
-/// Options passed to the pomsky compiler
+use pomsky_syntax::Span;
+
+use crate::{
+    diagnose::{
+        CompileError, CompileErrorKind, Diagnostic, DiagnosticCode, DiagnosticKind, Feature,
+        Severity,
+    },
+    features::PomskyFeatures,
+};
+
+/// Options passed to the pomsky compiler.
+///
+/// New options may be added in the future, so this can't be constructed with
+/// a struct expression outside of this crate. Use [`Default`] and the builder
+/// methods instead:
+///
+/// ```
+/// use pomsky::options::{CompileOptions, RegexFlavor};
+///
+/// let options = CompileOptions::default().with_flavor(RegexFlavor::Java);
+/// ```
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
 pub struct CompileOptions {
     /// The targeted regex flavor. Pomsky makes sure that the emitted regex is
     /// compatible with this flavor.
     pub flavor: RegexFlavor,
 
+    /// The oldest version of the regex engine that the emitted regex must be
+    /// compatible with. If this is `None`, the latest version is assumed.
+    ///
+    /// What the version refers to depends on the flavor. For example, it is
+    /// the ECMAScript edition for [`RegexFlavor::JavaScript`] (e.g. `2018`),
+    /// the version of the `regex` crate for [`RegexFlavor::Rust`], and the
+    /// Python version for [`RegexFlavor::Python`].
+    pub flavor_version: Option<FlavorVersion>,
+
+    /// Additional regex flavors the emitted regex must be compatible with.
+    ///
+    /// When this is not empty, the expression is compiled for [`flavor`] and
+    /// each flavor in this set. Compilation only succeeds if it succeeds for
+    /// every flavor _and_ produces the same regex for all of them. Where the
+    /// flavors support different syntax, pomsky picks one that works in all
+    /// of them. Constructs that match different things in some of the
+    /// flavors, such as `.` or `[word]`, are rejected.
+    ///
+    /// [`flavor`]: CompileOptions::flavor
+    pub additional_flavors: FlavorSet,
+
     /// The maximum number of digits in a `range` expression. Defaults to 6.
     ///
     /// Note that if you increase this number, the time needed to compile a
@@ -17,18 +60,212 @@ pub struct CompileOptions {
     /// input, this can be used for a DoS attack.
     pub max_range_size: u8,
 
+    /// Limits on the size of the expression and the work done by the
+    /// compiler. See [`CompileOptions::untrusted`] for input that could be
+    /// malicious.
+    pub limits: Limits,
+
     /// Allowed pomsky features. By default, all features are allowed.
     pub allowed_features: PomskyFeatures,
+
+    /// Which warnings are reported, and which are turned into errors. By
+    /// default, all warnings are reported.
+    ///
+    /// Warnings can also be silenced in the source code with an `allow`
+    /// statement, e.g. `allow deprecated;`. This takes precedence over
+    /// this configuration.
+    pub lints: Lints,
 }
 
 impl Default for CompileOptions {
     fn default() -> Self {
         Self {
             flavor: RegexFlavor::default(),
+            flavor_version: None,
+            additional_flavors: FlavorSet::default(),
             max_range_size: 6,
+            limits: Limits::default(),
             allowed_features: PomskyFeatures::default(),
+            lints: Lints::default(),
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Returns options suitable for compiling untrusted input, e.g. in a
+    /// web service, with the [`Limits::untrusted`] preset.
+    ///
+    /// ```
+    /// use pomsky::{Expr, diagnose::DiagnosticKind, options::CompileOptions};
+    ///
+    /// let source = "let a = 'a'; let b = a a; let c = b b; let d = c c; d{20000}";
+    /// let (output, diagnostics, _) = Expr::parse_and_compile(source, CompileOptions::untrusted());
+    /// assert!(output.is_none());
+    /// assert_eq!(diagnostics[0].kind, DiagnosticKind::Limits);
+    /// ```
+    pub fn untrusted() -> Self {
+        CompileOptions { limits: Limits::untrusted(), ..Default::default() }
+    }
+
+    /// Sets the targeted regex flavor
+    pub fn with_flavor(mut self, flavor: RegexFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Sets the oldest version of the regex engine that must be supported
+    pub fn with_flavor_version(mut self, version: Option<FlavorVersion>) -> Self {
+        self.flavor_version = version;
+        self
+    }
+
+    /// Sets the additional regex flavors the emitted regex must be compatible
+    /// with
+    pub fn with_additional_flavors(mut self, flavors: FlavorSet) -> Self {
+        self.additional_flavors = flavors;
+        self
+    }
+
+    /// Sets the maximum number of digits in a `range` expression
+    pub fn with_max_range_size(mut self, max_range_size: u8) -> Self {
+        self.max_range_size = max_range_size;
+        self
+    }
+
+    /// Sets the limits on the size of the expression and the work done by
+    /// the compiler
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the allowed pomsky features
+    pub fn with_allowed_features(mut self, allowed_features: PomskyFeatures) -> Self {
+        self.allowed_features = allowed_features;
+        self
+    }
+
+    /// Sets which warnings are reported, and which are turned into errors
+    pub fn with_lints(mut self, lints: Lints) -> Self {
+        self.lints = lints;
+        self
+    }
+
+    /// Returns the targeted flavor and the additional flavors, with their
+    /// minimum versions
+    pub(crate) fn all_flavors(
+        &self,
+    ) -> impl Iterator<Item = (RegexFlavor, Option<FlavorVersion>)> + '_ {
+        core::iter::once((self.flavor, self.flavor_version))
+            .chain(self.additional_flavors.iter().filter(move |&(flavor, _)| flavor != self.flavor))
+    }
+
+    /// Returns an error if the targeted flavor version is too old to support
+    /// the given feature.
+    pub(crate) fn require_version(&self, feature: Feature, span: Span) -> Result<(), CompileError> {
+        match (self.flavor_version, feature.min_version(self.flavor)) {
+            (Some(version), Some(required)) if version < required => {
+                Err(CompileErrorKind::UnsupportedInVersion {
+                    feature,
+                    flavor: self.flavor,
+                    version,
+                    required,
+                }
+                .at(span))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Limits on the size of an expression and the work done to compile it.
+///
+/// When a limit is exceeded, compilation fails with an error of kind
+/// [`DiagnosticKind::Limits`]. Limits that are `None` are not enforced.
+///
+/// ```
+/// use pomsky::options::Limits;
+///
+/// let limits = Limits::default().with_max_repetition(Some(1000)).with_max_output_len(Some(4096));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub struct Limits {
+    /// The maximum nesting depth of groups, repetitions and other expressions
+    /// when parsing. Defaults to 256.
+    pub max_nesting: u32,
+    /// The maximum number of variables that are expanded within each other,
+    /// e.g. 2 for `let a = 'a'; let b = a; b`.
+    pub max_variable_depth: Option<u32>,
+    /// The maximum number of expressions that are compiled, where each use of
+    /// a variable counts separately. This bounds the memory and time needed
+    /// for expressions where variables are expanded exponentially often.
+    pub max_compiled_nodes: Option<u32>,
+    /// The maximum value of repetition bounds, e.g. 5 in `'a'{2,5}`
+    pub max_repetition: Option<u32>,
+    /// The maximum length of the generated regex, in bytes. The literals are
+    /// counted while variables are expanded, so compilation is aborted early
+    /// when this limit is exceeded. Therefore an expression can be rejected
+    /// even if the optimized regex would be shorter.
+    pub max_output_len: Option<u32>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_nesting: 256,
+            max_variable_depth: None,
+            max_compiled_nodes: None,
+            max_repetition: None,
+            max_output_len: None,
+        }
+    }
+}
+
+impl Limits {
+    /// Limits for input that could be malicious, which still allow all
+    /// expressions that are reasonable to write by hand
+    pub fn untrusted() -> Self {
+        Limits {
+            max_nesting: 128,
+            max_variable_depth: Some(64),
+            max_compiled_nodes: Some(100_000),
+            max_repetition: Some(10_000),
+            max_output_len: Some(100_000),
         }
     }
+
+    /// Sets the maximum nesting depth when parsing
+    pub fn with_max_nesting(mut self, max_nesting: u32) -> Self {
+        self.max_nesting = max_nesting;
+        self
+    }
+
+    /// Sets the maximum number of variables that are expanded within each
+    /// other
+    pub fn with_max_variable_depth(mut self, max_variable_depth: Option<u32>) -> Self {
+        self.max_variable_depth = max_variable_depth;
+        self
+    }
+
+    /// Sets the maximum number of compiled expressions
+    pub fn with_max_compiled_nodes(mut self, max_compiled_nodes: Option<u32>) -> Self {
+        self.max_compiled_nodes = max_compiled_nodes;
+        self
+    }
+
+    /// Sets the maximum value of repetition bounds
+    pub fn with_max_repetition(mut self, max_repetition: Option<u32>) -> Self {
+        self.max_repetition = max_repetition;
+        self
+    }
+
+    /// Sets the maximum length of the generated regex, in bytes
+    pub fn with_max_output_len(mut self, max_output_len: Option<u32>) -> Self {
+        self.max_output_len = max_output_len;
+        self
+    }
 }
 
 /// A regex flavor is a regex engine or a set of regex engines that are similar
@@ -42,6 +279,8 @@ pub enum RegexFlavor {
     Pcre,
     /// Python's `re` module
     Python,
+    /// Python's third-party [`regex`](https://pypi.org/project/regex/) module
+    PythonRegex,
     /// The `java.util.regex.Pattern` class
     Java,
     /// JavaScript (ECMAScript) built-in regular expressions
@@ -54,4 +293,408 @@ pub enum RegexFlavor {
     Rust,
     /// The RE2 library
     RE2,
+    /// PostgreSQL's Advanced Regular Expressions (AREs), used by the `~`
+    /// operator and functions such as `regexp_match`
+    Postgres,
+}
+
+impl RegexFlavor {
+    const ALL: [RegexFlavor; 10] = [
+        RegexFlavor::Pcre,
+        RegexFlavor::Python,
+        RegexFlavor::PythonRegex,
+        RegexFlavor::Java,
+        RegexFlavor::JavaScript,
+        RegexFlavor::DotNet,
+        RegexFlavor::Ruby,
+        RegexFlavor::Rust,
+        RegexFlavor::RE2,
+        RegexFlavor::Postgres,
+    ];
+}
+
+/// A set of regex flavors, each with an optional minimum version.
+///
+/// ### Example
+///
+/// ```
+/// use pomsky::options::{FlavorSet, RegexFlavor};
+///
+/// let flavors = FlavorSet::new()
+///     .add(RegexFlavor::JavaScript, "2018".parse().ok())
+///     .add(RegexFlavor::Java, None);
+/// assert!(flavors.contains(RegexFlavor::Java));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct FlavorSet {
+    bits: u16,
+    versions: [Option<FlavorVersion>; RegexFlavor::ALL.len()],
+}
+
+impl FlavorSet {
+    /// Creates an empty set of flavors
+    pub fn new() -> Self {
+        FlavorSet::default()
+    }
+
+    /// Adds a flavor with an optional minimum version to the set. If the
+    /// flavor is already in the set, its version is replaced.
+    pub fn add(mut self, flavor: RegexFlavor, version: Option<FlavorVersion>) -> Self {
+        let idx = Self::index(flavor);
+        self.bits |= 1 << idx;
+        self.versions[idx] = version;
+        self
+    }
+
+    /// Returns whether the flavor is in the set
+    pub fn contains(&self, flavor: RegexFlavor) -> bool {
+        self.bits & (1 << Self::index(flavor)) != 0
+    }
+
+    /// Returns whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns an iterator over the flavors in this set, together with their
+    /// minimum versions
+    pub fn iter(&self) -> impl Iterator<Item = (RegexFlavor, Option<FlavorVersion>)> + '_ {
+        RegexFlavor::ALL
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| self.bits & (1 << i) != 0)
+            .map(|(i, flavor)| (flavor, self.versions[i]))
+    }
+
+    fn index(flavor: RegexFlavor) -> usize {
+        RegexFlavor::ALL.iter().position(|&f| f == flavor).unwrap()
+    }
+}
+
+/// The version of a regex engine, consisting of a major and a minor version
+/// number, e.g. `3.11`.
+///
+/// It can be parsed from a string like `"3.11"` or `"2018"`; a missing minor
+/// version is treated as 0. A patch version, as in `"3.11.2"`, is accepted
+/// but ignored, since regex syntax doesn't change in patch releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct FlavorVersion {
+    /// The major version number
+    pub major: u16,
+    /// The minor version number
+    pub minor: u16,
+}
+
+impl FlavorVersion {
+    /// Creates a new version from a major and minor version number
+    pub const fn new(major: u16, minor: u16) -> Self {
+        FlavorVersion { major, minor }
+    }
+}
+
+impl fmt::Display for FlavorVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.minor == 0 {
+            write!(f, "{}", self.major)
+        } else {
+            write!(f, "{}.{}", self.major, self.minor)
+        }
+    }
+}
+
+/// Error returned when parsing a [`FlavorVersion`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseFlavorVersionError;
+
+impl fmt::Display for ParseFlavorVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid version, expected a version like `3.11`")
+    }
+}
+
+impl core::error::Error for ParseFlavorVersionError {}
+
+impl FromStr for FlavorVersion {
+    type Err = ParseFlavorVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '.');
+        let major = parts.next().unwrap_or_default();
+        let minor = parts.next().unwrap_or("0");
+        let parse = |n: &str| {
+            if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) {
+                n.parse().map_err(|_| ParseFlavorVersionError)
+            } else {
+                Err(ParseFlavorVersionError)
+            }
+        };
+        if let Some(patch) = parts.next() {
+            parse(patch)?;
+        }
+        Ok(FlavorVersion { major: parse(major)?, minor: parse(minor)? })
+    }
+}
+
+/// How a warning is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum LintLevel {
+    /// The warning is not reported
+    Allow,
+    /// The warning is reported as a warning
+    Warn,
+    /// The warning is turned into an error
+    Deny,
+}
+
+impl LintLevel {
+    fn from_bits(bits: u64) -> Option<Self> {
+        match bits {
+            1 => Some(LintLevel::Allow),
+            2 => Some(LintLevel::Warn),
+            3 => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+
+    fn bits(self) -> u64 {
+        match self {
+            LintLevel::Allow => 1,
+            LintLevel::Warn => 2,
+            LintLevel::Deny => 3,
+        }
+    }
+}
+
+/// A group of warnings that can be configured in [`Lints`], either all
+/// warnings of a [`DiagnosticKind`] or the warnings with a [`DiagnosticCode`].
+///
+/// It can be parsed from a string such as `"deprecated"` or `"P0105"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    /// All warnings of a kind
+    Kind(DiagnosticKind),
+    /// All warnings with a code
+    Code(DiagnosticCode),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Lint::Kind(kind) => f.write_str(kind.into()),
+            Lint::Code(code) => code.fmt(f),
+        }
+    }
+}
+
+/// Error returned when parsing a [`Lint`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLintError;
+
+impl fmt::Display for ParseLintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "invalid lint, expected a diagnostic kind like `deprecated` or a code like `P0105`",
+        )
+    }
+}
+
+impl core::error::Error for ParseLintError {}
+
+impl FromStr for Lint {
+    type Err = ParseLintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(digits) = s.strip_prefix('P')
+            && digits.len() == 4
+            && digits.bytes().all(|b| b.is_ascii_digit())
+        {
+            let code = digits.parse::<u16>().map_err(|_| ParseLintError)?;
+            return DiagnosticCode::try_from(code).map(Lint::Code).map_err(|_| ParseLintError);
+        }
+        s.parse().map(Lint::Kind).map_err(|_| ParseLintError)
+    }
+}
+
+/// Configures which warnings are reported, and which are turned into errors.
+///
+/// A level set for a [`DiagnosticCode`] takes precedence over a level set for
+/// a [`DiagnosticKind`], which takes precedence over the default level.
+///
+/// ### Example
+///
+/// ```
+/// use pomsky::{
+///     Expr,
+///     diagnose::{DiagnosticCode, DiagnosticKind, Severity},
+///     options::{CompileOptions, Lint, LintLevel, Lints},
+/// };
+///
+/// // a shorthand in a character range is deprecated
+/// let input = "[U+1-n]";
+/// let (output, diagnostics, _) = Expr::parse_and_compile(input, CompileOptions::default());
+/// assert_eq!(output.as_deref(), Some(r"[\x01-\n]"));
+/// assert_eq!(diagnostics[0].code, Some(DiagnosticCode::DeprecatedSyntax));
+/// assert_eq!(diagnostics[0].severity, Severity::Warning);
+///
+/// let lints = Lints::new()
+///     .set_default(LintLevel::Deny)
+///     .set(Lint::Kind(DiagnosticKind::Compat), LintLevel::Warn);
+/// let options = CompileOptions::default().with_lints(lints);
+///
+/// // deprecated syntax is an error now
+/// let (output, diagnostics, _) = Expr::parse_and_compile(input, options);
+/// assert!(output.is_none());
+/// assert_eq!(diagnostics[0].code, Some(DiagnosticCode::DeprecatedSyntax));
+/// assert_eq!(diagnostics[0].severity, Severity::Error);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Lints {
+    /// The levels of the diagnostic kinds and the default level, 2 bits each,
+    /// encoded by `LintLevel::bits`. 0 means the level is not set.
+    kinds: u32,
+    /// The levels of the diagnostic codes, 2 bits each
+    codes: [u64; Lints::CODE_WORDS],
+}
+
+impl Lints {
+    const DEFAULT_SLOT: u32 = 9;
+    /// Diagnostic codes are grouped by hundreds, and each group has at most
+    /// 32 codes
+    const CODE_GROUP_SIZE: usize = 32;
+    const CODE_WORDS: usize = 7;
+
+    /// Creates a configuration where all warnings are reported
+    pub fn new() -> Self {
+        Lints::default()
+    }
+
+    /// Sets the level of warnings that aren't configured otherwise.
+    /// For example, [`LintLevel::Deny`] turns all warnings into errors.
+    pub fn set_default(mut self, level: LintLevel) -> Self {
+        self.kinds = set_bits(self.kinds as u64, Self::DEFAULT_SLOT, level) as u32;
+        self
+    }
+
+    /// Sets the level of a group of warnings
+    pub fn set(mut self, lint: Lint, level: LintLevel) -> Self {
+        match lint {
+            Lint::Kind(kind) => {
+                self.kinds = set_bits(self.kinds as u64, Self::kind_slot(kind), level) as u32;
+            }
+            Lint::Code(code) => {
+                let (word, slot) = Self::code_slot(code);
+                self.codes[word] = set_bits(self.codes[word], slot, level);
+            }
+        }
+        self
+    }
+
+    /// Returns the level of a warning with the given kind and code
+    pub fn level(&self, kind: DiagnosticKind, code: Option<DiagnosticCode>) -> LintLevel {
+        code.and_then(|code| {
+            let (word, slot) = Self::code_slot(code);
+            get_bits(self.codes[word], slot)
+        })
+        .or_else(|| get_bits(self.kinds as u64, Self::kind_slot(kind)))
+        .or_else(|| get_bits(self.kinds as u64, Self::DEFAULT_SLOT))
+        .unwrap_or(LintLevel::Warn)
+    }
+
+    /// Applies the configuration to the diagnostics: Allowed warnings are
+    /// removed, and denied warnings are turned into errors. Errors are not
+    /// affected.
+    ///
+    /// Returns `true` if a warning was turned into an error.
+    pub fn apply(&self, diagnostics: &mut Vec<Diagnostic>) -> bool {
+        let mut denied = false;
+        diagnostics.retain_mut(|d| {
+            if d.severity != Severity::Warning {
+                return true;
+            }
+            match self.level(d.kind, d.code) {
+                LintLevel::Allow => false,
+                LintLevel::Warn => true,
+                LintLevel::Deny => {
+                    d.severity = Severity::Error;
+                    denied = true;
+                    true
+                }
+            }
+        });
+        denied
+    }
+
+    fn kind_slot(kind: DiagnosticKind) -> u32 {
+        match kind {
+            DiagnosticKind::Syntax => 0,
+            DiagnosticKind::Resolve => 1,
+            DiagnosticKind::Compat => 2,
+            DiagnosticKind::Unsupported => 3,
+            DiagnosticKind::Deprecated => 4,
+            DiagnosticKind::Limits => 5,
+            DiagnosticKind::Invalid => 6,
+            DiagnosticKind::Test => 7,
+            DiagnosticKind::Other => 8,
+        }
+    }
+
+    /// Returns the index in `codes` and the slot within the `u64`
+    fn code_slot(code: DiagnosticCode) -> (usize, u32) {
+        let code = code as usize;
+        let index = code / 100 * Self::CODE_GROUP_SIZE + code % 100;
+        (index / 32, (index % 32) as u32)
+    }
+}
+
+fn get_bits(bits: u64, slot: u32) -> Option<LintLevel> {
+    LintLevel::from_bits(bits >> (slot * 2) & 0b11)
+}
+
+fn set_bits(bits: u64, slot: u32, level: LintLevel) -> u64 {
+    bits & !(0b11 << (slot * 2)) | level.bits() << (slot * 2)
+}
+
+#[test]
+fn all_codes_fit_in_lints() {
+    for code in (0..1000).filter_map(|n| DiagnosticCode::try_from(n).ok()) {
+        assert!((code as usize % 100) < Lints::CODE_GROUP_SIZE, "{code} doesn't fit");
+        assert!(Lints::code_slot(code).0 < Lints::CODE_WORDS, "{code} doesn't fit");
+        let lints = Lints::new().set(Lint::Code(code), LintLevel::Deny);
+        assert_eq!(lints.level(DiagnosticKind::Other, Some(code)), LintLevel::Deny);
+    }
+}
+
+#[test]
+fn untrusted_output_len_is_checked_while_expanding() {
+    use alloc::format;
+
+    // a 30 KB literal that is repeated 10^9 times
+    let mut source = format!("let a = '{}';", "x".repeat(30_000));
+    for (var, prev) in "bcdefghij".chars().zip("abcdefghi".chars()) {
+        let uses = format!("{prev} ").repeat(10);
+        source.push_str(&format!("\nlet {var} = {};", uses.trim_end()));
+    }
+    source.push_str("\nj");
+
+    let (output, diagnostics, _) =
+        crate::Expr::parse_and_compile(&source, CompileOptions::untrusted());
+    assert!(output.is_none());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Some(DiagnosticCode::OutputIsTooBig));
+}
+
+#[test]
+fn parse_flavor_version() {
+    assert_eq!("3.11".parse(), Ok(FlavorVersion::new(3, 11)));
+    assert_eq!("2018".parse(), Ok(FlavorVersion::new(2018, 0)));
+    assert_eq!("3.11.2".parse(), Ok(FlavorVersion::new(3, 11)));
+    assert_eq!("1.10.2".parse(), Ok(FlavorVersion::new(1, 10)));
+
+    for invalid in ["", "3.", ".11", "3.x", "3.11.", "3.11.x", "3.11.2.1", "-3", "99999"] {
+        assert_eq!(invalid.parse::<FlavorVersion>(), Err(ParseFlavorVersionError), "{invalid}");
+    }
 }
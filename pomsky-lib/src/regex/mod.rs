@@ -1,4 +1,8 @@
-use std::borrow::Borrow;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::borrow::Borrow;
 
 use pomsky_syntax::{
     Span,
@@ -348,7 +352,9 @@ impl Regex {
         }
     }
 
-    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor) {
+    /// Writes the regex to `buf`. If `portable` is true, the regex is compiled
+    /// for several flavors at once (see [`literal::compile_char`]).
+    pub(crate) fn codegen(&self, buf: &mut String, flavor: RegexFlavor, portable: bool) {
         match self {
             Regex::Literal(l) => {
                 // normalize line breaks: within string literals, \r, \n and \r\n should be
@@ -356,28 +362,30 @@ impl Regex {
                 let mut chars = l.chars();
                 while let Some(c) = chars.next() {
                     if c == '\r' {
-                        literal::codegen_char_esc('\n', buf, flavor);
+                        literal::codegen_char_esc('\n', buf, flavor, portable);
                         match chars.next() {
                             Some('\n') | None => {}
-                            Some(c) => literal::codegen_char_esc(c, buf, flavor),
+                            Some(c) => literal::codegen_char_esc(c, buf, flavor, portable),
                         }
                     } else {
-                        literal::codegen_char_esc(c, buf, flavor);
+                        literal::codegen_char_esc(c, buf, flavor, portable);
                     }
                 }
             }
             Regex::Unescaped(u) => {
                 buf.push_str(u);
             }
-            Regex::CharSet(c) => c.codegen(buf, flavor, false),
-            Regex::CompoundCharSet(c) => c.codegen(buf, flavor),
+            Regex::CharSet(c) => c.codegen(buf, flavor, portable, false),
+            Regex::CompoundCharSet(c) => c.codegen(buf, flavor, portable),
             Regex::Grapheme => buf.push_str("\\X"),
+            // in PostgreSQL, the dot also matches line breaks by default
+            Regex::Dot if flavor == RegexFlavor::Postgres => buf.push_str("[^\\n]"),
             Regex::Dot => buf.push('.'),
-            Regex::Group(g) => g.codegen(buf, flavor),
-            Regex::Alternation(a) => a.codegen(buf, flavor),
-            Regex::Repetition(r) => r.codegen(buf, flavor),
+            Regex::Group(g) => g.codegen(buf, flavor, portable),
+            Regex::Alternation(a) => a.codegen(buf, flavor, portable),
+            Regex::Repetition(r) => r.codegen(buf, flavor, portable),
             Regex::Boundary(b) => boundary_kind_codegen(*b, buf, flavor),
-            Regex::Lookaround(l) => l.codegen(buf, flavor),
+            Regex::Lookaround(l) => l.codegen(buf, flavor, portable),
             Regex::Reference(r) => r.codegen(buf),
             Regex::Recursion => recursion::codegen(buf, flavor),
         }
@@ -512,7 +520,7 @@ impl RegexProperty {
                         _ => buf.push_str(&b.as_str().replace('-', "_")),
                     };
                 }
-                RegexFlavor::Ruby => {
+                RegexFlavor::Ruby | RegexFlavor::PythonRegex => {
                     buf.push_str("In");
                     buf.push_str(b.as_str());
                 }
@@ -1,4 +1,10 @@
-use std::{mem, ops::Add};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{mem, ops::Add};
 
 use pomsky_syntax::exprs::RepetitionKind;
 
@@ -59,7 +65,7 @@ impl Regex {
                     return count;
                 }
 
-                if matches!(g.kind, RegexGroupKind::Capture | RegexGroupKind::NamedCapture(_)) {
+                if matches!(g.kind, RegexGroupKind::Capture | RegexGroupKind::NamedCapture(..)) {
                     Count::One
                 } else if g.parts.is_empty() {
                     // indicates that the parent should remove it
@@ -117,7 +123,7 @@ impl Regex {
                             | (Regex::CharSet(char_set), Regex::Literal(lit))
                                 if !char_set.negative =>
                             {
-                                let mut char_set = std::mem::take(char_set);
+                                let mut char_set = core::mem::take(char_set);
                                 char_set.set.add_char(lit.chars().next().unwrap());
                                 *lhs = Regex::CharSet(char_set);
                                 true
@@ -1,7 +1,7 @@
-use core::fmt;
-use std::{
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use core::{
     cmp::Ordering,
-    collections::BTreeSet,
+    fmt,
     ops::{Add, AddAssign, RangeInclusive},
 };
 
@@ -98,7 +98,7 @@ impl AddAssign for SetRange {
 }
 
 impl fmt::Debug for SetRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let (a, b) = self.as_chars();
         write!(f, "{a:?}..{b:?}")
     }
@@ -1,10 +1,12 @@
-use pomsky_syntax::{Span, exprs};
+use pomsky_syntax::{
+    Span, exprs,
+    visitor::{NestingKind, RuleVisitor},
+};
 
 use crate::{
     diagnose::{CompileError, CompileErrorKind, Feature},
     features::PomskyFeatures as Feat,
-    options::{CompileOptions, RegexFlavor},
-    visitor::{NestingKind, RuleVisitor},
+    options::{CompileOptions, Lint, RegexFlavor},
 };
 
 #[derive(Clone)]
@@ -46,6 +48,12 @@ impl RuleVisitor<CompileError> for Validator {
             return Err(CompileErrorKind::Unsupported(Feature::RepetitionAbove1000, self.flavor())
                 .at(repetition.span));
         }
+        if let Some(max) = self.options.limits.max_repetition {
+            let kind = repetition.kind;
+            if kind.lower_bound > max || kind.upper_bound.is_some_and(|upper| upper > max) {
+                return Err(CompileErrorKind::RepetitionIsTooBig(max).at(repetition.span));
+            }
+        }
         Ok(())
     }
 
@@ -58,8 +66,10 @@ impl RuleVisitor<CompileError> for Validator {
             exprs::GroupKind::Atomic => {
                 self.require(Feat::ATOMIC_GROUPS, group.span)?;
 
-                if let RegexFlavor::JavaScript | RegexFlavor::Rust | RegexFlavor::RE2 =
-                    self.flavor()
+                if let RegexFlavor::JavaScript
+                | RegexFlavor::Rust
+                | RegexFlavor::RE2
+                | RegexFlavor::Postgres = self.flavor()
                 {
                     return Err(CompileErrorKind::Unsupported(
                         Feature::AtomicGroups,
@@ -67,6 +77,7 @@ impl RuleVisitor<CompileError> for Validator {
                     )
                     .at(group.span));
                 }
+                self.options.require_version(Feature::AtomicGroups, group.span)?;
             }
             exprs::GroupKind::Capturing(c) => {
                 let feature = match &c.name {
@@ -75,6 +86,17 @@ impl RuleVisitor<CompileError> for Validator {
                 };
 
                 self.require(feature, group.span)?;
+
+                if c.name.is_some() && self.flavor() == RegexFlavor::Postgres {
+                    return Err(CompileErrorKind::Unsupported(
+                        Feature::NamedCaptureGroups,
+                        self.flavor(),
+                    )
+                    .at(group.span));
+                }
+                if c.name.is_some() {
+                    self.options.require_version(Feature::NamedCaptureGroups, group.span)?;
+                }
             }
             _ => (),
         }
@@ -96,6 +118,8 @@ impl RuleVisitor<CompileError> for Validator {
 
         if let flavor @ (RegexFlavor::Rust | RegexFlavor::RE2) = self.flavor() {
             Err(CompileErrorKind::Unsupported(Feature::Lookaround, flavor).at(lookaround.span))
+        } else if let LookaroundKind::Behind | LookaroundKind::BehindNegative = lookaround.kind {
+            self.options.require_version(Feature::Lookbehind, lookaround.span)
         } else {
             Ok(())
         }
@@ -122,6 +146,9 @@ impl RuleVisitor<CompileError> for Validator {
             Stmt::Disable(BS::Unicode, span) => self.require(Feat::ASCII_MODE, *span),
             Stmt::Let(l) => self.require(Feat::VARIABLES, l.name_span),
             Stmt::Test(t) if self.layer > 0 => Err(CompileErrorKind::NestedTest.at(t.span)),
+            Stmt::Allow(a) if a.lint.parse::<Lint>().is_err() => {
+                Err(CompileErrorKind::UnknownLint(a.lint.clone()).at(a.lint_span))
+            }
             _ => Ok(()),
         }
     }
@@ -137,7 +164,7 @@ impl RuleVisitor<CompileError> for Validator {
             self.first_recursion = Some(recursion.span);
         }
 
-        if let RegexFlavor::Pcre | RegexFlavor::Ruby = self.flavor() {
+        if let RegexFlavor::Pcre | RegexFlavor::Ruby | RegexFlavor::PythonRegex = self.flavor() {
             Ok(())
         } else {
             Err(CompileErrorKind::Unsupported(Feature::Recursion, self.flavor()).at(recursion.span))
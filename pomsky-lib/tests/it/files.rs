@@ -4,8 +4,8 @@ use std::{
 };
 
 use pomsky::{
-    diagnose::{Diagnostic, Severity},
-    options::{CompileOptions, RegexFlavor},
+    diagnose::{Diagnostic, QuickFix, Severity},
+    options::{CompileOptions, FlavorSet, FlavorVersion, LintLevel, Lints, RegexFlavor},
     test::{CaptureIdent, TestCapture, TestCase},
 };
 use regex_test::RegexTest;
@@ -25,10 +25,23 @@ pub(crate) enum TestResult {
 struct Options {
     /// The regex flavor to compile with
     flavor: RegexFlavor,
+    /// The minimum version of the regex flavor
+    flavor_version: Option<FlavorVersion>,
+    /// Additional flavors the regex must be compatible with
+    also: FlavorSet,
     /// Whether this test should be ignored entirely
     ignore: bool,
     /// Whether we expect a compilation error from pomsky or not
     expected_outcome: Outcome,
+    /// Whether warnings are turned into errors
+    deny_warnings: bool,
+    /// Whether the limits for untrusted input are enforced
+    untrusted: bool,
+    /// Whether the output is the English explanation instead of the regex
+    explain: bool,
+    /// Whether the input is a regex that is imported, and the output is the
+    /// pomsky expression
+    import: bool,
     /// Whether we attempt to compile the output with the `regex` crate.
     ///
     /// Defaults to `true` if the regex flavor is `rust`.
@@ -39,8 +52,14 @@ impl Default for Options {
     fn default() -> Self {
         Self {
             flavor: RegexFlavor::Rust,
+            flavor_version: None,
+            also: FlavorSet::new(),
             ignore: false,
             expected_outcome: Outcome::Success,
+            deny_warnings: false,
+            untrusted: false,
+            explain: false,
+            import: false,
             compile: true,
         }
     }
@@ -56,21 +75,31 @@ impl Options {
             let (key, value) = part.split_once('=').unwrap_or((part, ""));
             match key {
                 "flavor" => {
-                    result.flavor = match value.to_ascii_lowercase().as_str() {
-                        "pcre" => RegexFlavor::Pcre,
-                        "js" | "javascript" => RegexFlavor::JavaScript,
-                        "java" => RegexFlavor::Java,
-                        ".net" | "dotnet" => RegexFlavor::DotNet,
-                        "python" => RegexFlavor::Python,
-                        "rust" => RegexFlavor::Rust,
-                        "ruby" => RegexFlavor::Ruby,
-                        "re2" => RegexFlavor::RE2,
-                        _ => {
-                            eprintln!("{}: Unknown flavor {value:?}", yellow("Warning"));
+                    let (value, version) = value.split_once('@').unwrap_or((value, ""));
+                    if !version.is_empty() {
+                        let Ok(version) = version.parse() else {
+                            eprintln!("{}: Invalid flavor version {version:?}", yellow("Warning"));
                             eprintln!("  in {path:?}");
                             continue;
-                        }
+                        };
+                        result.flavor_version = Some(version);
+                    }
+                    let Some(flavor) = parse_flavor(value) else {
+                        eprintln!("{}: Unknown flavor {value:?}", yellow("Warning"));
+                        eprintln!("  in {path:?}");
+                        continue;
                     };
+                    result.flavor = flavor;
+                }
+                "also" => {
+                    for value in value.split_whitespace() {
+                        let Some(flavor) = parse_flavor(value) else {
+                            eprintln!("{}: Unknown flavor {value:?}", yellow("Warning"));
+                            eprintln!("  in {path:?}");
+                            continue;
+                        };
+                        result.also = result.also.add(flavor, None);
+                    }
                 }
                 "expect" => {
                     result.expected_outcome = match value {
@@ -94,6 +123,50 @@ impl Options {
                         }
                     }
                 }
+                "deny_warnings" => {
+                    result.deny_warnings = match value {
+                        "yes" | "true" | "" => true,
+                        "no" | "false" => false,
+                        _ => {
+                            eprintln!("{}: Unknown boolean {value:?}", yellow("Warning"));
+                            eprintln!("  in {path:?}");
+                            continue;
+                        }
+                    }
+                }
+                "untrusted" => {
+                    result.untrusted = match value {
+                        "yes" | "true" | "" => true,
+                        "no" | "false" => false,
+                        _ => {
+                            eprintln!("{}: Unknown boolean {value:?}", yellow("Warning"));
+                            eprintln!("  in {path:?}");
+                            continue;
+                        }
+                    }
+                }
+                "explain" => {
+                    result.explain = match value {
+                        "yes" | "true" | "" => true,
+                        "no" | "false" => false,
+                        _ => {
+                            eprintln!("{}: Unknown boolean {value:?}", yellow("Warning"));
+                            eprintln!("  in {path:?}");
+                            continue;
+                        }
+                    }
+                }
+                "import" => {
+                    result.import = match value {
+                        "yes" | "true" | "" => true,
+                        "no" | "false" => false,
+                        _ => {
+                            eprintln!("{}: Unknown boolean {value:?}", yellow("Warning"));
+                            eprintln!("  in {path:?}");
+                            continue;
+                        }
+                    }
+                }
                 "compile" => {
                     compile = Some(match value {
                         "yes" | "true" | "" => true,
@@ -116,9 +189,25 @@ impl Options {
     }
 }
 
+fn parse_flavor(value: &str) -> Option<RegexFlavor> {
+    Some(match value.to_ascii_lowercase().as_str() {
+        "pcre" => RegexFlavor::Pcre,
+        "js" | "javascript" => RegexFlavor::JavaScript,
+        "java" => RegexFlavor::Java,
+        ".net" | "dotnet" => RegexFlavor::DotNet,
+        "python" => RegexFlavor::Python,
+        "pythonregex" | "python-regex" => RegexFlavor::PythonRegex,
+        "rust" => RegexFlavor::Rust,
+        "ruby" => RegexFlavor::Ruby,
+        "re2" => RegexFlavor::RE2,
+        "postgres" => RegexFlavor::Postgres,
+        _ => return None,
+    })
+}
+
 fn can_compile_regex(flavor: RegexFlavor) -> bool {
     use RegexFlavor::*;
-    matches!(flavor, Rust | Pcre | Ruby | JavaScript | Java | Python | DotNet)
+    matches!(flavor, Rust | Pcre | Ruby | JavaScript | Java | Python | PythonRegex | DotNet)
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -148,10 +237,24 @@ pub(crate) fn test_file(
     if options.ignore && !args.include_ignored {
         return TestResult::Ignored;
     }
+    if options.explain {
+        return test_explanation(input, expected, path, args, options);
+    }
+    if options.import {
+        return test_import(input, expected, path, args, options);
+    }
 
     let parsed = pomsky::Expr::parse_and_compile(
         &input_owned,
-        CompileOptions { flavor: options.flavor, ..Default::default() },
+        (if options.untrusted { CompileOptions::untrusted() } else { CompileOptions::default() })
+            .with_flavor(options.flavor)
+            .with_flavor_version(options.flavor_version)
+            .with_additional_flavors(options.also)
+            .with_lints(if options.deny_warnings {
+                Lints::new().set_default(LintLevel::Deny)
+            } else {
+                Lints::new()
+            }),
     );
 
     match parsed {
@@ -160,6 +263,7 @@ pub(crate) fn test_file(
             for warning in warnings {
                 got.push_str("\nWARNING: ");
                 let _ = got.write_fmt(format_args!("{warning}\n  at {}", warning.span));
+                got.push_str(&fixes_to_string(&warning.fixes));
             }
             for test in tests {
                 for case in test.cases {
@@ -223,6 +327,7 @@ pub(crate) fn test_file(
                             RegexFlavor::JavaScript => proc.test_js(regex),
                             RegexFlavor::Java => proc.test_java(regex),
                             RegexFlavor::Python => proc.test_python(regex),
+                            RegexFlavor::PythonRegex => proc.test_python_regex(regex),
                             RegexFlavor::DotNet => proc.test_dotnet(regex),
                             _ => {
                                 eprintln!(
@@ -286,6 +391,83 @@ pub(crate) fn test_file(
     }
 }
 
+fn test_explanation(
+    input: &str,
+    expected: &str,
+    path: PathBuf,
+    args: &Args,
+    options: Options,
+) -> TestResult {
+    let (got, outcome) = match pomsky::explain::explain(input, options.flavor) {
+        (Some(explanation), _) => (Ok(explanation.to_string()), Outcome::Success),
+        (None, errors) => (Err(errors_to_string(errors)), Outcome::Error),
+    };
+    check_outcome(input, expected, got, outcome, path, args, options)
+}
+
+/// Imports the regex and checks that the resulting pomsky expression compiles
+fn test_import(
+    input: &str,
+    expected: &str,
+    path: PathBuf,
+    args: &Args,
+    options: Options,
+) -> TestResult {
+    let (got, outcome) = match pomsky::import::import(input, options.flavor) {
+        (Some(expr), warnings) => {
+            let compile_options = CompileOptions::default().with_flavor(options.flavor);
+            if let (None, errors, _) = pomsky::Expr::parse_and_compile(&expr, compile_options) {
+                return TestResult::InvalidOutput(format!(
+                    "The imported expression doesn't compile:\n{expr}\n{}",
+                    errors_to_string(errors)
+                ));
+            }
+
+            let mut got = expr;
+            if !warnings.is_empty() {
+                got.push('\n');
+                got.push_str(&errors_to_string(warnings));
+            }
+            (Ok(got), Outcome::Success)
+        }
+        (None, errors) => (Err(errors_to_string(errors)), Outcome::Error),
+    };
+    check_outcome(input, expected, got, outcome, path, args, options)
+}
+
+fn check_outcome(
+    input: &str,
+    expected: &str,
+    got: Result<String, String>,
+    outcome: Outcome,
+    path: PathBuf,
+    args: &Args,
+    options: Options,
+) -> TestResult {
+    let got_str = match &got {
+        Ok(s) | Err(s) => s.as_str(),
+    };
+
+    let outcome_matches = matches!(
+        (options.expected_outcome, outcome),
+        (Outcome::Success, Outcome::Success) | (Outcome::Error, Outcome::Error)
+    );
+    if outcome_matches && got_str == expected {
+        TestResult::Success
+    } else if args.bless {
+        let contents =
+            create_content(input, got_str, Options { expected_outcome: outcome, ..options });
+        std::fs::write(path, contents).expect("Failed to bless test because of IO error");
+        TestResult::Blessed
+    } else {
+        TestResult::IncorrectResult {
+            input: input.to_string(),
+            expected: options.expected_outcome.of(expected.to_string()),
+            got,
+        }
+    }
+}
+
 fn errors_to_string(diagnostics: Vec<Diagnostic>) -> String {
     diagnostics
         .into_iter()
@@ -294,16 +476,32 @@ fn errors_to_string(diagnostics: Vec<Diagnostic>) -> String {
                 Severity::Error => "ERROR",
                 Severity::Warning => "WARNING",
             };
+            let fixes = fixes_to_string(&diagnostic.fixes);
             if let Some(help) = diagnostic.help {
-                format!("{sev}: {}\nHELP: {help}\nSPAN: {}", diagnostic.msg, diagnostic.span)
+                format!("{sev}: {}\nHELP: {help}\nSPAN: {}{fixes}", diagnostic.msg, diagnostic.span)
             } else {
-                format!("{sev}: {}\nSPAN: {}", diagnostic.msg, diagnostic.span)
+                format!("{sev}: {}\nSPAN: {}{fixes}", diagnostic.msg, diagnostic.span)
             }
         })
         .collect::<Vec<_>>()
         .join("\n\n")
 }
 
+fn fixes_to_string(fixes: &[QuickFix]) -> String {
+    let mut result = String::new();
+    for fix in fixes {
+        let replacements = fix
+            .replacements
+            .iter()
+            .map(|r| format!("{} => {:?}", r.span, r.insert))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let unsafe_ = if fix.preserves_meaning { "" } else { " (may change meaning)" };
+        let _ = write!(result, "\nFIX{unsafe_}: {} ({replacements})", fix.description);
+    }
+    result
+}
+
 fn process_content<'a>(content: &'a str, path: &Path) -> (&'a str, &'a str, Options) {
     let (mut input, expected) = content.split_once("\n-----").unwrap_or((content, ""));
     let expected = expected.trim_start_matches('-');
@@ -327,9 +525,27 @@ fn create_content(input: &str, outcome: &str, options: Options) -> String {
     if options.ignore {
         option_strings.push(String::from("ignore"));
     }
-    if options.flavor != RegexFlavor::Rust {
+    if options.deny_warnings {
+        option_strings.push(String::from("deny_warnings"));
+    }
+    if options.untrusted {
+        option_strings.push(String::from("untrusted"));
+    }
+    if options.explain {
+        option_strings.push(String::from("explain"));
+    }
+    if options.import {
+        option_strings.push(String::from("import"));
+    }
+    if let Some(version) = options.flavor_version {
+        option_strings.push(format!("flavor={:?}@{version}", options.flavor));
+    } else if options.flavor != RegexFlavor::Rust {
         option_strings.push(format!("flavor={:?}", options.flavor));
     }
+    if !options.also.is_empty() {
+        let flavors: Vec<_> = options.also.iter().map(|(f, _)| format!("{f:?}")).collect();
+        option_strings.push(format!("also={}", flavors.join(" ")));
+    }
 
     let option_strings = if option_strings.is_empty() {
         "".to_string()
@@ -122,6 +122,7 @@ fn defer_main() {
         eprintln!("  Java   was invoked {} times", rt.java.get_count());
         eprintln!("  JS     was invoked {} times", rt.js.get_count());
         eprintln!("  Python was invoked {} times", rt.py.get_count());
+        eprintln!("  Py regex was invoked {} times", rt.py_regex.get_count());
         eprintln!("  Ruby   was invoked {} times", rt.ruby.get_count());
         eprintln!("  Rust   was invoked {} times", rt.rust.get_count());
         eprintln!("  PCRE   was invoked {} times", rt.pcre.get_count());
@@ -0,0 +1,76 @@
+use lsp_types::{DiagnosticSeverity, NumberOrString};
+use pomsky::{
+    Expr,
+    diagnose::{Diagnostic, Severity},
+    options::CompileOptions,
+};
+use pomsky_syntax::{
+    cst::{Cst, Token, TokenKind},
+    exprs::Rule,
+};
+
+use crate::{line_index::LineIndex, symbols::Symbols};
+
+/// An open text document and the results of parsing it
+pub(crate) struct Document {
+    pub(crate) text: String,
+    pub(crate) index: LineIndex,
+    pub(crate) cst: Cst,
+    pub(crate) symbols: Symbols,
+}
+
+impl Document {
+    pub(crate) fn new(text: String) -> Self {
+        let index = LineIndex::new(&text);
+        let (cst, _) = Cst::parse(&text, 256);
+        let symbols = Symbols::new(cst.rule());
+        Document { text, index, cst, symbols }
+    }
+
+    pub(crate) fn rule(&self) -> &Rule {
+        self.cst.rule()
+    }
+
+    /// Returns the errors and warnings of the document, including the ones
+    /// found during compilation
+    pub(crate) fn diagnostics(&self, options: CompileOptions) -> Vec<Diagnostic> {
+        let (_, diagnostics, _) = Expr::parse_and_compile(&self.text, options);
+        diagnostics
+    }
+
+    pub(crate) fn lsp_diagnostic(&self, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+        let message = match &diagnostic.help {
+            Some(help) => format!("{}\nhelp: {help}", diagnostic.msg),
+            None => diagnostic.msg.clone(),
+        };
+        let severity = match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        };
+
+        lsp_types::Diagnostic {
+            range: self.index.range(&self.text, diagnostic.span),
+            severity: Some(severity),
+            code: diagnostic.code.map(|code| NumberOrString::String(code.to_string())),
+            source: Some("pomsky".into()),
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether the offset is within a character set, e.g. `[w|]`
+    pub(crate) fn is_in_char_set(&self, offset: usize) -> bool {
+        let mut depth = 0;
+        for token in self.cst.tokens() {
+            if token.span().range_unchecked().end > offset {
+                break;
+            }
+            match token.kind() {
+                TokenKind::Token(Token::OpenBracket) => depth += 1,
+                TokenKind::Token(Token::CloseBracket) if depth > 0 => depth -= 1,
+                _ => {}
+            }
+        }
+        depth > 0
+    }
+}
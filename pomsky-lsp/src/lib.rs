@@ -0,0 +1,19 @@
+//! # pomsky-lsp
+//!
+//! A [language server](https://microsoft.github.io/language-server-protocol/)
+//! for [pomsky](https://pomsky-lang.org). It provides
+//!
+//! - diagnostics for syntax errors, compile errors and warnings
+//! - the compiled regex of a `let` binding on hover
+//! - go-to-definition and find-references for variables
+//! - completion of variables and of shorthands in character sets
+//! - quick fixes for typos
+//!
+//! The `pomsky-lsp` binary communicates over stdin and stdout.
+
+mod document;
+mod line_index;
+mod server;
+mod symbols;
+
+pub use server::run;
@@ -0,0 +1,34 @@
+use lsp_types::{Position, Range};
+use pomsky::{
+    Span,
+    line_index::{self, LineCol, TextUnit},
+};
+
+/// Converts between byte offsets and LSP positions, which count UTF-16 code
+/// units within a line.
+pub(crate) struct LineIndex(line_index::LineIndex);
+
+impl LineIndex {
+    pub(crate) fn new(text: &str) -> Self {
+        LineIndex(line_index::LineIndex::new(text))
+    }
+
+    pub(crate) fn position(&self, text: &str, offset: usize) -> Position {
+        let LineCol { line, col } = self.0.line_col(text, offset, TextUnit::Utf16);
+        Position::new(line as u32, col as u32)
+    }
+
+    pub(crate) fn range(&self, text: &str, span: Span) -> Range {
+        match span.range() {
+            Some(range) => {
+                Range::new(self.position(text, range.start), self.position(text, range.end))
+            }
+            None => Range::default(),
+        }
+    }
+
+    pub(crate) fn offset(&self, text: &str, position: Position) -> usize {
+        let pos = LineCol::new(position.line as usize, position.character as usize);
+        self.0.offset(text, pos, TextUnit::Utf16)
+    }
+}
@@ -0,0 +1,18 @@
+use std::process::exit;
+
+use lsp_server::Connection;
+
+fn main() {
+    let (connection, io_threads) = Connection::stdio();
+    if let Err(error) = pomsky_lsp::run(&connection) {
+        eprintln!("error: {error}");
+        exit(1);
+    }
+
+    // the connection must be dropped so the I/O threads can finish
+    drop(connection);
+    if let Err(error) = io_threads.join() {
+        eprintln!("error: {error}");
+        exit(1);
+    }
+}
@@ -0,0 +1,288 @@
+use std::{collections::HashMap, error::Error};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CompletionItem, CompletionItemKind, CompletionOptions,
+    CompletionParams, CompletionResponse, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentChanges, GotoDefinitionParams, GotoDefinitionResponse,
+    Hover, HoverContents, HoverParams, HoverProviderCapability, Location, MarkupContent,
+    MarkupKind, OneOf, OptionalVersionedTextDocumentIdentifier, PublishDiagnosticsParams,
+    ReferenceParams, ServerCapabilities, TextDocumentEdit, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri, WorkspaceEdit,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Completion, GotoDefinition, HoverRequest, References},
+};
+use pomsky::{Expr, options::CompileOptions};
+
+use crate::{document::Document, symbols::let_expression};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Runs the language server on the given connection until the client shuts
+/// it down.
+pub fn run(connection: &Connection) -> Result<()> {
+    let capabilities = serde_json::to_value(capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let mut server = Server { connection, documents: HashMap::new(), options: Default::default() };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(response.into())?;
+            }
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["[".into()]),
+            ..Default::default()
+        }),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    documents: HashMap<Uri, Document>,
+    options: CompileOptions,
+}
+
+impl Server<'_> {
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            <HoverRequest as lsp_types::request::Request>::METHOD => {
+                self.request::<HoverRequest>(request, Server::hover)
+            }
+            <GotoDefinition as lsp_types::request::Request>::METHOD => {
+                self.request::<GotoDefinition>(request, Server::definition)
+            }
+            <References as lsp_types::request::Request>::METHOD => {
+                self.request::<References>(request, Server::references)
+            }
+            <Completion as lsp_types::request::Request>::METHOD => {
+                self.request::<Completion>(request, Server::completion)
+            }
+            <CodeActionRequest as lsp_types::request::Request>::METHOD => {
+                self.request::<CodeActionRequest>(request, Server::code_actions)
+            }
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request `{method}`"),
+            ),
+        }
+    }
+
+    fn request<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handler: fn(&Self, R::Params) -> R::Result,
+    ) -> Response {
+        let id = request.id.clone();
+        match request.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => Response::new_ok(id, handler(self, params)),
+            Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = params.text_document;
+                self.update(document.uri, document.text, Some(document.version))?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                // with full document sync, the last change contains the entire text
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    let document = params.text_document;
+                    self.update(document.uri, change.text, Some(document.version))?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.publish_diagnostics(uri, vec![], None)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, uri: Uri, text: String, version: Option<i32>) -> Result<()> {
+        let document = Document::new(text);
+        let diagnostics =
+            document.diagnostics(self.options).iter().map(|d| document.lsp_diagnostic(d)).collect();
+        self.documents.insert(uri.clone(), document);
+        self.publish_diagnostics(uri, diagnostics, version)
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: Uri,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        let params = PublishDiagnosticsParams { uri, diagnostics, version };
+        let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    /// Shows the compiled regex of a `let` binding when hovering over its name
+    /// or a variable referring to it
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.index.offset(&document.text, position.position);
+
+        let definition = document.symbols.definition_at(offset)?;
+        let (_, name_span) = document.symbols.definitions[definition];
+        let rule = let_expression(document.rule(), name_span)?;
+        let (regex, _) = Expr::from(rule).compile(&document.text, self.options);
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```regex\n{}\n```", regex?),
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.index.offset(&document.text, position.position);
+
+        let definition = document.symbols.definition_at(offset)?;
+        let (_, name_span) = document.symbols.definitions[definition];
+        let range = document.index.range(&document.text, name_span);
+        Some(GotoDefinitionResponse::Scalar(Location::new(position.text_document.uri, range)))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let position = params.text_document_position;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.index.offset(&document.text, position.position);
+
+        let definition = document.symbols.definition_at(offset)?;
+        let (_, name_span) = document.symbols.definitions[definition];
+        let declaration = params.context.include_declaration.then_some(name_span);
+        let spans = declaration.into_iter().chain(document.symbols.references_to(definition));
+
+        let uri = position.text_document.uri;
+        let locations = spans
+            .map(|span| Location::new(uri.clone(), document.index.range(&document.text, span)))
+            .collect();
+        Some(locations)
+    }
+
+    /// Completes shorthands such as `word` in character sets, and variables
+    /// everywhere else
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.index.offset(&document.text, position.position);
+
+        let items = if document.is_in_char_set(offset) {
+            pomsky::list_shorthands()
+                .map(|(name, group)| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::CONSTANT),
+                    detail: Some(group.kind().to_string()),
+                    ..Default::default()
+                })
+                .collect()
+        } else {
+            let mut names = document
+                .symbols
+                .definitions
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            names.dedup();
+            names
+                .into_iter()
+                .map(|name| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    ..Default::default()
+                })
+                .collect()
+        };
+        Some(CompletionResponse::Array(items))
+    }
+
+    /// Offers to apply the quick fixes of diagnostics in the selected range
+    fn code_actions(&self, params: CodeActionParams) -> Option<Vec<CodeActionOrCommand>> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let start = document.index.offset(&document.text, params.range.start);
+        let end = document.index.offset(&document.text, params.range.end);
+
+        let mut actions = Vec::new();
+        for diagnostic in document.diagnostics(self.options) {
+            if !diagnostic.span.range().is_some_and(|r| r.start <= end && start <= r.end) {
+                continue;
+            }
+            let lsp_diagnostic = document.lsp_diagnostic(&diagnostic);
+
+            for (i, fix) in diagnostic.fixes.iter().enumerate() {
+                let edits = fix
+                    .replacements
+                    .iter()
+                    .map(|r| {
+                        let range = document.index.range(&document.text, r.span);
+                        OneOf::Left(TextEdit::new(range, r.insert.clone()))
+                    })
+                    .collect();
+                let changes = DocumentChanges::Edits(vec![TextDocumentEdit {
+                    text_document: OptionalVersionedTextDocumentIdentifier {
+                        uri: params.text_document.uri.clone(),
+                        version: None,
+                    },
+                    edits,
+                }]);
+
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.description.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![lsp_diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit {
+                        document_changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    // preferred fixes may be applied without asking the user
+                    is_preferred: Some(i == 0 && fix.preserves_meaning),
+                    ..Default::default()
+                }));
+            }
+        }
+        Some(actions)
+    }
+}
@@ -0,0 +1,158 @@
+use std::{collections::HashMap, convert::Infallible};
+
+use pomsky::{
+    Span,
+    visitor::{self, NestingKind, RuleVisitor},
+};
+use pomsky_syntax::exprs::{Rule, Stmt, StmtExpr, Variable};
+
+/// The variables declared and used in a document
+#[derive(Default)]
+pub(crate) struct Symbols {
+    /// The name of each `let` binding, and the span of the name
+    pub(crate) definitions: Vec<(String, Span)>,
+    /// Every variable usage, and the index of the definition it refers to
+    pub(crate) references: Vec<(Span, Option<usize>)>,
+}
+
+impl Symbols {
+    pub(crate) fn new(rule: &Rule) -> Self {
+        let mut resolver = Resolver::default();
+        let Ok(()) = visitor::visit(rule, &mut resolver);
+        resolver.symbols
+    }
+
+    /// Returns the definition whose name, or a reference to it, contains the
+    /// offset
+    pub(crate) fn definition_at(&self, offset: usize) -> Option<usize> {
+        let contains =
+            |span: Span| span.range().is_some_and(|r| r.start <= offset && offset <= r.end);
+
+        self.definitions.iter().position(|&(_, span)| contains(span)).or_else(|| {
+            self.references.iter().find(|&&(span, _)| contains(span)).and_then(|&(_, def)| def)
+        })
+    }
+
+    pub(crate) fn references_to(&self, definition: usize) -> impl Iterator<Item = Span> + '_ {
+        self.references.iter().filter(move |&&(_, def)| def == Some(definition)).map(|&(s, _)| s)
+    }
+}
+
+/// Resolves variables the same way as the compiler: A variable refers to the
+/// innermost `let` binding with that name whose scope contains it, unless the
+/// variable is in the binding's own definition.
+#[derive(Default)]
+struct Resolver {
+    symbols: Symbols,
+    indices: HashMap<Span, usize>,
+    scopes: Vec<Vec<usize>>,
+    expanding: Vec<usize>,
+    current_let: Option<usize>,
+}
+
+impl Resolver {
+    fn definition(&mut self, name: &str, span: Span) -> usize {
+        *self.indices.entry(span).or_insert_with(|| {
+            self.symbols.definitions.push((name.to_string(), span));
+            self.symbols.definitions.len() - 1
+        })
+    }
+}
+
+impl RuleVisitor<Infallible> for Resolver {
+    fn enter_rule(&mut self, mut rule: &Rule) -> Result<(), Infallible> {
+        // all `let` bindings in a sequence of statements are visible to each other
+        if let Rule::StmtExpr(_) = rule {
+            let mut scope = Vec::new();
+            while let Rule::StmtExpr(expr) = rule {
+                if let Stmt::Let(l) = &expr.stmt {
+                    scope.push(self.definition(&l.name, l.name_span));
+                }
+                rule = &expr.rule;
+            }
+            self.scopes.push(scope);
+        }
+        Ok(())
+    }
+
+    fn visit_statement(&mut self, statement: &Stmt) -> Result<(), Infallible> {
+        if let Stmt::Let(l) = statement {
+            self.current_let = self.indices.get(&l.name_span).copied();
+        }
+        Ok(())
+    }
+
+    fn down(&mut self, kind: NestingKind) {
+        if kind == NestingKind::Let
+            && let Some(idx) = self.current_let.take()
+        {
+            self.expanding.push(idx);
+        }
+    }
+
+    fn up(&mut self, kind: NestingKind) {
+        match kind {
+            NestingKind::Let => {
+                self.expanding.pop();
+            }
+            NestingKind::StmtExpr => {
+                self.scopes.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_variable(&mut self, var: &Variable) -> Result<(), Infallible> {
+        let definitions = &self.symbols.definitions;
+        let def = self.scopes.iter().rev().find_map(|scope| {
+            scope
+                .iter()
+                .copied()
+                .find(|&idx| definitions[idx].0 == var.name && !self.expanding.contains(&idx))
+        });
+        self.symbols.references.push((var.span, def));
+        Ok(())
+    }
+}
+
+/// Returns an expression consisting of the statements in scope of the `let`
+/// binding with the given name span, followed by a variable referring to it.
+/// This is used to compile a single `let` binding.
+pub(crate) fn let_expression(rule: &Rule, name_span: Span) -> Option<Rule> {
+    match rule {
+        Rule::StmtExpr(_) => {
+            let mut statements: Vec<&StmtExpr> = Vec::new();
+            let mut tail = rule;
+            while let Rule::StmtExpr(expr) = tail {
+                statements.push(expr);
+                tail = &expr.rule;
+            }
+
+            let inner = statements
+                .iter()
+                .find_map(|expr| match &expr.stmt {
+                    Stmt::Let(l) if l.name_span == name_span => {
+                        Some(Rule::Variable(Variable::new(&l.name, l.name_span)))
+                    }
+                    Stmt::Let(l) => let_expression(&l.rule, name_span),
+                    _ => None,
+                })
+                .or_else(|| let_expression(tail, name_span))?;
+
+            let statements = statements.into_iter().rev();
+            Some(statements.filter(|expr| !matches!(expr.stmt, Stmt::Test(_))).fold(
+                inner,
+                |rule, expr| {
+                    Rule::StmtExpr(Box::new(StmtExpr::new(expr.stmt.clone(), rule, expr.span)))
+                },
+            ))
+        }
+        Rule::Group(g) => g.parts.iter().find_map(|rule| let_expression(rule, name_span)),
+        Rule::Alternation(a) => a.rules.iter().find_map(|rule| let_expression(rule, name_span)),
+        Rule::Intersection(i) => i.rules.iter().find_map(|rule| let_expression(rule, name_span)),
+        Rule::Repetition(r) => let_expression(&r.rule, name_span),
+        Rule::Lookaround(l) => let_expression(&l.rule, name_span),
+        Rule::Negation(n) => let_expression(&n.rule, name_span),
+        _ => None,
+    }
+}
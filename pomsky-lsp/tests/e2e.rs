@@ -0,0 +1,210 @@
+use std::thread::{self, JoinHandle};
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use serde_json::{Value, json};
+
+struct Client {
+    connection: Connection,
+    server: Option<JoinHandle<()>>,
+    next_id: i32,
+}
+
+impl Client {
+    fn start() -> Self {
+        let (server, connection) = Connection::memory();
+        let server = thread::spawn(move || pomsky_lsp::run(&server).unwrap());
+        let mut client = Client { connection, server: Some(server), next_id: 0 };
+
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["capabilities"]["hoverProvider"], json!(true));
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), method.into(), params);
+        self.connection.sender.send(request.into()).unwrap();
+
+        match self.connection.receiver.recv().unwrap() {
+            Message::Response(Response { id: response_id, result, error }) => {
+                assert_eq!(response_id, id);
+                assert!(error.is_none(), "{error:?}");
+                result.unwrap_or_default()
+            }
+            message => panic!("expected response, got {message:?}"),
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let notification = Notification::new(method.into(), params);
+        self.connection.sender.send(notification.into()).unwrap();
+    }
+
+    fn open(&self, uri: &str, text: &str) -> Value {
+        let document = json!({ "uri": uri, "languageId": "pomsky", "version": 1, "text": text });
+        self.notify("textDocument/didOpen", json!({ "textDocument": document }));
+        self.diagnostics()
+    }
+
+    fn diagnostics(&self) -> Value {
+        match self.connection.receiver.recv().unwrap() {
+            Message::Notification(n) if n.method == "textDocument/publishDiagnostics" => {
+                n.params["diagnostics"].clone()
+            }
+            message => panic!("expected diagnostics, got {message:?}"),
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return;
+        }
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        self.server.take().unwrap().join().unwrap();
+    }
+}
+
+fn position(uri: &str, line: u32, character: u32) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } })
+}
+
+const URI: &str = "file:///test.pomsky";
+
+#[test]
+fn diagnostics() {
+    let client = Client::start();
+
+    let diagnostics = client.open(URI, "let x = 'a';\n[w] | [foo] | y");
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], json!(1));
+    assert_eq!(diagnostics[0]["source"], json!("pomsky"));
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 1, "character": 7 }, "end": { "line": 1, "character": 10 } })
+    );
+
+    let change = json!({
+        "textDocument": { "uri": URI, "version": 2 },
+        "contentChanges": [{ "text": "let x = 'a';\n[w] | x" }],
+    });
+    client.notify("textDocument/didChange", change);
+    assert_eq!(client.diagnostics(), json!([]));
+}
+
+#[test]
+fn hover_shows_compiled_let() {
+    let mut client = Client::start();
+    client.open(URI, "let x = 'a' | 'b';\nlet y = x{2} 'c';\n:(y)");
+
+    let hover = client.request("textDocument/hover", position(URI, 1, 5));
+    assert_eq!(hover["contents"]["value"], json!("```regex\n[ab]{2}c\n```"));
+
+    // a variable referring to the binding
+    let hover = client.request("textDocument/hover", position(URI, 2, 2));
+    assert_eq!(hover["contents"]["value"], json!("```regex\n[ab]{2}c\n```"));
+
+    let hover = client.request("textDocument/hover", position(URI, 0, 15));
+    assert_eq!(hover, Value::Null);
+}
+
+#[test]
+fn definition_and_references() {
+    let mut client = Client::start();
+    client.open(URI, "let x = 'a';\nlet y = (let x = 'b'; x) x;\nx y");
+
+    let definition = client.request("textDocument/definition", position(URI, 1, 22));
+    assert_eq!(definition["range"]["start"], json!({ "line": 1, "character": 13 }));
+
+    let definition = client.request("textDocument/definition", position(URI, 1, 25));
+    assert_eq!(definition["range"]["start"], json!({ "line": 0, "character": 4 }));
+
+    let mut params = position(URI, 0, 4);
+    params["context"] = json!({ "includeDeclaration": true });
+    let references = client.request("textDocument/references", params);
+    let lines = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|loc| {
+            (loc["range"]["start"]["line"].clone(), loc["range"]["start"]["character"].clone())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(json!(0), json!(4)), (json!(1), json!(25)), (json!(2), json!(0))]);
+}
+
+#[test]
+fn completion() {
+    let mut client = Client::start();
+    client.open(URI, "let foo = 'a';\n[");
+
+    let completion = client.request("textDocument/completion", position(URI, 1, 1));
+    let labels =
+        completion.as_array().unwrap().iter().map(|item| &item["label"]).collect::<Vec<_>>();
+    assert!(labels.contains(&&json!("word")));
+    assert!(labels.contains(&&json!("Greek")));
+    assert!(!labels.contains(&&json!("foo")));
+
+    let completion = client.request("textDocument/completion", position(URI, 1, 0));
+    assert_eq!(completion, json!([{ "label": "foo", "kind": 6 }]));
+}
+
+#[test]
+fn code_action_fixes_typo() {
+    let mut client = Client::start();
+    client.open(URI, "let number = [d]+;\nnumbr");
+
+    let range =
+        json!({ "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } });
+    let params = json!({
+        "textDocument": { "uri": URI },
+        "range": range,
+        "context": { "diagnostics": [] },
+    });
+    let actions = client.request("textDocument/codeAction", params);
+    let actions = actions.as_array().unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0]["title"], json!("Replace with `number`"));
+    assert_eq!(actions[0]["kind"], json!("quickfix"));
+    assert_eq!(
+        actions[0]["edit"]["documentChanges"],
+        json!([{
+            "textDocument": { "uri": URI, "version": null },
+            "edits": [{
+                "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 5 } },
+                "newText": "number",
+            }],
+        }])
+    );
+}
+
+#[test]
+fn code_action_with_multiple_fixes() {
+    let mut client = Client::start();
+    client.open(URI, "'a'+?");
+
+    let range =
+        json!({ "start": { "line": 0, "character": 4 }, "end": { "line": 0, "character": 5 } });
+    let params = json!({
+        "textDocument": { "uri": URI },
+        "range": range,
+        "context": { "diagnostics": [] },
+    });
+    let actions = client.request("textDocument/codeAction", params);
+    let actions = actions.as_array().unwrap();
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0]["title"], json!("Make the repetition lazy"));
+    assert_eq!(actions[0]["isPreferred"], json!(false));
+    assert_eq!(actions[1]["title"], json!("Add parentheses around the inner repetition"));
+    assert_eq!(actions[1]["isPreferred"], json!(false));
+
+    let edits = &actions[1]["edit"]["documentChanges"][0]["edits"];
+    let new_texts = edits.as_array().unwrap().iter().map(|e| &e["newText"]).collect::<Vec<_>>();
+    assert_eq!(new_texts, [&json!("("), &json!(")")]);
+    assert_eq!(edits[1]["range"]["start"], json!({ "line": 0, "character": 4 }));
+}
@@ -178,7 +178,7 @@ fn pomsky_impl(items: impl Iterator<Item = TokenTree>) -> Result<Literal, Error>
 
     let input = input.trim_start_matches("/*«*/").trim_end_matches("/*»*/");
 
-    match Expr::parse_and_compile(input, CompileOptions { flavor, ..Default::default() }) {
+    match Expr::parse_and_compile(input, CompileOptions::default().with_flavor(flavor)) {
         (Some(compiled), _warnings, _tests) => Ok(Literal::string(&compiled)),
 
         (None, errors, _) => {
@@ -136,6 +136,7 @@ fn generate_enum(
     format!(
         r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(non_camel_case_types)]
 #[allow(clippy::enum_variant_names)]
 #[repr({size})]
@@ -0,0 +1,646 @@
+//! A lossless concrete syntax tree (CST) for tooling.
+//!
+//! Unlike the [`Rule`] syntax tree, the CST keeps every token of the source
+//! code, including whitespace and comments ("trivia"), so it can be converted
+//! back to text byte-for-byte. This makes it suitable for automated refactors
+//! and for tools that must preserve comments.
+//!
+//! Tokens are grouped into nodes that correspond to the expressions and
+//! statements of the [`Rule`] it was built from. Trivia between two nodes
+//! belongs to the enclosing node.
+//!
+//! ```
+//! use pomsky_syntax::cst::{Cst, NodeKind, TokenKind};
+//!
+//! let source = "# greeting\nlet x = 'hello';  x+ # repeated\n";
+//! let (cst, diagnostics) = Cst::parse(source, 256);
+//! assert!(diagnostics.is_empty());
+//! assert_eq!(cst.to_string(), source);
+//!
+//! let comments = cst.tokens().filter(|t| t.kind() == TokenKind::Comment).count();
+//! assert_eq!(comments, 2);
+//!
+//! let let_node = cst.root().nodes().find(|n| n.kind() == NodeKind::Let).unwrap();
+//! assert_eq!(let_node.to_string(), "let x = 'hello';");
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{convert::Infallible, fmt};
+
+use crate::{
+    Span,
+    diagnose::ParseDiagnostic,
+    exprs::{
+        Group, GroupKind, Rule, Stmt,
+        test::{TestCase, TestCaseMatch},
+    },
+    lexer::tokenize,
+    visitor::{self, RuleVisitor},
+};
+
+pub use crate::lexer::Token;
+
+/// A lossless syntax tree of a pomsky expression. See the [module-level
+/// documentation](self) for more information.
+#[derive(Debug, Clone)]
+pub struct Cst {
+    root: SyntaxNode,
+    rule: Rule,
+}
+
+impl Cst {
+    /// Parses the source code into a lossless syntax tree.
+    ///
+    /// This never fails: Syntax errors are returned as diagnostics, and the
+    /// parts of the source code that couldn't be parsed are covered by
+    /// [`NodeKind::Error`] nodes.
+    ///
+    /// ```
+    /// use pomsky_syntax::cst::{Cst, NodeKind};
+    ///
+    /// let (cst, diagnostics) = Cst::parse("'a' | [foo] # comment", 256);
+    /// assert_eq!(diagnostics.len(), 1);
+    ///
+    /// let error = cst.root().nodes().find(|n| n.kind() == NodeKind::Error).unwrap();
+    /// assert_eq!(error.to_string(), "[foo]");
+    /// ```
+    pub fn parse(source: &str, recursion: u32) -> (Cst, Vec<ParseDiagnostic>) {
+        let (rule, diagnostics) = crate::parse_partial(source, recursion);
+        let tokens = lex_lossless(source);
+        let nodes = collect_nodes(&rule, &tokens);
+        let root = build_tree(source, tokens, nodes);
+        (Cst { root, rule }, diagnostics)
+    }
+
+    /// Returns the root node, which spans the entire source code.
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns the syntax tree of the expression. It contains a [`Rule::Error`]
+    /// for every part of the source code that couldn't be parsed.
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// Converts the CST into the syntax tree of the expression.
+    pub fn into_rule(self) -> Rule {
+        self.rule
+    }
+
+    /// Returns an iterator over all tokens in the source code, including
+    /// trivia.
+    pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        self.root.tokens()
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)
+    }
+}
+
+/// The kind of a [`SyntaxNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// The entire source code
+    Root,
+    /// A `let` statement, including the terminating `;`
+    Let,
+    /// A `test {...}` block
+    Test,
+    /// An `enable`, `disable` or `allow` statement
+    Modifier,
+    /// A string literal
+    Literal,
+    /// A character class
+    CharClass,
+    /// A sequence of expressions or a group in parentheses
+    Group,
+    /// An alternation
+    Alternation,
+    /// An intersection
+    Intersection,
+    /// A repeated expression
+    Repetition,
+    /// A boundary
+    Boundary,
+    /// A lookahead or lookbehind
+    Lookaround,
+    /// A variable
+    Variable,
+    /// A backreference or forward reference
+    Reference,
+    /// A `range` expression
+    Range,
+    /// A negated expression
+    Negation,
+    /// A `regex` expression
+    Regex,
+    /// The `recursion` keyword
+    Recursion,
+    /// The dot, `.`
+    Dot,
+    /// The `Grapheme` or `G` built-in variable
+    Grapheme,
+    /// The `Codepoint` or `C` built-in variable
+    Codepoint,
+    /// A part of the source code that contains a syntax error
+    Error,
+}
+
+/// The kind of a [`SyntaxToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A token that is relevant for parsing
+    Token(Token),
+    /// A sequence of whitespace characters
+    Whitespace,
+    /// A comment starting with `#`, not including the line break
+    Comment,
+}
+
+impl TokenKind {
+    /// Returns `true` for whitespace and comments.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, TokenKind::Whitespace | TokenKind::Comment)
+    }
+}
+
+/// A node in the [`Cst`], which contains tokens and other nodes.
+#[derive(Debug, Clone)]
+pub struct SyntaxNode {
+    kind: NodeKind,
+    span: Span,
+    children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    /// Returns the kind of this node.
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the span from the start of the first token to the end of the
+    /// last token in this node.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the direct children of this node.
+    pub fn children(&self) -> &[SyntaxElement] {
+        &self.children
+    }
+
+    /// Returns an iterator over all tokens in this node and its descendants,
+    /// including trivia.
+    pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        let mut stack = vec![self.children.iter()];
+        core::iter::from_fn(move || {
+            loop {
+                match stack.last_mut()?.next() {
+                    Some(SyntaxElement::Token(token)) => return Some(token),
+                    Some(SyntaxElement::Node(node)) => stack.push(node.children.iter()),
+                    None => {
+                        stack.pop();
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over all descendants of this node in pre-order,
+    /// not including the node itself.
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        let mut stack = vec![self.children.iter()];
+        core::iter::from_fn(move || {
+            loop {
+                match stack.last_mut()?.next() {
+                    Some(SyntaxElement::Node(node)) => {
+                        stack.push(node.children.iter());
+                        return Some(node);
+                    }
+                    Some(SyntaxElement::Token(_)) => {}
+                    None => {
+                        stack.pop();
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokens().try_for_each(|token| f.write_str(&token.text))
+    }
+}
+
+/// A child of a [`SyntaxNode`].
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+/// A token in the [`Cst`], including its text and position.
+#[derive(Debug, Clone)]
+pub struct SyntaxToken {
+    kind: TokenKind,
+    span: Span,
+    text: String,
+}
+
+impl SyntaxToken {
+    /// Returns the kind of this token.
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// Returns the location of this token in the source code.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the text of this token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Tokenizes the source code, keeping whitespace and comments that the lexer
+/// skips.
+pub(crate) fn lex_lossless(source: &str) -> Vec<(TokenKind, Span)> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    for (token, span) in tokenize(source) {
+        let range = span.range_unchecked();
+        lex_trivia(source, offset, range.start, &mut tokens);
+        tokens.push((TokenKind::Token(token), span));
+        offset = range.end;
+    }
+    lex_trivia(source, offset, source.len(), &mut tokens);
+    tokens
+}
+
+fn lex_trivia(source: &str, start: usize, end: usize, tokens: &mut Vec<(TokenKind, Span)>) {
+    let mut offset = start;
+    while offset < end {
+        let rest = &source[offset..end];
+        let (kind, mut len) = if rest.starts_with('#') {
+            (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else {
+            (TokenKind::Whitespace, rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len()))
+        };
+        let kind = if len == 0 {
+            // the lexer shouldn't skip anything else, but make sure not to lose it
+            len = rest.chars().next().map_or(1, char::len_utf8);
+            TokenKind::Token(Token::Error)
+        } else {
+            kind
+        };
+        tokens.push((kind, Span::new(offset, offset + len)));
+        offset += len;
+    }
+}
+
+/// Collects the spans of all nodes in the syntax tree, sorted by their start
+/// position. Outer nodes come before inner nodes.
+fn collect_nodes(rule: &Rule, tokens: &[(TokenKind, Span)]) -> Vec<(NodeKind, Span)> {
+    let mut let_names = LetNames(Vec::new());
+    let Ok(()) = visitor::visit(rule, &mut let_names);
+
+    let mut collector = NodeCollector { tokens, let_names: let_names.0, nodes: Vec::new() };
+    let Ok(()) = visitor::visit(rule, &mut collector);
+
+    let mut nodes = collector.nodes;
+    nodes.retain(|(_, span)| {
+        let range = span.range_unchecked();
+        !span.is_empty() && range.start < range.end
+    });
+    nodes.sort_by_key(|(_, span)| {
+        let range = span.range_unchecked();
+        (range.start, core::cmp::Reverse(range.end))
+    });
+    nodes
+}
+
+/// Collects the names of all variables declared with `let`
+struct LetNames(Vec<String>);
+
+impl RuleVisitor<Infallible> for LetNames {
+    fn enter_rule(&mut self, rule: &Rule) -> Result<(), Infallible> {
+        if let Rule::StmtExpr(s) = rule
+            && let Stmt::Let(l) = &s.stmt
+        {
+            self.0.push(l.name().to_string());
+        }
+        Ok(())
+    }
+}
+
+struct NodeCollector<'a> {
+    tokens: &'a [(TokenKind, Span)],
+    /// Variables with these names may shadow the built-in variables
+    let_names: Vec<String>,
+    nodes: Vec<(NodeKind, Span)>,
+}
+
+impl NodeCollector<'_> {
+    /// Returns the end of the `;` token following `offset`, if there is one
+    fn semicolon_after(&self, offset: usize) -> Option<usize> {
+        let idx = self.tokens.partition_point(|(_, span)| span.range_unchecked().start < offset);
+        let (kind, span) = self.tokens[idx..].iter().find(|(kind, _)| !kind.is_trivia())?;
+        (*kind == TokenKind::Token(Token::Semicolon)).then(|| span.range_unchecked().end)
+    }
+
+    fn push_test_match(&mut self, test_match: &TestCaseMatch) {
+        self.nodes.push((NodeKind::Literal, test_match.literal.span));
+        for capture in &test_match.captures {
+            self.nodes.push((NodeKind::Literal, capture.literal.span));
+        }
+    }
+}
+
+impl RuleVisitor<Infallible> for NodeCollector<'_> {
+    fn enter_rule(&mut self, rule: &Rule) -> Result<(), Infallible> {
+        let kind = match rule {
+            Rule::StmtExpr(s) => {
+                let (kind, span) = match &s.stmt {
+                    Stmt::Enable(_, span) | Stmt::Disable(_, span) => (NodeKind::Modifier, *span),
+                    Stmt::Allow(a) => (NodeKind::Modifier, a.span),
+                    Stmt::Test(test) => (NodeKind::Test, test.span),
+                    Stmt::Let(l) => {
+                        let start = s.span.range_unchecked().start;
+                        let end = l.name_span.join(l.rule.span()).range_unchecked().end;
+                        let end = self.semicolon_after(end).unwrap_or(end);
+                        (NodeKind::Let, Span::new(start, end))
+                    }
+                };
+                self.nodes.push((kind, span));
+                return Ok(());
+            }
+            Rule::Literal(_) => NodeKind::Literal,
+            Rule::CharClass(_) => NodeKind::CharClass,
+            Rule::Group(Group { kind: GroupKind::Implicit, parts, .. }) if parts.is_empty() => {
+                return Ok(());
+            }
+            Rule::Group(_) => NodeKind::Group,
+            Rule::Alternation(_) => NodeKind::Alternation,
+            Rule::Intersection(_) => NodeKind::Intersection,
+            Rule::Repetition(_) => NodeKind::Repetition,
+            Rule::Boundary(_) => NodeKind::Boundary,
+            Rule::Lookaround(_) => NodeKind::Lookaround,
+            Rule::Variable(v) if !self.let_names.contains(&v.name) => match v.name.as_str() {
+                "Grapheme" | "G" => NodeKind::Grapheme,
+                "Codepoint" | "C" => NodeKind::Codepoint,
+                _ => NodeKind::Variable,
+            },
+            Rule::Variable(_) => NodeKind::Variable,
+            Rule::Reference(_) => NodeKind::Reference,
+            Rule::Range(_) => NodeKind::Range,
+            Rule::Negation(_) => NodeKind::Negation,
+            Rule::Regex(_) => NodeKind::Regex,
+            Rule::Recursion(_) => NodeKind::Recursion,
+            Rule::Error(_) => NodeKind::Error,
+            Rule::Dot(_) => NodeKind::Dot,
+            // these are only created programmatically, so they have no span
+            Rule::Grapheme | Rule::Codepoint => return Ok(()),
+        };
+        self.nodes.push((kind, rule.span()));
+        Ok(())
+    }
+
+    fn visit_test_case(&mut self, test_case: &TestCase) -> Result<(), Infallible> {
+        match test_case {
+            TestCase::Match(m) => self.push_test_match(m),
+            TestCase::MatchAll(m) => {
+                self.nodes.push((NodeKind::Literal, m.literal.span));
+                m.matches.iter().for_each(|m| self.push_test_match(m));
+            }
+            TestCase::Reject(r) => self.nodes.push((NodeKind::Literal, r.literal.span)),
+        }
+        Ok(())
+    }
+}
+
+/// Arranges the tokens in a tree, using the nodes' spans. Nodes that aren't
+/// properly nested are ignored.
+fn build_tree(
+    source: &str,
+    tokens: Vec<(TokenKind, Span)>,
+    nodes: Vec<(NodeKind, Span)>,
+) -> SyntaxNode {
+    let mut nodes = nodes.into_iter().peekable();
+    let mut stack = vec![(NodeKind::Root, source.len(), Vec::new())];
+
+    for (kind, span) in tokens {
+        let range = span.range_unchecked();
+        while stack.len() > 1 && stack.last().is_some_and(|&(_, end, _)| end <= range.start) {
+            close_node(&mut stack);
+        }
+
+        if !kind.is_trivia() {
+            while let Some(&(node_kind, node_span)) = nodes.peek()
+                && node_span.range_unchecked().start <= range.start
+            {
+                nodes.next();
+                let node_end = node_span.range_unchecked().end;
+                let parent_end = stack.last().map_or(0, |&(_, end, _)| end);
+                if node_end > range.start && node_end <= parent_end {
+                    stack.push((node_kind, node_end, Vec::new()));
+                }
+            }
+        }
+
+        let text = source[range].to_string();
+        stack.last_mut().unwrap().2.push(SyntaxElement::Token(SyntaxToken { kind, span, text }));
+    }
+
+    while stack.len() > 1 {
+        close_node(&mut stack);
+    }
+    let (_, _, children) = stack.pop().unwrap();
+    SyntaxNode { kind: NodeKind::Root, span: Span::new(0, source.len()), children }
+}
+
+fn close_node(stack: &mut Vec<(NodeKind, usize, Vec<SyntaxElement>)>) {
+    let (kind, _, children) = stack.pop().unwrap();
+    let mut node = SyntaxNode { kind, span: Span::empty(), children };
+    let span = {
+        let mut tokens = node.tokens().map(|token| token.span.range_unchecked());
+        tokens.next().map(|first| {
+            let end = tokens.last().map_or(first.end, |last| last.end);
+            Span::new(first.start, end)
+        })
+    };
+    if let Some(span) = span {
+        node.span = span;
+    }
+    stack.last_mut().unwrap().2.push(SyntaxElement::Node(node));
+}
+
+#[cfg(test)]
+use alloc::format;
+
+/// Prints the nodes and tokens of the CST, one per line and indented by depth
+#[cfg(test)]
+fn tree(source: &str) -> String {
+    fn print(node: &SyntaxNode, depth: usize, out: &mut String) {
+        out.push_str(&format!("{:indent$}{:?} {}\n", "", node.kind, node.span, indent = depth * 2));
+        for child in &node.children {
+            match child {
+                SyntaxElement::Node(node) => print(node, depth + 1, out),
+                SyntaxElement::Token(token) => {
+                    let kind = match token.kind {
+                        TokenKind::Token(token) => format!("{token:?}"),
+                        kind => format!("{kind:?}"),
+                    };
+                    let indent = depth * 2 + 2;
+                    out.push_str(&format!("{:indent$}{kind} {:?}\n", "", token.text));
+                }
+            }
+        }
+    }
+
+    let (cst, diagnostics) = Cst::parse(source, 256);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    assert_eq!(cst.to_string(), source);
+    let mut out = String::new();
+    print(cst.root(), 0, &mut out);
+    out
+}
+
+#[test]
+fn cst_statements() {
+    assert_eq!(
+        tree("# c\nlet x = 'a';  x+ # r"),
+        r##"Root 0..24
+  Comment "# c"
+  Whitespace "\n"
+  Let 4..16
+    ReservedName "let"
+    Whitespace " "
+    Identifier "x"
+    Whitespace " "
+    Equals "="
+    Whitespace " "
+    Literal 12..15
+      String "'a'"
+    Semicolon ";"
+  Whitespace "  "
+  Repetition 18..20
+    Variable 18..19
+      Identifier "x"
+    Plus "+"
+  Whitespace " "
+  Comment "# r"
+"##
+    );
+}
+
+#[test]
+fn cst_modifiers() {
+    assert_eq!(
+        tree("enable lazy; allow deprecated; 'a'"),
+        r#"Root 0..34
+  Modifier 0..12
+    ReservedName "enable"
+    Whitespace " "
+    ReservedName "lazy"
+    Semicolon ";"
+  Whitespace " "
+  Modifier 13..30
+    Identifier "allow"
+    Whitespace " "
+    Identifier "deprecated"
+    Semicolon ";"
+  Whitespace " "
+  Literal 31..34
+    String "'a'"
+"#
+    );
+}
+
+#[test]
+fn cst_dot_and_builtins() {
+    assert_eq!(
+        tree(". G Codepoint C"),
+        r##"Root 0..15
+  Group 0..15
+    Dot 0..1
+      Dot "."
+    Whitespace " "
+    Grapheme 2..3
+      Identifier "G"
+    Whitespace " "
+    Codepoint 4..13
+      Identifier "Codepoint"
+    Whitespace " "
+    Codepoint 14..15
+      Identifier "C"
+"##
+    );
+
+    // a variable that shadows a built-in is not a built-in
+    assert_eq!(
+        tree("let G = 'x'; G C"),
+        r##"Root 0..16
+  Let 0..12
+    ReservedName "let"
+    Whitespace " "
+    Identifier "G"
+    Whitespace " "
+    Equals "="
+    Whitespace " "
+    Literal 8..11
+      String "'x'"
+    Semicolon ";"
+  Whitespace " "
+  Group 13..16
+    Variable 13..14
+      Identifier "G"
+    Whitespace " "
+    Codepoint 15..16
+      Identifier "C"
+"##
+    );
+}
+
+#[test]
+fn cst_trivia_in_delimiters() {
+    assert_eq!(
+        tree("( . # c\n)"),
+        r##"Root 0..9
+  Group 0..9
+    OpenParen "("
+    Whitespace " "
+    Dot 2..3
+      Dot "."
+    Whitespace " "
+    Comment "# c"
+    Whitespace "\n"
+    CloseParen ")"
+"##
+    );
+
+    assert_eq!(
+        tree("[ 'a' # c\n]"),
+        r##"Root 0..11
+  CharClass 0..11
+    OpenBracket "["
+    Whitespace " "
+    String "'a'"
+    Whitespace " "
+    Comment "# c"
+    Whitespace "\n"
+    CloseBracket "]"
+"##
+    );
+}
@@ -1,6 +1,7 @@
 //! Module containing all the errors that can occur during parsing
 
-use std::{
+use alloc::{boxed::Box, string::String};
+use core::{
     fmt,
     num::{IntErrorKind, ParseIntError},
 };
@@ -18,7 +19,7 @@ pub struct ParseError {
 
 impl core::fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(std::ops::Range { start, end }) = self.span.range() {
+        if let Some(core::ops::Range { start, end }) = self.span.range() {
             write!(f, "{}\n  at {start}..{end}", self.kind)
         } else {
             self.kind.fmt(f)
@@ -93,7 +94,7 @@ impl From<NumberError> for ParseErrorKind {
     }
 }
 
-impl std::error::Error for ParseErrorKind {}
+impl core::error::Error for ParseErrorKind {}
 
 impl core::fmt::Display for ParseErrorKind {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -167,7 +168,7 @@ impl core::fmt::Display for ParseErrorKind {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeprecationError {}
 
-impl std::error::Error for DeprecationError {}
+impl core::error::Error for DeprecationError {}
 
 impl core::fmt::Display for DeprecationError {
     fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -187,7 +188,7 @@ pub enum CharStringError {
     TooManyCodePoints,
 }
 
-impl std::error::Error for CharStringError {}
+impl core::error::Error for CharStringError {}
 
 impl core::fmt::Display for CharStringError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -232,7 +233,7 @@ pub enum CharClassError {
     WrongPrefix { expected: &'static str, has_in_prefix: bool },
 }
 
-impl std::error::Error for CharClassError {}
+impl core::error::Error for CharClassError {}
 
 impl core::fmt::Display for CharClassError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -305,7 +306,7 @@ impl From<ParseIntError> for NumberError {
     }
 }
 
-impl std::error::Error for NumberError {}
+impl core::error::Error for NumberError {}
 
 impl core::fmt::Display for NumberError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -332,7 +333,7 @@ pub enum RepetitionError {
     Multi,
 }
 
-impl std::error::Error for RepetitionError {}
+impl core::error::Error for RepetitionError {}
 
 impl core::fmt::Display for RepetitionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -2,6 +2,7 @@
 //! `('alt1' | 'alt2' | 'alt3')`.
 
 use crate::Span;
+use alloc::vec::Vec;
 
 use super::Rule;
 
@@ -14,12 +15,18 @@ use super::Rule;
 /// `'a' | ('b' 'c')` compiles to `a|bc`.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Alternation {
     pub rules: Vec<Rule>,
     pub(crate) span: Span,
 }
 
 impl Alternation {
+    #[doc(hidden)]
+    pub fn new(rules: Vec<Rule>, span: Span) -> Self {
+        Alternation { rules, span }
+    }
+
     #[cfg(feature = "dbg")]
     pub(super) fn pretty_print(&self, buf: &mut crate::PrettyPrinter, needs_parens: bool) {
         if needs_parens {
@@ -1,3 +1,9 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
 use arbitrary::{Arbitrary, Unstructured};
 
 pub(crate) struct Ident(pub(crate) String);
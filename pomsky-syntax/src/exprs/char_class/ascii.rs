@@ -1,4 +1,5 @@
 use crate::error::CharClassError;
+use alloc::{vec, vec::Vec};
 
 use super::GroupItem;
 
@@ -7,6 +7,7 @@
 //! Refer to the [`char_class` module](crate::char_class) for more information.
 
 use crate::{Span, error::ParseErrorKind};
+use alloc::{vec, vec::Vec};
 
 use super::unicode::{Category, CodeBlock, OtherProperties, Script};
 
@@ -36,7 +37,8 @@ impl CharGroup {
     /// If the name is uppercase (and not `R`), we just assume that it is a
     /// Unicode category, script or block. This needs to be fixed at one
     /// point!
-    pub(crate) fn try_from_group_name(
+    #[doc(hidden)]
+    pub fn try_from_group_name(
         kind: Option<&str>,
         name: &str,
         negative: bool,
@@ -56,6 +58,7 @@ impl CharGroup {
 
 /// One item in a character class.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GroupItem {
     /// A Unicode code point. It can be denoted in quotes (e.g. `'a'`) or in
     /// hexadecimal notation (`U+201`).
@@ -161,6 +164,7 @@ impl arbitrary::Arbitrary<'_> for GroupItem {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GroupName {
     Word,
     Digit,
@@ -175,6 +179,7 @@ pub enum GroupName {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ScriptExtension {
     Yes,
     No,
@@ -77,6 +77,7 @@
 //!   `![!w]` = `\w`, `![!L]` = `\p{L}`.
 
 use crate::Span;
+use alloc::vec::Vec;
 
 pub use char_group::{CharGroup, GroupItem, GroupName, ScriptExtension};
 pub use unicode::{Category, CodeBlock, OtherProperties, Script};
@@ -90,6 +91,7 @@ pub use unicode::{blocks_supported_in_dotnet, list_shorthands, props_supported_i
 /// A _character class_. Refer to the [module-level documentation](self) for
 /// details.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CharClass {
     pub inner: Vec<GroupItem>,
     pub span: Span,
@@ -1,5 +1,6 @@
 use super::char_group::{GroupName, ScriptExtension};
 use crate::error::CharClassError;
+use alloc::string::String;
 
 include!(concat!(env!("OUT_DIR"), "/unicode_data.rs"));
 
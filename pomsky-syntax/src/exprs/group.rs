@@ -1,4 +1,8 @@
 use crate::Span;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use super::Rule;
 
@@ -9,6 +13,7 @@ use super::Rule;
 /// If it is non-capturing, the parentheses can be omitted in same cases.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Group {
     pub parts: Vec<Rule>,
     pub kind: GroupKind,
@@ -68,6 +73,7 @@ impl Group {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GroupKind {
     /// A (possibly named) capturing group e.g. `:foo`
     Capturing(Capture),
@@ -86,6 +92,7 @@ impl GroupKind {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Capture {
     pub name: Option<String>,
 }
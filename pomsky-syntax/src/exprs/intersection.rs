@@ -2,6 +2,7 @@
 //! and only makes sense in certain scenarios.
 
 use crate::Span;
+use alloc::vec::Vec;
 
 use super::Rule;
 
@@ -14,6 +15,7 @@ use super::Rule;
 /// `'a' | ('b' 'c')` compiles to `a|bc`.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Intersection {
     pub rules: Vec<Rule>,
     pub span: Span,
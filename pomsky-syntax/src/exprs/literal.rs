@@ -1,7 +1,9 @@
 use crate::Span;
+use alloc::string::String;
 
 #[derive(Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Literal {
     pub content: String,
     pub span: Span,
@@ -18,8 +20,8 @@ impl Literal {
     }
 }
 
-impl std::fmt::Debug for Literal {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Literal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:?} at {}", self.content, self.span)
     }
 }
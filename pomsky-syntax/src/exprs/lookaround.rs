@@ -4,6 +4,7 @@ use super::Rule;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Lookaround {
     pub kind: LookaroundKind,
     pub rule: Rule,
@@ -12,6 +13,7 @@ pub struct Lookaround {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LookaroundKind {
     Ahead,
     Behind,
@@ -20,7 +22,8 @@ pub enum LookaroundKind {
 }
 
 impl Lookaround {
-    pub(crate) fn new(rule: Rule, kind: LookaroundKind, span: Span) -> Self {
+    #[doc(hidden)]
+    pub fn new(rule: Rule, kind: LookaroundKind, span: Span) -> Self {
         Lookaround { kind, rule, span }
     }
 
@@ -4,6 +4,7 @@ use super::Rule;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Negation {
     pub rule: Rule,
     pub not_span: Span,
@@ -1,6 +1,8 @@
 use crate::Span;
+use alloc::boxed::Box;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Range {
     pub start: Box<[u8]>,
     pub end: Box<[u8]>,
@@ -9,7 +11,8 @@ pub struct Range {
 }
 
 impl Range {
-    pub(crate) fn new(start: Box<[u8]>, end: Box<[u8]>, radix: u8, span: Span) -> Self {
+    #[doc(hidden)]
+    pub fn new(start: Box<[u8]>, end: Box<[u8]>, radix: u8, span: Span) -> Self {
         Range { start, end, radix, span }
     }
 
@@ -4,15 +4,18 @@
 //! [named references](https://www.regular-expressions.info/named.html).
 
 use crate::Span;
+use alloc::string::String;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Reference {
     pub target: ReferenceTarget,
     pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ReferenceTarget {
     Named(String),
     Number(u32),
@@ -20,7 +23,8 @@ pub enum ReferenceTarget {
 }
 
 impl Reference {
-    pub(crate) fn new(target: ReferenceTarget, span: Span) -> Self {
+    #[doc(hidden)]
+    pub fn new(target: ReferenceTarget, span: Span) -> Self {
         Reference { target, span }
     }
 
@@ -1,14 +1,17 @@
 use crate::Span;
+use alloc::string::String;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Regex {
     pub content: String,
     pub span: Span,
 }
 
 impl Regex {
-    pub(crate) fn new(content: String, span: Span) -> Self {
+    #[doc(hidden)]
+    pub fn new(content: String, span: Span) -> Self {
         Regex { content, span }
     }
 
@@ -4,6 +4,7 @@ use super::Rule;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Repetition {
     pub rule: Rule,
     pub kind: RepetitionKind,
@@ -12,12 +13,8 @@ pub struct Repetition {
 }
 
 impl Repetition {
-    pub(crate) fn new(
-        rule: Rule,
-        kind: RepetitionKind,
-        quantifier: Quantifier,
-        span: Span,
-    ) -> Self {
+    #[doc(hidden)]
+    pub fn new(rule: Rule, kind: RepetitionKind, quantifier: Quantifier, span: Span) -> Self {
         Repetition { rule, kind, quantifier, span }
     }
 
@@ -56,6 +53,7 @@ impl Repetition {
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Quantifier {
     Greedy,
     Lazy,
@@ -71,6 +69,7 @@ pub enum Quantifier {
 ///  * `'x'+` is equivalent to `'x'{1,}`
 ///  * `'x'*` is equivalent to `'x'{0,}`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RepetitionKind {
     /// The lower bound, e.g. `{4,}`
     pub lower_bound: u32,
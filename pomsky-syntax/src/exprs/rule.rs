@@ -1,4 +1,5 @@
 use crate::Span;
+use alloc::boxed::Box;
 
 use super::{
     Alternation, Boundary, CharClass, Group, Literal, Lookaround, Range, Recursion, Reference,
@@ -8,6 +9,7 @@ use super::{
 /// A parsed pomsky expression, which might contain more sub-expressions.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Rule {
     /// A string literal
     Literal(Literal),
@@ -48,7 +50,12 @@ pub enum Rule {
     /// A Unicode code point
     Codepoint,
     /// The dot
-    Dot,
+    Dot(Span),
+
+    /// A part of the expression that contains a syntax error. This only
+    /// appears in syntax trees returned by [`parse_partial`](crate::parse_partial).
+    #[cfg_attr(feature = "arbitrary", arbitrary(skip))]
+    Error(Span),
 }
 
 impl Rule {
@@ -70,7 +77,8 @@ impl Rule {
             Rule::Negation(n) => n.not_span.join(n.rule.span()),
             Rule::Regex(r) => r.span,
             Rule::Recursion(r) => r.span,
-            Rule::Grapheme | Rule::Codepoint | Rule::Dot => Span::empty(),
+            Rule::Error(span) | Rule::Dot(span) => *span,
+            Rule::Grapheme | Rule::Codepoint => Span::empty(),
         }
     }
 
@@ -94,14 +102,15 @@ impl Rule {
             Rule::Recursion(_) => buf.push_str("recursion"),
             Rule::Grapheme => buf.push_str("Grapheme"),
             Rule::Codepoint => buf.push_str("Codepoint"),
-            Rule::Dot => buf.push_str("."),
+            Rule::Dot(_) => buf.push_str("."),
+            Rule::Error(_) => buf.push_str("<error>"),
         }
     }
 }
 
 #[cfg(feature = "dbg")]
 impl core::fmt::Display for Rule {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut buf = crate::PrettyPrinter::new();
         self.pretty_print(&mut buf, false);
         f.write_str(&buf.finish())
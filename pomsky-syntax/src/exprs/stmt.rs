@@ -1,9 +1,11 @@
 use crate::Span;
+use alloc::string::{String, ToString};
 
 use super::{Rule, test::Test};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StmtExpr {
     pub stmt: Stmt,
     pub rule: Rule,
@@ -12,15 +14,18 @@ pub struct StmtExpr {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Stmt {
     Enable(BooleanSetting, Span),
     Disable(BooleanSetting, Span),
+    Allow(Allow),
     Let(Let),
     Test(Test),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BooleanSetting {
     Lazy,
     Unicode,
@@ -36,7 +41,19 @@ impl BooleanSetting {
     }
 }
 
+/// An `allow` statement, e.g. `allow deprecated;`, which silences warnings
+/// in the expression that follows it
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Allow {
+    /// The name of the allowed lint, e.g. `deprecated` or `P0105`
+    pub lint: String,
+    pub lint_span: Span,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Let {
     pub name: String,
     pub rule: Rule,
@@ -53,6 +70,18 @@ impl Let {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl arbitrary::Arbitrary<'_> for Allow {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
+        let lint = *u.choose(&["deprecated", "compat", "P0105"])?;
+        Ok(Allow { lint: lint.into(), lint_span: Span::arbitrary(u)?, span: Span::arbitrary(u)? })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, None)
+    }
+}
+
 #[cfg(feature = "arbitrary")]
 impl arbitrary::Arbitrary<'_> for Let {
     fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
@@ -92,6 +121,12 @@ impl StmtExpr {
                 buf.write(";\n");
                 self.rule.pretty_print(buf, false);
             }
+            Stmt::Allow(allow) => {
+                buf.push_str("allow ");
+                buf.write(&allow.lint);
+                buf.write(";\n");
+                self.rule.pretty_print(buf, false);
+            }
             Stmt::Let(r#let) => {
                 buf.push_str("let ");
                 buf.write(&r#let.name);
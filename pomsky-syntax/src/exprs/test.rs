@@ -1,9 +1,11 @@
 use crate::Span;
+use alloc::{string::String, vec::Vec};
 
 use super::Literal;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Test {
     pub cases: Vec<TestCase>,
     pub span: Span,
@@ -11,6 +13,7 @@ pub struct Test {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TestCase {
     Match(TestCaseMatch),
     MatchAll(TestCaseMatchAll),
@@ -19,6 +22,7 @@ pub enum TestCase {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TestCaseMatch {
     pub literal: Literal,
     pub captures: Vec<TestCapture>,
@@ -27,6 +31,7 @@ pub struct TestCaseMatch {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TestCaseMatchAll {
     pub literal: Literal,
     pub matches: Vec<TestCaseMatch>,
@@ -34,6 +39,7 @@ pub struct TestCaseMatchAll {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TestCaseReject {
     pub literal: Literal,
     pub as_substring: bool,
@@ -41,6 +47,7 @@ pub struct TestCaseReject {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TestCapture {
     pub ident: CaptureIdent,
     pub ident_span: Span,
@@ -49,6 +56,7 @@ pub struct TestCapture {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CaptureIdent {
     Name(String),
     Index(u16),
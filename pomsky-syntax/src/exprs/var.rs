@@ -1,13 +1,16 @@
 use crate::Span;
+use alloc::string::{String, ToString};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Variable {
     pub name: String,
     pub span: Span,
 }
 
 impl Variable {
-    pub(crate) fn new(name: &str, span: Span) -> Self {
+    #[doc(hidden)]
+    pub fn new(name: &str, span: Span) -> Self {
         Variable { name: name.to_string(), span }
     }
 
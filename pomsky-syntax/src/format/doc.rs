@@ -0,0 +1,166 @@
+//! A small document model for pretty printing, in the style of Wadler's
+//! "prettier printer". A [`Doc::Group`] is printed on a single line if it fits
+//! into the remaining width; otherwise, all of its line breaks are used.
+
+use alloc::{string::String, vec, vec::Vec};
+
+#[derive(Debug, Clone)]
+pub(super) enum Doc {
+    /// Text that doesn't contain line breaks
+    Text(String),
+    /// A space, or a line break if the enclosing group is broken
+    Line,
+    /// Nothing, or a line break if the enclosing group is broken
+    SoftLine,
+    /// A line break that is always printed; it breaks all enclosing groups
+    HardLine,
+    /// Text that is only printed if the enclosing group is broken
+    IfBreak(&'static str),
+    /// A comment that is printed at the end of the current line
+    LineSuffix(String),
+    /// A list of documents
+    Concat(Vec<Doc>),
+    /// Increases the indentation of line breaks within
+    Nest(Vec<Doc>),
+    /// A group whose line breaks are either all used or none. The flag
+    /// indicates whether the group must be broken
+    Group(Vec<Doc>, bool),
+    /// Like a group, but if it must be broken, the enclosing groups don't
+    /// have to be. Used for delimited groups like `(...)`
+    Block(Vec<Doc>, bool),
+}
+
+impl Doc {
+    pub(super) fn text(s: impl Into<String>) -> Doc {
+        Doc::Text(s.into())
+    }
+
+    pub(super) fn group(docs: Vec<Doc>) -> Doc {
+        let broken = docs.iter().any(Doc::forces_break);
+        Doc::Group(docs, broken)
+    }
+
+    pub(super) fn block(docs: Vec<Doc>) -> Doc {
+        let broken = docs.iter().any(Doc::forces_break);
+        Doc::Block(docs, broken)
+    }
+
+    fn forces_break(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::LineSuffix(_) => true,
+            Doc::Concat(docs) | Doc::Nest(docs) => docs.iter().any(Doc::forces_break),
+            &Doc::Group(_, broken) => broken,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub(super) fn render(docs: &[Doc], indent_width: usize, max_width: usize) -> String {
+    let mut out = String::new();
+    let mut column = 0;
+    let mut suffixes: Vec<&str> = vec![];
+    let mut stack: Vec<(usize, Mode, &Doc)> =
+        docs.iter().rev().map(|doc| (0, Mode::Break, doc)).collect();
+
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(s) => {
+                out.push_str(s);
+                column += s.chars().count();
+            }
+            Doc::IfBreak(s) => {
+                if mode == Mode::Break {
+                    out.push_str(s);
+                    column += s.chars().count();
+                }
+            }
+            Doc::Line if mode == Mode::Flat => {
+                out.push(' ');
+                column += 1;
+            }
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                newline(&mut out, &mut suffixes, indent);
+                column = indent;
+            }
+            Doc::LineSuffix(s) => suffixes.push(s),
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            Doc::Nest(docs) => {
+                let indent = indent + indent_width;
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+            &Doc::Group(ref docs, broken) | &Doc::Block(ref docs, broken) => {
+                let mode = if broken {
+                    Mode::Break
+                } else if mode == Mode::Flat
+                    || fits(max_width as isize - column as isize, docs, &stack)
+                {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+        }
+    }
+
+    for suffix in suffixes {
+        out.push_str(suffix);
+    }
+    let len = out.trim_end().len();
+    out.truncate(len);
+    out
+}
+
+fn newline(out: &mut String, suffixes: &mut Vec<&str>, indent: usize) {
+    for suffix in suffixes.drain(..) {
+        out.push_str(suffix);
+    }
+    let len = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(len);
+    out.push('\n');
+    out.extend(core::iter::repeat_n(' ', indent));
+}
+
+/// Checks whether the documents fit into the given width when printed flat,
+/// followed by the remaining documents up to the next line break.
+fn fits(mut width: isize, docs: &[Doc], rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut stack: Vec<(Mode, &Doc)> = docs.iter().rev().map(|doc| (Mode::Flat, doc)).collect();
+    let mut rest = rest.iter().rev();
+
+    while width >= 0 {
+        let (mode, doc) = match stack.pop() {
+            Some(item) => item,
+            None => match rest.next() {
+                Some(&(_, mode, doc)) => (mode, doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(s) => width -= s.chars().count() as isize,
+            Doc::IfBreak(s) => {
+                if mode == Mode::Break {
+                    width -= s.chars().count() as isize;
+                }
+            }
+            Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+            Doc::Line => width -= 1,
+            Doc::SoftLine | Doc::LineSuffix(_) => {}
+            Doc::HardLine => return true,
+            Doc::Concat(docs) | Doc::Nest(docs) => {
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)));
+            }
+            &Doc::Group(ref docs, broken) | &Doc::Block(ref docs, broken) => {
+                let mode = if broken { Mode::Break } else { mode };
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)));
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,707 @@
+//! A formatter for pomsky expressions
+//!
+//! Unlike the `Display` output of the parsed expression, the formatter
+//! preserves comments, string literals as written and the order of
+//! statements, so formatting an expression doesn't change its meaning.
+//!
+//! ### Example
+//!
+//! ```
+//! use pomsky_syntax::format::{FormatOptions, format};
+//!
+//! let (formatted, _) = format("let  x='a'|'b' ; # comment\nx  +", &FormatOptions::default());
+//! assert_eq!(formatted.as_deref(), Some("let x = 'a' | 'b'; # comment\nx+\n"));
+//! ```
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::ops::Range;
+
+use crate::{
+    Span,
+    diagnose::ParseDiagnostic,
+    exprs::{
+        BooleanSetting, BoundaryKind, CharClass, Group, GroupItem, GroupKind, GroupName, Let,
+        Literal, LookaroundKind, Quantifier, ReferenceTarget, RepetitionKind, Rule, Stmt,
+        test::{CaptureIdent, Test, TestCase, TestCaseMatch},
+    },
+    lexer::{Token, tokenize},
+};
+
+use self::doc::Doc;
+
+mod doc;
+
+/// Options for the [`PrettyPrinter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces per indentation level. The default is 2.
+    pub indent: usize,
+    /// The preferred maximum line width. Lines are broken when they get
+    /// longer, but long literals or comments can still exceed it. The default
+    /// is 80.
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions { indent: 2, max_width: 80 }
+    }
+}
+
+/// Parses and formats a pomsky expression. Returns `None` if it can't be
+/// parsed; the returned diagnostics are the same as those of
+/// [`parse`](crate::parse).
+pub fn format(source: &str, options: &FormatOptions) -> (Option<String>, Vec<ParseDiagnostic>) {
+    let (rule, diagnostics) = crate::parse(source, 256);
+    let formatted = rule.map(|rule| PrettyPrinter::new(source, options).print(&rule));
+    (formatted, diagnostics)
+}
+
+/// Formats a parsed expression in the canonical style, using the source code
+/// it was parsed from to preserve comments and literals.
+///
+/// Nodes with an empty [`Span`] (e.g. those created programmatically) are
+/// printed from the syntax tree alone.
+pub struct PrettyPrinter<'s> {
+    source: &'s str,
+    options: FormatOptions,
+    tokens: Vec<(Token, Span)>,
+    comments: Vec<Range<usize>>,
+    next_comment: usize,
+}
+
+impl<'s> PrettyPrinter<'s> {
+    /// Creates a pretty printer for an expression parsed from `source`
+    pub fn new(source: &'s str, options: &FormatOptions) -> Self {
+        let tokens = tokenize(source);
+        let comments = find_comments(source, &tokens);
+        PrettyPrinter { source, options: *options, tokens, comments, next_comment: 0 }
+    }
+
+    /// Formats the expression. The result ends with a line break, unless it
+    /// is empty.
+    pub fn print(mut self, rule: &Rule) -> String {
+        let mut docs = vec![];
+        self.statements(rule, &mut docs);
+        self.remaining_comments(&mut docs, usize::MAX);
+
+        let mut out = doc::render(&docs, self.options.indent, self.options.max_width);
+        let len = out.trim_start_matches('\n').len();
+        out.drain(..out.len() - len);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Prints a rule preceded by statements, each on its own line
+    fn statements(&mut self, mut rule: &Rule, docs: &mut Vec<Doc>) {
+        let mut prev_end = None;
+        while let Rule::StmtExpr(stmt_expr) = rule {
+            let start = stmt_expr.span.range_unchecked().start;
+            self.leading_comments(docs, start, prev_end);
+            docs.push(self.stmt(&stmt_expr.stmt));
+
+            let end = self.stmt_end(&stmt_expr.stmt);
+            if let Some(end) = end {
+                self.trailing_comment(docs, end);
+            }
+            docs.push(Doc::HardLine);
+            prev_end = end;
+            rule = &stmt_expr.rule;
+        }
+
+        if let Some(range) = rule.span().range() {
+            self.leading_comments(docs, range.start, prev_end);
+        } else if is_empty_literal(rule) {
+            if !docs.is_empty() {
+                docs.pop();
+            }
+            return;
+        }
+        docs.push(self.rule(rule));
+        self.trailing_comment_of(docs, rule);
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Doc {
+        match stmt {
+            Stmt::Enable(setting, _) => Doc::text(format!("enable {};", setting_name(setting))),
+            Stmt::Disable(setting, _) => Doc::text(format!("disable {};", setting_name(setting))),
+            Stmt::Allow(a) => Doc::text(format!("allow {};", a.lint)),
+            Stmt::Let(l) => self.let_stmt(l),
+            Stmt::Test(t) => self.test(t),
+        }
+    }
+
+    fn let_stmt(&mut self, l: &Let) -> Doc {
+        let mut inner = vec![Doc::Line];
+        if let Some(range) = l.rule.span().range() {
+            self.leading_comments(&mut inner, range.start, None);
+        }
+        if let (Rule::Group(g), [_]) = (&l.rule, inner.as_slice())
+            && g.kind != GroupKind::Implicit
+        {
+            // a delimited group is kept on the same line as the `=`
+            return Doc::Concat(vec![
+                Doc::text(format!("let {} = ", l.name)),
+                self.group(g),
+                Doc::text(";"),
+            ]);
+        }
+        inner.push(self.rule(&l.rule));
+        self.trailing_comment_of(&mut inner, &l.rule);
+
+        Doc::group(vec![Doc::text(format!("let {} =", l.name)), Doc::Nest(inner), Doc::text(";")])
+    }
+
+    fn test(&mut self, test: &Test) -> Doc {
+        let end = test.span.range().map_or(0, |r| r.end - 1);
+        let mut inner = vec![];
+        let mut prev_end = None;
+        for case in &test.cases {
+            inner.push(Doc::HardLine);
+            let (start, case_end) = self.test_case_range(case);
+            if let Some(start) = start {
+                self.leading_comments(&mut inner, start, prev_end);
+            }
+            inner.push(self.test_case(case));
+            if let Some(case_end) = case_end {
+                self.trailing_comment(&mut inner, case_end);
+            }
+            prev_end = case_end;
+        }
+        self.remaining_comments(&mut inner, end);
+
+        if inner.is_empty() {
+            Doc::text("test {}")
+        } else {
+            Doc::Concat(vec![Doc::text("test {"), Doc::Nest(inner), Doc::HardLine, Doc::text("}")])
+        }
+    }
+
+    fn test_case(&self, case: &TestCase) -> Doc {
+        match case {
+            TestCase::Match(m) => {
+                Doc::Concat(vec![Doc::text("match "), self.test_case_match(m), Doc::text(";")])
+            }
+            TestCase::MatchAll(m) => {
+                let mut docs = vec![Doc::text("match ")];
+                for (i, m) in m.matches.iter().enumerate() {
+                    if i > 0 {
+                        docs.push(Doc::text(","));
+                        docs.push(Doc::Line);
+                    }
+                    docs.push(self.test_case_match(m));
+                }
+                if !m.matches.is_empty() {
+                    docs.push(Doc::Line);
+                }
+                docs.push(Doc::text(format!("in {};", self.literal(&m.literal))));
+                Doc::group(vec![Doc::Nest(docs)])
+            }
+            TestCase::Reject(r) => {
+                let in_ = if r.as_substring { "in " } else { "" };
+                Doc::text(format!("reject {in_}{};", self.literal(&r.literal)))
+            }
+        }
+    }
+
+    fn test_case_match(&self, m: &TestCaseMatch) -> Doc {
+        let literal = Doc::text(self.literal(&m.literal));
+        if m.captures.is_empty() {
+            return literal;
+        }
+
+        let mut captures = vec![];
+        for (i, capture) in m.captures.iter().enumerate() {
+            if i > 0 {
+                captures.push(Doc::text(","));
+            }
+            captures.push(Doc::Line);
+            let ident = match &capture.ident {
+                CaptureIdent::Name(name) => name.clone(),
+                CaptureIdent::Index(index) => index.to_string(),
+            };
+            captures.push(Doc::text(format!("{ident}: {}", self.literal(&capture.literal))));
+        }
+        captures.push(Doc::IfBreak(","));
+
+        Doc::group(vec![
+            literal,
+            Doc::text(" as {"),
+            Doc::Nest(captures),
+            Doc::Line,
+            Doc::text("}"),
+        ])
+    }
+
+    fn rule(&mut self, rule: &Rule) -> Doc {
+        match rule {
+            Rule::Literal(l) => Doc::text(self.literal(l)),
+            Rule::CharClass(c) => self.char_class(c),
+            Rule::Group(g) => self.group(g),
+            Rule::Alternation(a) => self.alternatives(&a.rules, "|", "| "),
+            Rule::Intersection(i) => self.alternatives(&i.rules, "&", "& "),
+            Rule::Repetition(r) => {
+                let mut docs =
+                    vec![self.rule(&r.rule), Doc::text(quantifier(r.kind, r.quantifier))];
+                if let (Some(inner), Some(outer)) = (r.rule.span().range(), r.span.range()) {
+                    // comments before the quantifier are moved after it
+                    self.comments_in_gap(&mut docs, inner.end, outer.end);
+                }
+                Doc::Concat(docs)
+            }
+            Rule::Boundary(b) => Doc::text(match b.kind {
+                BoundaryKind::Start => "^",
+                BoundaryKind::End => "$",
+                BoundaryKind::Word => "%",
+                BoundaryKind::NotWord => "!%",
+                BoundaryKind::WordStart => "<",
+                BoundaryKind::WordEnd => ">",
+            }),
+            Rule::Lookaround(l) => {
+                let arrow = match l.kind {
+                    LookaroundKind::Ahead => ">>",
+                    LookaroundKind::Behind => "<<",
+                    LookaroundKind::AheadNegative => "!>>",
+                    LookaroundKind::BehindNegative => "!<<",
+                };
+                let mut inner = vec![Doc::Line];
+                if let Some(range) = l.rule.span().range() {
+                    self.leading_comments(&mut inner, range.start, None);
+                }
+                inner.push(self.rule(&l.rule));
+                Doc::group(vec![Doc::text(arrow), Doc::Nest(inner)])
+            }
+            Rule::Variable(v) => Doc::text(&v.name),
+            Rule::Reference(r) => Doc::text(match &r.target {
+                ReferenceTarget::Named(name) => format!("::{name}"),
+                ReferenceTarget::Number(n) => format!("::{n}"),
+                ReferenceTarget::Relative(n) => format!("::{n:+}"),
+            }),
+            Rule::Range(r) => {
+                let digits =
+                    |d: &[u8]| d.iter().map(|&d| char::from_digit(d.into(), 36).unwrap()).collect();
+                let start: String = digits(&r.start);
+                let end: String = digits(&r.end);
+                let mut out = format!("range '{start}'-'{end}'");
+                if r.radix != 10 {
+                    out.push_str(&format!(" base {}", r.radix));
+                }
+                Doc::text(out)
+            }
+            Rule::StmtExpr(_) => {
+                let mut docs = vec![];
+                self.statements(rule, &mut docs);
+                Doc::Concat(docs)
+            }
+            Rule::Negation(n) => Doc::Concat(vec![Doc::text("!"), self.rule(&n.rule)]),
+            Rule::Regex(r) => Doc::text(match r.span.range() {
+                Some(range) => {
+                    let content = self.source[range].strip_prefix("regex").unwrap_or_default();
+                    format!("regex {}", content.trim_start())
+                }
+                None => format!("regex {}", quote(&r.content)),
+            }),
+            Rule::Recursion(_) => Doc::text("recursion"),
+            Rule::Grapheme => Doc::text("Grapheme"),
+            Rule::Codepoint => Doc::text("Codepoint"),
+            Rule::Dot(_) => Doc::text("."),
+            Rule::Error(span) => {
+                Doc::text(self.source.get(span.range_unchecked()).unwrap_or_default().trim())
+            }
+        }
+    }
+
+    fn group(&mut self, group: &Group) -> Doc {
+        let open = match &group.kind {
+            GroupKind::Implicit => return self.sequence(&group.parts),
+            GroupKind::Normal => "(".to_string(),
+            GroupKind::Atomic => "atomic(".to_string(),
+            GroupKind::Capturing(capture) => match &capture.name {
+                Some(name) => format!(":{name}("),
+                None => ":(".to_string(),
+            },
+        };
+
+        let mut inner = vec![Doc::SoftLine];
+        if let Some(start) = group.parts.first().and_then(|part| part.span().range()) {
+            self.leading_comments(&mut inner, start.start, None);
+        }
+        match group.parts.as_slice() {
+            [rule @ Rule::StmtExpr(_)] => {
+                // statements must be on separate lines
+                inner[0] = Doc::HardLine;
+                inner.push(self.rule(rule));
+            }
+            [rule] => {
+                inner.push(self.rule(rule));
+                self.trailing_comment_of(&mut inner, rule);
+            }
+            parts => inner.push(self.sequence(parts)),
+        }
+        if let Some(range) = group.span.range() {
+            self.remaining_comments(&mut inner, range.end - 1);
+        }
+
+        Doc::block(vec![Doc::text(open), Doc::Nest(inner), Doc::SoftLine, Doc::text(")")])
+    }
+
+    fn sequence(&mut self, parts: &[Rule]) -> Doc {
+        let mut docs = vec![];
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                docs.push(Doc::Line);
+                if let Some(range) = part.span().range() {
+                    self.leading_comments(&mut docs, range.start, None);
+                }
+            }
+            docs.push(self.rule(part));
+            self.trailing_comment_of(&mut docs, part);
+        }
+        Doc::group(docs)
+    }
+
+    /// Prints alternatives or intersected expressions. If they don't fit on
+    /// one line, each one gets its own line, preceded by the operator
+    fn alternatives(&mut self, rules: &[Rule], op: &str, leading_op: &'static str) -> Doc {
+        let mut docs = vec![Doc::IfBreak(leading_op)];
+        for (i, rule) in rules.iter().enumerate() {
+            if i > 0 {
+                docs.push(Doc::Line);
+                if let Some(range) = rule.span().range() {
+                    self.leading_comments(&mut docs, range.start, None);
+                }
+                docs.push(Doc::text(format!("{op} ")));
+            }
+            docs.push(Doc::Nest(vec![self.rule(rule)]));
+            self.trailing_comment_of(&mut docs, rule);
+        }
+        Doc::group(docs)
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal.span.range() {
+            Some(range) => self.source[range].to_string(),
+            // the content of `()`
+            None if literal.content.is_empty() => String::new(),
+            None => quote(&literal.content),
+        }
+    }
+
+    fn char_class(&mut self, class: &CharClass) -> Doc {
+        let Some(range) = class.span.range() else {
+            let items = class.inner.iter().map(group_item).collect::<Vec<_>>();
+            return Doc::text(format!("[{}]", items.join(" ")));
+        };
+        if !self.source[range.clone()].starts_with('[') {
+            // a code point, e.g. `U+10`
+            return Doc::text(code_point(&self.source[range]));
+        }
+
+        let tokens = self.tokens_in(range.clone()).to_vec();
+        if self.comments.get(self.next_comment).is_none_or(|c| c.start >= range.end) {
+            let mut out = String::new();
+            let mut prev = None;
+            for (token, span) in tokens {
+                push_class_token(&mut out, &self.source[span.range_unchecked()], prev, token);
+                prev = Some(token);
+            }
+            return Doc::text(out);
+        }
+
+        // a class with comments is printed with one line per line of items,
+        // so that the comments stay where they were
+        let mut inner = vec![];
+        let mut line = String::new();
+        let mut prev = None;
+        let mut prev_end = range.start;
+        for (token, span) in &tokens[1..] {
+            let span = span.range_unchecked();
+            if !line.is_empty() {
+                inner.push(Doc::text(core::mem::take(&mut line)));
+            }
+            let has_comment = self.comments_in_gap(&mut inner, prev_end, span.start);
+            if *token == Token::CloseBracket {
+                break;
+            }
+            if prev.is_none() || has_comment {
+                inner.push(Doc::HardLine);
+                prev = None;
+            }
+            push_class_token(&mut line, &self.source[span.clone()], prev, *token);
+            prev = Some(*token);
+            prev_end = span.end;
+        }
+        Doc::Concat(vec![Doc::text("["), Doc::Nest(inner), Doc::HardLine, Doc::text("]")])
+    }
+
+    fn tokens_in(&self, range: Range<usize>) -> &[(Token, Span)] {
+        let start =
+            self.tokens.partition_point(|(_, span)| span.range_unchecked().start < range.start);
+        let end = self.tokens.partition_point(|(_, span)| span.range_unchecked().end <= range.end);
+        &self.tokens[start..end.max(start)]
+    }
+
+    /// Returns the end of a statement, including the `;` or `}`
+    fn stmt_end(&self, stmt: &Stmt) -> Option<usize> {
+        match stmt {
+            Stmt::Enable(_, span) | Stmt::Disable(_, span) => span.range().map(|r| r.end),
+            Stmt::Allow(a) => a.span.range().map(|r| r.end),
+            Stmt::Test(test) => test.span.range().map(|r| r.end),
+            Stmt::Let(l) => self.semicolon_after(l.name_span.range()?.end),
+        }
+    }
+
+    /// Returns the end of the first `;` after `pos` that isn't nested in
+    /// parentheses, brackets or braces
+    fn semicolon_after(&self, pos: usize) -> Option<usize> {
+        let start = self.tokens.partition_point(|(_, span)| span.range_unchecked().start < pos);
+        let mut depth = 0usize;
+        for &(token, span) in &self.tokens[start..] {
+            match token {
+                Token::OpenParen | Token::OpenBracket | Token::OpenBrace => depth += 1,
+                Token::CloseParen | Token::CloseBracket | Token::CloseBrace => {
+                    depth = depth.saturating_sub(1);
+                }
+                Token::Semicolon if depth == 0 => return Some(span.range_unchecked().end),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Returns the start of the `match` or `reject` keyword of a test case,
+    /// and the end of its `;`
+    fn test_case_range(&self, case: &TestCase) -> (Option<usize>, Option<usize>) {
+        let (first, last) = match case {
+            TestCase::Match(m) => {
+                (&m.literal, m.captures.last().map_or(&m.literal, |c| &c.literal))
+            }
+            TestCase::MatchAll(m) => {
+                (m.matches.first().map_or(&m.literal, |m| &m.literal), &m.literal)
+            }
+            TestCase::Reject(r) => (&r.literal, &r.literal),
+        };
+        let Some(first) = first.span.range() else { return (None, None) };
+
+        let idx =
+            self.tokens.partition_point(|(_, span)| span.range_unchecked().start < first.start);
+        let start = self.tokens[..idx]
+            .iter()
+            .rev()
+            .take(2)
+            .find(|&&(token, span)| {
+                token == Token::Identifier
+                    && matches!(&self.source[span.range_unchecked()], "match" | "reject")
+            })
+            .map(|(_, span)| span.range_unchecked().start);
+        let end = last.span.range().and_then(|r| self.semicolon_after(r.end));
+        (start, end)
+    }
+
+    /// Adds the comments before `pos` on their own lines. Blank lines after
+    /// the comments and after `prev_end` are preserved (but at most one)
+    fn leading_comments(&mut self, docs: &mut Vec<Doc>, pos: usize, mut prev_end: Option<usize>) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.start >= pos {
+                break;
+            }
+            if let Some(prev) = prev_end
+                && has_blank_line(&self.source[prev..comment.start])
+            {
+                docs.push(Doc::HardLine);
+            }
+            prev_end = Some(comment.end);
+            docs.push(Doc::text(&self.source[comment]));
+            docs.push(Doc::HardLine);
+            self.next_comment += 1;
+        }
+        if let Some(prev) = prev_end
+            && prev < pos
+            && has_blank_line(&self.source[prev..pos])
+        {
+            docs.push(Doc::HardLine);
+        }
+    }
+
+    /// Adds a comment that follows `end` on the same line
+    fn trailing_comment(&mut self, docs: &mut Vec<Doc>, end: usize) {
+        if let Some(comment) = self.comments.get(self.next_comment)
+            && comment.start >= end
+            && self.source[end..comment.start].chars().all(|c| matches!(c, ' ' | '\t' | '|' | '&'))
+        {
+            docs.push(Doc::LineSuffix(format!(" {}", &self.source[comment.clone()])));
+            self.next_comment += 1;
+        }
+    }
+
+    fn trailing_comment_of(&mut self, docs: &mut Vec<Doc>, rule: &Rule) {
+        if let Some(range) = rule.span().range() {
+            self.trailing_comment(docs, range.end);
+        }
+    }
+
+    /// Adds the comments between `prev_end` and `end`. A comment on the same
+    /// line as `prev_end` stays at the end of that line; the others get their
+    /// own lines. Returns whether there were any comments
+    fn comments_in_gap(&mut self, docs: &mut Vec<Doc>, mut prev_end: usize, end: usize) -> bool {
+        let mut found = false;
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.start >= end {
+                break;
+            }
+            if comment.start >= prev_end && !self.source[prev_end..comment.start].contains('\n') {
+                docs.push(Doc::LineSuffix(format!(" {}", &self.source[comment.clone()])));
+            } else {
+                docs.push(Doc::HardLine);
+                docs.push(Doc::text(&self.source[comment.clone()]));
+            }
+            prev_end = comment.end;
+            found = true;
+            self.next_comment += 1;
+        }
+        found
+    }
+
+    /// Adds all comments before `end` on their own lines
+    fn remaining_comments(&mut self, docs: &mut Vec<Doc>, end: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.start >= end {
+                break;
+            }
+            docs.push(Doc::HardLine);
+            docs.push(Doc::text(&self.source[comment]));
+            self.next_comment += 1;
+        }
+    }
+}
+
+/// Finds all comments, which are in the gaps between tokens
+fn find_comments(source: &str, tokens: &[(Token, Span)]) -> Vec<Range<usize>> {
+    let mut comments = vec![];
+    let mut gap_start = 0;
+    let gap_ends = tokens
+        .iter()
+        .map(|(_, span)| span.range_unchecked())
+        .chain(core::iter::once(source.len()..source.len()));
+    for token in gap_ends {
+        let mut pos = gap_start;
+        while let Some(offset) = source[pos..token.start].find('#') {
+            let start = pos + offset;
+            let len = source[start..token.start].find('\n').unwrap_or(token.start - start);
+            comments.push(start..start + source[start..start + len].trim_end().len());
+            pos = start + len;
+        }
+        gap_start = token.end;
+    }
+    comments
+}
+
+/// Appends a token of a character class, preceded by a space if needed
+fn push_class_token(out: &mut String, text: &str, prev: Option<Token>, token: Token) {
+    let no_space = matches!(
+        (prev, token),
+        (None, _)
+            | (Some(Token::OpenBracket | Token::Not | Token::Dash | Token::Colon), _)
+            | (_, Token::CloseBracket | Token::Dash | Token::Colon)
+    );
+    if !no_space {
+        out.push(' ');
+    }
+    if token == Token::CodePoint {
+        out.push_str(&code_point(text));
+    } else {
+        out.push_str(text);
+    }
+}
+
+fn has_blank_line(gap: &str) -> bool {
+    let mut lines = gap.split('\n');
+    lines.next();
+    lines.next_back();
+    lines.any(|line| line.trim().is_empty())
+}
+
+fn is_empty_literal(rule: &Rule) -> bool {
+    matches!(rule, Rule::Literal(l) if l.content.is_empty() && l.span.is_empty())
+}
+
+fn setting_name(setting: &BooleanSetting) -> &'static str {
+    match setting {
+        BooleanSetting::Lazy => "lazy",
+        BooleanSetting::Unicode => "unicode",
+    }
+}
+
+fn quantifier(kind: RepetitionKind, quantifier: Quantifier) -> String {
+    let mut out = match (kind.lower_bound, kind.upper_bound) {
+        (0, None) => "*".to_string(),
+        (1, None) => "+".to_string(),
+        (0, Some(1)) => "?".to_string(),
+        (lower, None) => format!("{{{lower},}}"),
+        (lower, Some(upper)) if lower == upper => format!("{{{lower}}}"),
+        (lower, Some(upper)) => format!("{{{lower},{upper}}}"),
+    };
+    match quantifier {
+        Quantifier::Greedy => out.push_str(" greedy"),
+        Quantifier::Lazy => out.push_str(" lazy"),
+        Quantifier::DefaultGreedy | Quantifier::DefaultLazy => {}
+    }
+    out
+}
+
+/// Removes whitespace from a code point, e.g. `U + 10`
+fn code_point(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn quote(content: &str) -> String {
+    if !content.contains('\'') {
+        format!("'{content}'")
+    } else {
+        format!("\"{}\"", content.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+fn group_item(item: &GroupItem) -> String {
+    fn char(c: char) -> String {
+        match c {
+            '\n' => "n".to_string(),
+            '\r' => "r".to_string(),
+            '\t' => "t".to_string(),
+            '\u{07}' => "a".to_string(),
+            '\u{1b}' => "e".to_string(),
+            '\u{0c}' => "f".to_string(),
+            _ if c.is_control() || (c.is_whitespace() && c != ' ') => {
+                format!("U+{:X}", c as u32)
+            }
+            _ => quote(c.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    match *item {
+        GroupItem::Char(c) => char(c),
+        GroupItem::Range { first, last } => format!("{}-{}", char(first), char(last)),
+        GroupItem::Named { name, negative, .. } => {
+            let not = if negative { "!" } else { "" };
+            let name = match name {
+                GroupName::Word => "word".to_string(),
+                GroupName::Digit => "digit".to_string(),
+                GroupName::Space => "space".to_string(),
+                GroupName::HorizSpace => "horiz_space".to_string(),
+                GroupName::VertSpace => "vert_space".to_string(),
+                GroupName::Category(c) => c.as_str().to_string(),
+                GroupName::Script(s, e) => format!("{}{}", e.as_str(), s.as_str()),
+                GroupName::CodeBlock(b) => b.as_str().to_string(),
+                GroupName::OtherProperties(p) => p.as_str().to_string(),
+            };
+            format!("{not}{name}")
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Classification of tokens for syntax highlighting.
+//!
+//! Unlike a highlighting grammar, this uses pomsky's lexer, so it always
+//! agrees with the parser about what is a string, a comment or a keyword. It
+//! also knows some context, e.g. that `match` is a keyword in `test` blocks,
+//! and that `Greek` in a character set is a Unicode script.
+//!
+//! ```
+//! use pomsky_syntax::highlight::{TokenClass, highlight};
+//!
+//! let source = "let x = [Greek w]; :name(x) # comment";
+//! let classes = highlight(source)
+//!     .into_iter()
+//!     .map(|(class, span)| (class, &source[span.range_unchecked()]))
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(classes[0], (TokenClass::Keyword, "let"));
+//! assert_eq!(classes[1], (TokenClass::Variable, "x"));
+//! assert_eq!(classes[4], (TokenClass::UnicodeProperty, "Greek"));
+//! assert_eq!(classes[5], (TokenClass::CharClassName, "w"));
+//! assert_eq!(classes[9], (TokenClass::GroupName, "name"));
+//! assert_eq!(classes[13], (TokenClass::Comment, "# comment"));
+//! ```
+
+use crate::{
+    Span,
+    cst::{TokenKind, lex_lossless},
+    exprs::{CharGroup, GroupItem, GroupName},
+    lexer::Token,
+};
+use alloc::vec::Vec;
+
+/// The class of a token, which determines how it is highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenClass {
+    /// A keyword such as `let`, or a contextual keyword such as `match` in a
+    /// `test` block, `unicode` in `enable unicode;` or `allow` in
+    /// `allow deprecated;`
+    Keyword,
+    /// A variable, including built-in variables such as `Start`
+    Variable,
+    /// A string literal
+    String,
+    /// A number or a code point such as `U+FF`
+    Number,
+    /// A shorthand or special character in a character set, e.g. `word` or `n`
+    CharClassName,
+    /// A Unicode category, script, block or property in a character set,
+    /// e.g. `Greek`, or a prefix such as `scx:`
+    UnicodeProperty,
+    /// The name of a capturing group, in a group, reference or test
+    GroupName,
+    /// An operator or delimiter, e.g. `|` or `(`
+    Operator,
+    /// A comment
+    Comment,
+    /// A token that is invalid, or an unknown name in a character set
+    Invalid,
+}
+
+impl TokenClass {
+    /// Returns the name of the class in lowercase, e.g. `"char-class-name"`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenClass::Keyword => "keyword",
+            TokenClass::Variable => "variable",
+            TokenClass::String => "string",
+            TokenClass::Number => "number",
+            TokenClass::CharClassName => "char-class-name",
+            TokenClass::UnicodeProperty => "unicode-property",
+            TokenClass::GroupName => "group-name",
+            TokenClass::Operator => "operator",
+            TokenClass::Comment => "comment",
+            TokenClass::Invalid => "invalid",
+        }
+    }
+}
+
+/// Classifies all tokens in the source code, except whitespace. This never
+/// fails; tokens that are invalid are classified as [`TokenClass::Invalid`].
+///
+/// Contextual keywords are only classified as keywords where they have a
+/// special meaning:
+///
+/// ```
+/// use pomsky_syntax::highlight::{TokenClass, highlight};
+///
+/// let source = "enable unicode; test { match 'a'; } match";
+/// let classes = highlight(source).into_iter().map(|(class, _)| class).collect::<Vec<_>>();
+/// assert_eq!(classes[1], TokenClass::Keyword); // unicode
+/// assert_eq!(classes[5], TokenClass::Keyword); // match
+/// assert_eq!(classes[9], TokenClass::Variable); // match
+/// ```
+pub fn highlight(source: &str) -> Vec<(TokenClass, Span)> {
+    let tokens = lex_lossless(source)
+        .into_iter()
+        .filter(|&(kind, _)| kind != TokenKind::Whitespace)
+        .map(|(kind, span)| (kind, span, &source[span.range_unchecked()]))
+        .collect::<Vec<_>>();
+
+    let mut classes = Vec::with_capacity(tokens.len());
+    let mut prev = None;
+    let mut set_depth = 0usize;
+    let mut test_depth = 0usize;
+
+    for (i, &(kind, span, text)) in tokens.iter().enumerate() {
+        let TokenKind::Token(token) = kind else {
+            classes.push((TokenClass::Comment, span));
+            continue;
+        };
+        let mut following = tokens[i + 1..].iter().filter_map(|&(kind, _, _)| match kind {
+            TokenKind::Token(token) => Some(token),
+            _ => None,
+        });
+        let next = following.next();
+        let is_allow = text == "allow"
+            && next == Some(Token::Identifier)
+            && following.next() == Some(Token::Semicolon);
+
+        let class = match token {
+            Token::ReservedName => TokenClass::Keyword,
+            Token::Identifier => match prev {
+                Some((Token::Colon, _)) if set_depth > 0 => TokenClass::UnicodeProperty,
+                Some((Token::Colon | Token::DoubleColon, _)) => TokenClass::GroupName,
+                _ if set_depth > 0 && next == Some(Token::Colon) => TokenClass::UnicodeProperty,
+                _ if set_depth > 0 => classify_set_item(text),
+                _ if test_depth > 0 && matches!(text, "match" | "reject" | "in" | "as") => {
+                    TokenClass::Keyword
+                }
+                _ if test_depth > 0 && next == Some(Token::Colon) => TokenClass::GroupName,
+                Some((Token::ReservedName, "enable" | "disable")) if text == "unicode" => {
+                    TokenClass::Keyword
+                }
+                _ if is_allow => TokenClass::Keyword,
+                Some((Token::Identifier, "allow")) if next == Some(Token::Semicolon) => {
+                    TokenClass::Keyword
+                }
+                _ => TokenClass::Variable,
+            },
+            Token::String => TokenClass::String,
+            Token::Number | Token::CodePoint => TokenClass::Number,
+            Token::ErrorMsg(_) | Token::Error => TokenClass::Invalid,
+            _ => TokenClass::Operator,
+        };
+        classes.push((class, span));
+
+        match token {
+            Token::OpenBracket => set_depth += 1,
+            Token::CloseBracket => set_depth = set_depth.saturating_sub(1),
+            Token::OpenBrace if test_depth > 0 || prev == Some((Token::ReservedName, "test")) => {
+                test_depth += 1;
+            }
+            Token::CloseBrace => test_depth = test_depth.saturating_sub(1),
+            _ => {}
+        }
+        prev = Some((token, text));
+    }
+    classes
+}
+
+fn classify_set_item(name: &str) -> TokenClass {
+    if let "n" | "r" | "t" | "a" | "e" | "f" = name {
+        return TokenClass::CharClassName;
+    }
+    match CharGroup::try_from_group_name(None, name, false, Span::empty()).as_deref() {
+        Ok(
+            [
+                GroupItem::Named {
+                    name:
+                        GroupName::Category(_)
+                        | GroupName::Script(..)
+                        | GroupName::CodeBlock(_)
+                        | GroupName::OtherProperties(_),
+                    ..
+                },
+            ],
+        ) => TokenClass::UnicodeProperty,
+        Ok(_) => TokenClass::CharClassName,
+        Err(_) => TokenClass::Invalid,
+    }
+}
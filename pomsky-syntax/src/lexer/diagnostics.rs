@@ -6,6 +6,10 @@
 //! the functions in this module.
 
 use super::LexErrorMsg;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 
 pub(super) fn get_parse_error_msg_help(msg: LexErrorMsg, slice: &str) -> Option<String> {
     Some(match msg {
@@ -1,5 +1,7 @@
 //! This module contains errors that can occur during lexing.
 
+use alloc::string::String;
+
 /// An error message for a token that is invalid in a pomsky expression.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -41,7 +43,7 @@ impl LexErrorMsg {
     }
 }
 
-impl std::error::Error for LexErrorMsg {}
+impl core::error::Error for LexErrorMsg {}
 
 impl core::fmt::Display for LexErrorMsg {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -8,3 +8,4 @@ pub use error::LexErrorMsg;
 pub use token::Token;
 
 pub(crate) use tokenize::tokenize;
+pub use tokenize::{is_reserved_word, parse_backslash, parse_special_group};
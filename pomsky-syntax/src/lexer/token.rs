@@ -91,7 +91,7 @@ pub enum Token {
 }
 
 impl core::fmt::Display for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str(match self {
             Token::Caret => "`^`",
             Token::Dollar => "`$`",
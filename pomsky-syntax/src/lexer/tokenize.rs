@@ -1,4 +1,5 @@
 use crate::Span;
+use alloc::{vec, vec::Vec};
 
 use super::{
     LexErrorMsg, Token,
@@ -42,6 +43,12 @@ macro_rules! reserved_word_pattern {
     );
 }
 
+/// Returns whether `word` is reserved, so it can't be used as a variable or
+/// group name.
+pub fn is_reserved_word(word: &str) -> bool {
+    matches!(word, reserved_word_pattern!())
+}
+
 static SINGLE_TOKEN_LOOKUP: [Option<Token>; 127] = const {
     let mut table = [const { None }; 127];
     table[b'^' as usize] = Some(Token::Caret);
@@ -176,7 +183,9 @@ fn find_unescaped_quote(input: &str) -> Option<usize> {
     }
 }
 
-fn parse_backslash(input: &str) -> Option<(usize, LexErrorMsg)> {
+/// Recognizes a regex escape sequence at the start of `input`, such as `\x41`
+/// or `\p{L}`. Returns its length and the kind of escape.
+pub fn parse_backslash(input: &str) -> Option<(usize, LexErrorMsg)> {
     let hex = CharIs(|c| c.is_ascii_hexdigit());
 
     let ident = Many1(CharIs(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '_')));
@@ -203,7 +212,10 @@ fn parse_backslash(input: &str) -> Option<(usize, LexErrorMsg)> {
     Capture(('\\', &after_backslash[..])).is_start(input).map(|(len, (_, err))| (len, err))
 }
 
-fn parse_special_group(input: &str) -> Option<(usize, LexErrorMsg)> {
+/// Recognizes the start of a regex group with special syntax at the start of
+/// `input`, such as `(?:` or `(?<name>`. Returns its length and the kind of
+/// group.
+pub fn parse_special_group(input: &str) -> Option<(usize, LexErrorMsg)> {
     let ident = Many1(CharIs(|c| c.is_ascii_alphanumeric() || c == '-' || c == '+'));
 
     let after_open: &[&dyn MicroRegex<Context = _>] = &[
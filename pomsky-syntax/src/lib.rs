@@ -9,6 +9,17 @@
 //! assert!(result.is_some());
 //! assert!(warnings.is_empty());
 //! ```
+//!
+//! ## Features
+//!
+//! The `std` feature is enabled by default. Without it, this crate is
+//! `no_std`, but it still requires the `alloc` crate.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod error;
 mod lexer;
@@ -20,10 +31,15 @@ mod warning;
 #[cfg(feature = "dbg")]
 mod pretty_print;
 
+pub mod cst;
 pub mod diagnose;
 pub mod exprs;
+pub mod format;
+pub mod highlight;
+pub mod line_index;
+pub mod visitor;
 
-pub use parse::parse;
+pub use parse::{parse, parse_partial};
 pub use span::Span;
 
 #[cfg(feature = "suggestions")]
@@ -34,3 +50,5 @@ use pretty_print::PrettyPrinter;
 
 #[doc(hidden)]
 pub use exprs::char_class::{blocks_supported_in_dotnet, list_shorthands, props_supported_in_java};
+#[doc(hidden)]
+pub use lexer::{is_reserved_word, parse_backslash, parse_special_group};
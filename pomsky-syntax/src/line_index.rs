@@ -0,0 +1,153 @@
+//! Converts byte offsets to lines and columns, and vice versa.
+//!
+//! [`Span`]s are byte offsets into the UTF-8 source code. Editors usually
+//! need a line and column instead, and JavaScript indexes strings by UTF-16
+//! code units, so [`LineIndex`] can count columns in different [`TextUnit`]s.
+//!
+//! ### Example
+//!
+//! ```
+//! use pomsky_syntax::{Span, line_index::{LineCol, LineIndex, TextUnit}};
+//!
+//! let source = "let x = 'ä';\n'😀' x";
+//! let index = LineIndex::new(source);
+//!
+//! // `x` in the second line
+//! assert_eq!(index.line_col(source, 21, TextUnit::Utf8), LineCol::new(1, 7));
+//! assert_eq!(index.line_col(source, 21, TextUnit::Utf16), LineCol::new(1, 5));
+//! assert_eq!(index.line_col(source, 21, TextUnit::Char), LineCol::new(1, 4));
+//! assert_eq!(index.offset(source, LineCol::new(1, 5), TextUnit::Utf16), 21);
+//!
+//! let span = Span::new(21, 22);
+//! let end = LineCol::new(1, 6);
+//! assert_eq!(index.span_line_cols(source, span, TextUnit::Utf16), Some((LineCol::new(1, 5), end)));
+//!
+//! // absolute offsets, e.g. for JavaScript
+//! assert_eq!(TextUnit::Utf16.offset(source, 21), 18);
+//! ```
+
+use crate::Span;
+use alloc::vec::Vec;
+
+/// The unit in which columns and offsets are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextUnit {
+    /// UTF-8 bytes, the unit of [`Span`]s
+    Utf8,
+    /// UTF-16 code units, as used by JavaScript, Java and C#, and by default
+    /// in the Language Server Protocol
+    Utf16,
+    /// Unicode scalar values, i.e. Rust `char`s
+    Char,
+}
+
+impl TextUnit {
+    /// Returns the length of the text in this unit
+    pub fn len(self, text: &str) -> usize {
+        match self {
+            TextUnit::Utf8 => text.len(),
+            TextUnit::Utf16 => text.chars().map(char::len_utf16).sum(),
+            TextUnit::Char => text.chars().count(),
+        }
+    }
+
+    /// Converts a byte offset in the text to an offset in this unit. If the
+    /// offset is in the middle of a character, it is rounded down.
+    pub fn offset(self, text: &str, offset: usize) -> usize {
+        self.len(&text[..floor_char_boundary(text, offset)])
+    }
+}
+
+/// A position in the source code, consisting of a line and a column. Both
+/// count from zero. The column is counted in a [`TextUnit`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    /// The line, counting from zero
+    pub line: usize,
+    /// The column within the line, counting from zero
+    pub col: usize,
+}
+
+impl LineCol {
+    /// Creates a new position
+    pub const fn new(line: usize, col: usize) -> Self {
+        LineCol { line, col }
+    }
+}
+
+/// Converts byte offsets to [`LineCol`]s and back.
+///
+/// It stores where each line starts. All methods must be called with the same
+/// text that was passed to [`LineIndex::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Creates a line index of the text. Lines are separated by `\n`; a
+    /// preceding `\r` is counted as part of the line.
+    pub fn new(text: &str) -> Self {
+        let line_starts =
+            core::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        LineIndex { line_starts }
+    }
+
+    /// Returns the number of lines. This is always at least 1.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Converts a byte offset to a line and column. Offsets past the end are
+    /// treated as the end of the text, offsets in the middle of a character
+    /// are rounded down.
+    pub fn line_col(&self, text: &str, offset: usize, unit: TextUnit) -> LineCol {
+        let offset = floor_char_boundary(text, offset);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        LineCol { line, col: unit.len(&text[start..offset]) }
+    }
+
+    /// Converts the start and end of a span to lines and columns. Returns
+    /// `None` if the span is empty.
+    pub fn span_line_cols(
+        &self,
+        text: &str,
+        span: Span,
+        unit: TextUnit,
+    ) -> Option<(LineCol, LineCol)> {
+        let range = span.range()?;
+        Some((self.line_col(text, range.start, unit), self.line_col(text, range.end, unit)))
+    }
+
+    /// Converts a line and column to a byte offset. A line past the end is
+    /// treated as the end of the text, and a column past the end of the line
+    /// as the end of the line.
+    pub fn offset(&self, text: &str, pos: LineCol, unit: TextUnit) -> usize {
+        let Some(&start) = self.line_starts.get(pos.line) else {
+            return text.len();
+        };
+        let line = text[start..].split('\n').next().unwrap_or_default();
+
+        let mut col = 0;
+        for (i, c) in line.char_indices() {
+            if col >= pos.col {
+                return start + i;
+            }
+            col += match unit {
+                TextUnit::Utf8 => c.len_utf8(),
+                TextUnit::Utf16 => c.len_utf16(),
+                TextUnit::Char => 1,
+            };
+        }
+        start + line.len()
+    }
+}
+
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    if offset >= text.len() {
+        text.len()
+    } else {
+        (0..=offset).rev().find(|&i| text.is_char_boundary(i)).unwrap_or(0)
+    }
+}
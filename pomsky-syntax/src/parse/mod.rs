@@ -4,6 +4,6 @@ mod helper;
 mod parser;
 mod parser_impl;
 
-pub use parser::parse;
+pub use parser::{parse, parse_partial};
 
 use parser::Parser;
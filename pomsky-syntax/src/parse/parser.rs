@@ -1,9 +1,11 @@
-use std::str::FromStr;
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::str::FromStr;
 
 use crate::{
     Span,
     diagnose::{
-        LexErrorMsg, NumberError, ParseDiagnostic, ParseError, ParseErrorKind as PEK, ParseWarning,
+        LexErrorMsg, NumberError, ParseDiagnostic, ParseError, ParseErrorKind,
+        ParseErrorKind as PEK, ParseWarning,
     },
     exprs::*,
     lexer::{Token, tokenize},
@@ -15,10 +17,48 @@ use crate::{
 /// expression. Note that **pomsky will overflow the stack** when parsing an
 /// expression with too much nesting, so the `recursion` argument should be low
 /// enough to prevent that. The recommended default is 256.
+///
+/// If the expression contains syntax errors, `None` is returned along with
+/// all the errors that were found. Use [`parse_partial`] to also get the
+/// syntax tree in that case.
 pub fn parse(source: &str, recursion: u32) -> (Option<Rule>, Vec<ParseDiagnostic>) {
+    let (rule, errors, warnings) = parse_impl(source, recursion);
+    let is_ok = errors.is_empty();
+    let mut diagnostics: Vec<ParseDiagnostic> = errors.into_iter().map(Into::into).collect();
+    diagnostics.extend(warnings);
+    (if is_ok { Some(rule) } else { None }, diagnostics)
+}
+
+/// Parses a source string as a pomsky expression, recovering from syntax
+/// errors.
+///
+/// Unlike [`parse`], this always returns a syntax tree. Parts of the
+/// expression that couldn't be parsed are represented as [`Rule::Error`]. This
+/// is useful for editor integrations, which need to analyze incomplete code.
+///
+/// ```
+/// use pomsky_syntax::{diagnose::ParseDiagnosticKind, exprs::Rule};
+///
+/// let (rule, diagnostics) = pomsky_syntax::parse_partial("'a' | [foo] | 'b' | 'c'{", 256);
+/// let Rule::Alternation(alt) = rule else { panic!() };
+/// assert!(matches!(alt.rules[1], Rule::Error(_)));
+/// assert!(matches!(alt.rules[2], Rule::Literal(_)));
+///
+/// let errors =
+///     diagnostics.iter().filter(|d| matches!(d.kind, ParseDiagnosticKind::Error(_))).count();
+/// assert_eq!(errors, 2);
+/// ```
+pub fn parse_partial(source: &str, recursion: u32) -> (Rule, Vec<ParseDiagnostic>) {
+    let (rule, errors, warnings) = parse_impl(source, recursion);
+    let mut diagnostics: Vec<ParseDiagnostic> = errors.into_iter().map(Into::into).collect();
+    diagnostics.extend(warnings);
+    (rule, diagnostics)
+}
+
+fn parse_impl(source: &str, recursion: u32) -> (Rule, Vec<ParseError>, Vec<ParseDiagnostic>) {
     if source.len() > u32::MAX as usize {
         let error = PEK::LexErrorWithMessage(LexErrorMsg::FileTooBig);
-        return (None, vec![error.at(Span::empty()).into()]);
+        return (Rule::Error(Span::empty()), vec![error.at(Span::empty())], vec![]);
     }
 
     let tokens = tokenize(source);
@@ -35,12 +75,10 @@ pub fn parse(source: &str, recursion: u32) -> (Option<Rule>, Vec<ParseDiagnostic
     if !errors.is_empty() {
         let errors = errors
             .into_iter()
-            .map(|(span, msg)| {
-                msg.map_or(PEK::UnknownToken, PEK::LexErrorWithMessage).at(span).into()
-            })
+            .map(|(span, msg)| msg.map_or(PEK::UnknownToken, PEK::LexErrorWithMessage).at(span))
             .collect::<Vec<_>>();
 
-        return (None, errors);
+        return (Rule::Error(Span::new(0, source.len())), errors, vec![]);
     }
 
     let mut parser = Parser {
@@ -48,6 +86,9 @@ pub fn parse(source: &str, recursion: u32) -> (Option<Rule>, Vec<ParseDiagnostic
         tokens: tokens.into_boxed_slice(),
         offset: 0,
         warnings: Vec::new(),
+        errors: Vec::new(),
+        delimiters: Vec::new(),
+        recovered_at: None,
         recursion,
         is_lazy: false,
         is_unicode_aware: true,
@@ -56,18 +97,14 @@ pub fn parse(source: &str, recursion: u32) -> (Option<Rule>, Vec<ParseDiagnostic
     let rule = match parser.parse_modified() {
         Ok(rule) => rule,
         Err(err) => {
-            let mut diagnostics = vec![err.into()];
-            diagnostics.extend(parser.warnings);
-            return (None, diagnostics);
+            parser.add_error(err);
+            Rule::Error(Span::new(0, source.len()))
         }
     };
-    if parser.is_empty() {
-        (Some(rule), parser.warnings)
-    } else {
-        let mut diagnostics = vec![PEK::LeftoverTokens.at(parser.span()).into()];
-        diagnostics.extend(parser.warnings);
-        (None, diagnostics)
+    if !parser.is_empty() {
+        parser.add_error_unless_recovered(PEK::LeftoverTokens.at(parser.span()));
     }
+    (rule, parser.errors, parser.warnings)
 }
 
 type PResult<T> = Result<T, ParseError>;
@@ -77,6 +114,12 @@ pub(super) struct Parser<'i> {
     tokens: Box<[(Token, Span)]>,
     offset: usize,
     warnings: Vec<ParseDiagnostic>,
+    errors: Vec<ParseError>,
+    /// Closing delimiters of the enclosing groups, which are not skipped when
+    /// recovering from a syntax error
+    delimiters: Vec<Token>,
+    /// Position where the parser resumed after the last syntax error
+    recovered_at: Option<usize>,
     recursion: u32,
     pub(super) is_lazy: bool,
     pub(super) is_unicode_aware: bool,
@@ -112,6 +155,31 @@ impl<'i> Parser<'i> {
         self.tokens[self.offset - 1].1
     }
 
+    /// Returns the token after the next token
+    pub(super) fn peek_second(&self) -> Option<(Token, Span)> {
+        self.tokens.get(self.offset + 1).copied()
+    }
+
+    /// Returns the token after the next two tokens
+    pub(super) fn peek_third(&self) -> Option<(Token, Span)> {
+        self.tokens.get(self.offset + 2).copied()
+    }
+
+    /// Returns the number of tokens consumed so far
+    pub(super) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the span of the tokens consumed since the given offset, or an
+    /// empty span at the next token if none were consumed
+    pub(super) fn span_since(&self, offset: usize) -> Span {
+        if self.offset > offset {
+            self.tokens[offset].1.join(self.last_span())
+        } else {
+            self.span().start()
+        }
+    }
+
     pub(super) fn advance(&mut self) {
         self.offset += 1;
     }
@@ -130,6 +198,91 @@ impl<'i> Parser<'i> {
         self.warnings.push(warning.into());
     }
 
+    pub(super) fn add_error(&mut self, error: ParseError) {
+        self.errors.push(error);
+    }
+
+    /// Returns whether the parser is where it resumed after the last syntax
+    /// error
+    pub(super) fn is_recovered(&self) -> bool {
+        self.recovered_at == Some(self.offset)
+    }
+
+    /// Records the error, unless the parser is where it resumed after the
+    /// previous error, in which case it is most likely a consequence of that
+    pub(super) fn add_error_unless_recovered(&mut self, error: ParseError) {
+        if !self.is_recovered() {
+            self.errors.push(error);
+        }
+    }
+
+    /// Runs the parser function. If it fails, the error is recorded and the
+    /// parser state is restored, except for the position; call
+    /// [`Parser::recover`] afterwards to skip the invalid tokens.
+    pub(super) fn catch<T>(&mut self, f: impl FnOnce(&mut Self) -> PResult<T>) -> Option<T> {
+        let recursion = self.recursion;
+        let is_lazy = self.is_lazy;
+        let is_unicode_aware = self.is_unicode_aware;
+        let delimiters = self.delimiters.len();
+
+        match f(self) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.recursion = recursion;
+                self.is_lazy = is_lazy;
+                self.is_unicode_aware = is_unicode_aware;
+                self.delimiters.truncate(delimiters);
+                self.add_error_unless_recovered(error);
+                None
+            }
+        }
+    }
+
+    /// Skips tokens after a syntax error until one of the `stop` tokens or the
+    /// closing delimiter of an enclosing group is reached. Delimited groups
+    /// that start within the skipped tokens are skipped entirely.
+    pub(super) fn recover(&mut self, stop: &[Token]) {
+        let mut nesting = Vec::new();
+        while let Some((t, _)) = self.peek_pair() {
+            if nesting.is_empty() && (stop.contains(&t) || self.delimiters.contains(&t)) {
+                break;
+            }
+            match t {
+                Token::OpenParen => nesting.push(Token::CloseParen),
+                Token::OpenBracket => nesting.push(Token::CloseBracket),
+                Token::OpenBrace => nesting.push(Token::CloseBrace),
+                // unmatched closing delimiters are skipped
+                Token::CloseParen | Token::CloseBracket | Token::CloseBrace
+                    if nesting.last() == Some(&t) =>
+                {
+                    nesting.pop();
+                }
+                _ => {}
+            }
+            self.offset += 1;
+        }
+        self.recovered_at = Some(self.offset);
+    }
+
+    /// Marks the start of a delimited group, which is closed by `closing`
+    pub(super) fn open_delimiter(&mut self, closing: Token) {
+        self.delimiters.push(closing);
+    }
+
+    /// Consumes the closing delimiter of the innermost group. If it is missing,
+    /// the error is recorded and tokens are skipped until it is found. Returns
+    /// whether it was present.
+    pub(super) fn close_delimiter(&mut self, error: ParseErrorKind) -> bool {
+        let closing = self.delimiters.pop().expect("no open delimiter");
+        if self.consume(closing) {
+            return true;
+        }
+        self.add_error_unless_recovered(error.at(self.span()));
+        self.recover(&[closing]);
+        self.consume(closing);
+        false
+    }
+
     pub(super) fn is(&mut self, token: Token) -> bool {
         matches!(self.peek_pair(), Some((t, _)) if t == token)
     }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeSet, string::ToString, vec, vec::Vec};
 
 use intersection::Intersection;
 
@@ -26,15 +26,7 @@ impl<'i> Parser<'i> {
         let was_lazy = self.is_lazy;
         let was_unicode_aware = self.is_unicode_aware;
 
-        loop {
-            let Some(stmt) = self
-                .parse_mode_modifier()?
-                .try_or_else(|| self.parse_let())?
-                .try_or_else(|| self.parse_test())?
-            else {
-                break;
-            };
-
+        while let Some(stmt) = self.parse_statement() {
             match &stmt.0 {
                 Stmt::Enable(BooleanSetting::Lazy, _) => self.is_lazy = true,
                 Stmt::Enable(BooleanSetting::Unicode, _) => self.is_unicode_aware = true,
@@ -47,7 +39,7 @@ impl<'i> Parser<'i> {
         }
 
         self.recursion_start()?;
-        let mut rule = self.parse_or()?;
+        let mut rule = self.parse_or();
         self.recursion_end();
 
         self.is_lazy = was_lazy;
@@ -55,11 +47,11 @@ impl<'i> Parser<'i> {
 
         // TODO: This should not be part of the parser
         if stmts.len() > 1 {
-            let mut set = HashSet::new();
+            let mut set = BTreeSet::new();
             for (stmt, _) in &stmts {
                 if let Stmt::Let(l) = stmt {
                     if set.contains(l.name()) {
-                        return Err(PEK::LetBindingExists.at(l.name_span));
+                        self.add_error(PEK::LetBindingExists.at(l.name_span));
                     }
                     set.insert(l.name());
                 }
@@ -74,6 +66,29 @@ impl<'i> Parser<'i> {
         Ok(rule)
     }
 
+    /// Parses a statement. If it contains a syntax error, the error is recorded
+    /// and the statement is skipped up to the next `;`.
+    fn parse_statement(&mut self) -> Option<(Stmt, Span)> {
+        loop {
+            let start = self.offset();
+            let stmt = self.catch(|p| {
+                p.parse_mode_modifier()?
+                    .try_or_else(|| p.parse_allow())?
+                    .try_or_else(|| p.parse_let())?
+                    .try_or_else(|| p.parse_test())
+            });
+            if let Some(stmt) = stmt {
+                return stmt;
+            }
+
+            self.recover(&[Token::Semicolon]);
+            self.consume(Token::Semicolon);
+            if self.offset() == start {
+                return None;
+            }
+        }
+    }
+
     fn parse_mode_modifier(&mut self) -> PResult<Option<(Stmt, Span)>> {
         let mode = if self.consume_reserved("enable") {
             true
@@ -100,45 +115,84 @@ impl<'i> Parser<'i> {
         Ok(Some((stmt, span)))
     }
 
+    /// Parses an `allow` statement. Since `allow` isn't a reserved word, it is
+    /// only recognized if it is followed by an identifier and a `;`.
+    fn parse_allow(&mut self) -> PResult<Option<(Stmt, Span)>> {
+        let (Some((Token::Identifier, "allow")), Some((Token::Identifier, lint_span))) =
+            (self.peek(), self.peek_second())
+        else {
+            return Ok(None);
+        };
+        let Some((Token::Semicolon, span_end)) = self.peek_third() else {
+            return Ok(None);
+        };
+        let span = self.span().join(span_end);
+        let lint = self.source_at(lint_span).to_owned();
+        self.advance();
+        self.advance();
+        self.advance();
+
+        Ok(Some((Stmt::Allow(Allow { lint, lint_span, span }), span)))
+    }
+
     fn parse_let(&mut self) -> PResult<Option<(Stmt, Span)>> {
-        if self.consume_reserved("let") {
-            let span_start = self.last_span();
-            let name_span = self.span();
-            let name = self.expect_as(Token::Identifier).map_err(|e| {
-                if self.is(Token::ReservedName) {
-                    PEK::KeywordAfterLet(self.source_at(self.span()).to_owned()).at(e.span)
-                } else {
-                    e
-                }
-            })?;
+        let span_start = if self.consume_reserved("let") {
+            self.last_span()
+        } else if let (Some((Token::Identifier, span1)), Some((Token::Equals, span2))) =
+            (self.peek_pair(), self.peek_second())
+        {
+            // parse it as a `let` statement anyway to avoid follow-up errors
+            self.add_error(PEK::MissingLetKeyword.at(span1.join(span2)));
+            span1
+        } else {
+            return Ok(None);
+        };
 
-            self.expect(Token::Equals)?;
+        let name_span = self.span();
+        let name = self.expect_as(Token::Identifier).map_err(|e| {
+            if self.is(Token::ReservedName) {
+                PEK::KeywordAfterLet(self.source_at(self.span()).to_owned()).at(e.span)
+            } else {
+                e
+            }
+        })?;
 
-            self.recursion_start()?;
-            let rule = self.parse_or()?;
-            self.recursion_end();
+        self.expect(Token::Equals)?;
 
-            self.expect(Token::Semicolon)
-                .map_err(|p| PEK::Expected("expression or `;`").at(p.span))?;
-            let span_end = self.last_span();
+        self.recursion_start()?;
+        let rule = self.parse_or();
+        self.recursion_end();
 
-            Ok(Some((Stmt::Let(Let::new(name, rule, name_span)), span_start.join(span_end))))
-        } else {
-            Ok(None)
+        if !self.consume(Token::Semicolon) {
+            self.add_error_unless_recovered(PEK::Expected("expression or `;`").at(self.span()));
+            self.recover(&[Token::Semicolon]);
+            self.consume(Token::Semicolon);
         }
+        let span_end = self.last_span();
+
+        Ok(Some((Stmt::Let(Let::new(name, rule, name_span)), span_start.join(span_end))))
     }
 
     fn parse_test(&mut self) -> PResult<Option<(Stmt, Span)>> {
         if self.consume_reserved("test") {
             let span_start = self.last_span();
             self.expect(Token::OpenBrace)?;
+            self.open_delimiter(Token::CloseBrace);
 
             let mut cases = Vec::new();
-            while let Some(case) = self.parse_test_cases()? {
-                cases.push(case);
+            loop {
+                match self.catch(Self::parse_test_cases) {
+                    Some(Some(case)) => cases.push(case),
+                    Some(None) => break,
+                    None => {
+                        // skip the invalid test case
+                        self.recover(&[Token::Semicolon]);
+                        self.consume(Token::Semicolon);
+                    }
+                }
             }
 
-            self.expect(Token::CloseBrace)?;
+            self.close_delimiter(PEK::ExpectedToken(Token::CloseBrace));
             let span_end = self.last_span();
             let span = span_start.join(span_end);
 
@@ -202,26 +256,36 @@ impl<'i> Parser<'i> {
 
         if self.consume_contextual_keyword("as") {
             self.expect(Token::OpenBrace)?;
+            self.open_delimiter(Token::CloseBrace);
 
-            let mut is_first = true;
-            loop {
-                if !is_first && !self.consume(Token::Comma) {
-                    break;
-                }
-                let Some(capture) = self.parse_test_capture()? else {
-                    break;
-                };
-                captures.push(capture);
-                is_first = false;
+            match self.catch(Self::parse_test_captures) {
+                Some(parsed) => captures = parsed,
+                None => self.recover(&[]),
             }
 
-            self.expect(Token::CloseBrace)?;
+            self.close_delimiter(PEK::ExpectedToken(Token::CloseBrace));
         }
 
         let span_end = self.last_span();
         Ok(TestCaseMatch { literal, captures, span: span_start.join(span_end) })
     }
 
+    fn parse_test_captures(&mut self) -> PResult<Vec<TestCapture>> {
+        let mut captures = Vec::new();
+        let mut is_first = true;
+        loop {
+            if !is_first && !self.consume(Token::Comma) {
+                break;
+            }
+            let Some(capture) = self.parse_test_capture()? else {
+                break;
+            };
+            captures.push(capture);
+            is_first = false;
+        }
+        Ok(captures)
+    }
+
     fn parse_test_capture(&mut self) -> PResult<Option<TestCapture>> {
         let ident = if let Some(n) = self.consume_number(u16::MAX)? {
             CaptureIdent::Index(n)
@@ -239,32 +303,56 @@ impl<'i> Parser<'i> {
         Ok(Some(TestCapture { ident, ident_span, literal }))
     }
 
-    fn parse_or(&mut self) -> PResult<Rule> {
+    fn parse_or(&mut self) -> Rule {
         let mut span = self.span();
         let leading_pipe = self.consume(Token::Pipe);
 
         let mut alts = Vec::new();
-        let Some(first_alt) = self.parse_and()? else {
-            if leading_pipe {
-                return Err(PEK::LonePipe.at(span));
-            } else {
-                return Ok(Rule::Literal(Literal::new("".to_string(), Span::default())));
-            }
-        };
-        alts.push(first_alt);
+        let first_alt = self.parse_alternative();
+        if first_alt.is_none() && !leading_pipe {
+            return Rule::Literal(Literal::new("".to_string(), Span::default()));
+        }
+        alts.push(first_alt.unwrap_or_else(|| self.lone_pipe(span, true)));
 
-        while self.consume(Token::Pipe) {
-            let Some(next_alt) = self.parse_and()? else {
-                return Err(PEK::LonePipe.at(self.last_span()));
-            };
+        loop {
+            let report = !self.is_recovered();
+            if !self.consume(Token::Pipe) {
+                break;
+            }
+            let pipe_span = self.last_span();
+            let next_alt =
+                self.parse_alternative().unwrap_or_else(|| self.lone_pipe(pipe_span, report));
             span = span.join(next_alt.span());
             alts.push(next_alt);
         }
 
         if alts.len() == 1 {
-            Ok(alts.pop().unwrap())
+            alts.pop().unwrap()
         } else {
-            Ok(Rule::Alternation(Alternation { rules: alts, span }))
+            Rule::Alternation(Alternation { rules: alts, span })
+        }
+    }
+
+    /// Handles a `|` that isn't followed by an expression. Subsequent tokens
+    /// that can't start an alternative, e.g. a repetition, are skipped.
+    fn lone_pipe(&mut self, pipe_span: Span, report: bool) -> Rule {
+        if report {
+            self.add_error(PEK::LonePipe.at(pipe_span));
+        }
+        self.recover(&[Token::Pipe, Token::Semicolon]);
+        Rule::Error(pipe_span)
+    }
+
+    /// Parses an alternative. If it contains a syntax error, the error is
+    /// recorded and the alternative is skipped up to the next `|`.
+    fn parse_alternative(&mut self) -> Option<Rule> {
+        let start = self.offset();
+        match self.catch(Self::parse_and) {
+            Some(rule) => rule,
+            None => {
+                self.recover(&[Token::Pipe, Token::Semicolon]);
+                Some(Rule::Error(self.span_since(start)))
+            }
         }
     }
 
@@ -463,17 +551,25 @@ impl<'i> Parser<'i> {
             return Ok(None);
         }
 
-        self.recursion_start()?;
-        let rule = self.parse_modified()?;
-        self.recursion_end();
+        self.open_delimiter(Token::CloseParen);
+        let rule = self.catch(|p| {
+            p.recursion_start()?;
+            let rule = p.parse_modified()?;
+            p.recursion_end();
+            Ok(rule)
+        });
+        if rule.is_none() {
+            self.recover(&[]);
+        }
 
-        self.expect(Token::CloseParen)
-            .map_err(|p| PEK::Expected("`)` or an expression").at(p.span))?;
+        self.close_delimiter(PEK::Expected("`)` or an expression"));
         // start_span may be 0..0, so we need to use join_unchecked
         let span = start_span.join_unchecked(self.last_span());
 
-        let rule = Rule::Group(Group::new(vec![rule], kind, span));
-        Ok(Some(rule))
+        Ok(Some(match rule {
+            Some(rule) => Rule::Group(Group::new(vec![rule], kind, span)),
+            None => Rule::Error(span),
+        }))
     }
 
     /// Parses `:name` or just `:`. Returns the span of the colon with the name.
@@ -527,21 +623,29 @@ impl<'i> Parser<'i> {
     fn parse_char_set(&mut self) -> PResult<Option<Rule>> {
         if self.consume(Token::OpenBracket) {
             let start_span = self.last_span();
+            self.open_delimiter(Token::CloseBracket);
 
-            if self.consume(Token::Caret) {
-                return Err(PEK::CharClass(CharClassError::CaretInGroup).at(self.last_span()));
+            let inner = self.catch(|p| {
+                if p.consume(Token::Caret) {
+                    return Err(PEK::CharClass(CharClassError::CaretInGroup).at(p.last_span()));
+                }
+                p.parse_char_set_inner()
+            });
+            if inner.is_none() {
+                self.recover(&[]);
             }
 
-            let inner = self.parse_char_set_inner()?;
-
-            self.expect(Token::CloseBracket).map_err(|p| {
-                PEK::Expected("character class, string, code point, Unicode property or `]`")
-                    .at(p.span)
-            })?;
+            let closed = self.close_delimiter(PEK::Expected(
+                "character class, string, code point, Unicode property or `]`",
+            ));
             let span = start_span.join(self.last_span());
 
+            let Some(inner) = inner else { return Ok(Some(Rule::Error(span))) };
             if inner.is_empty() {
-                return Err(PEK::CharClass(CharClassError::Empty).at(span));
+                if closed {
+                    self.add_error(PEK::CharClass(CharClassError::Empty).at(span));
+                }
+                return Ok(Some(Rule::Error(span)));
             }
 
             Ok(Some(Rule::CharClass(CharClass::new(inner, span, self.is_unicode_aware))))
@@ -850,7 +954,7 @@ impl<'i> Parser<'i> {
 
     /// Parses the dot
     fn parse_dot(&mut self) -> Option<Rule> {
-        if self.consume(Token::Dot) { Some(Rule::Dot) } else { None }
+        if self.consume(Token::Dot) { Some(Rule::Dot(self.last_span())) } else { None }
     }
 
     /// Parses the `recursion` keyword
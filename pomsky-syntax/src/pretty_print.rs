@@ -1,4 +1,5 @@
-use std::fmt::{Debug, Display};
+use alloc::{format, string::String};
+use core::fmt::{Debug, Display};
 
 pub(crate) struct PrettyPrinter {
     buf: String,
@@ -15,7 +16,7 @@ impl PrettyPrinter {
     }
 
     fn add_spaces(&mut self, n: usize) {
-        self.buf.extend(std::iter::repeat_n(' ', n));
+        self.buf.extend(core::iter::repeat_n(' ', n));
     }
 
     pub(crate) fn write(&mut self, s: &str) {
@@ -63,19 +64,19 @@ impl PrettyPrinter {
         self.buf.push_str(paren);
         self.indent += 2;
         self.buf.push('\n');
-        self.buf.extend(std::iter::repeat_n(' ', self.indent));
+        self.buf.extend(core::iter::repeat_n(' ', self.indent));
     }
 
     pub(crate) fn end_indentation(&mut self, paren: &str) {
         debug_assert!(!paren.contains('\n'));
         self.indent = self.indent.saturating_sub(2);
         self.buf.push('\n');
-        self.buf.extend(std::iter::repeat_n(' ', self.indent));
+        self.buf.extend(core::iter::repeat_n(' ', self.indent));
         self.buf.push_str(paren);
     }
 
     pub(crate) fn pretty_print_char(&mut self, char: char) {
-        use std::fmt::Write;
+        use core::fmt::Write;
 
         // fast path first
         if char == ' ' || char.is_ascii_graphic() {
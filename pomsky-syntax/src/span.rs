@@ -1,4 +1,4 @@
-use std::{
+use core::{
     fmt::{Debug, Display},
     ops::Range,
 };
@@ -7,6 +7,7 @@ use std::{
 /// zero, this is considered as "empty" or "missing", and [`Span::range`]
 /// returns `None`.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Span {
     start: u32,
     end: u32,
@@ -31,13 +32,13 @@ impl Span {
         self.end == 0
     }
 
-    /// Converts this span to a [`std::ops::Range`]. If it is empty, `None` is
+    /// Converts this span to a [`core::ops::Range`]. If it is empty, `None` is
     /// returned.
     pub fn range(self) -> Option<Range<usize>> {
         if self.is_empty() { None } else { Some(self.start as usize..self.end as usize) }
     }
 
-    /// Converts this span to a [`std::ops::Range`], without checking if it is
+    /// Converts this span to a [`core::ops::Range`], without checking if it is
     /// empty.
     pub fn range_unchecked(self) -> Range<usize> {
         self.start as usize..self.end as usize
@@ -79,13 +80,13 @@ impl Default for Span {
 }
 
 impl Display for Span {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}..{}", self.start, self.end)
     }
 }
 
 impl Debug for Span {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Span({}..{})", self.start, self.end)
     }
 }
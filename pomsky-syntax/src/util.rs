@@ -2,7 +2,10 @@
 /// and returns the option with the lowest distance, if the distance is lower
 /// than a certain threshold.
 #[cfg(feature = "suggestions")]
-pub fn find_suggestion<'a>(name: &str, options: impl Iterator<Item = &'a str>) -> Option<Box<str>> {
+pub fn find_suggestion<'a>(
+    name: &str,
+    options: impl Iterator<Item = &'a str>,
+) -> Option<alloc::boxed::Box<str>> {
     options
         .map(|option| (option, strsim::jaro_winkler(option, name)))
         .max_by(|(_, score1), (_, score2)| f64::total_cmp(score1, score2))
@@ -0,0 +1,465 @@
+//! Traversal of the syntax tree.
+//!
+//! Implement [`RuleVisitor`] to inspect a [`Rule`] tree, or [`RuleVisitorMut`]
+//! to modify it in place. Every hook has a default implementation that does
+//! nothing, so only the relevant ones need to be implemented. Returning an
+//! error from a hook stops the traversal immediately.
+//!
+//! ## Example
+//!
+//! ```
+//! use pomsky_syntax::{Span, exprs, visitor::RuleVisitor};
+//!
+//! struct NoRegex;
+//!
+//! impl RuleVisitor<Span> for NoRegex {
+//!     fn visit_regex(&mut self, regex: &exprs::Regex) -> Result<(), Span> {
+//!         Err(regex.span)
+//!     }
+//! }
+//!
+//! let (rule, _) = pomsky_syntax::parse("'a' | regex '[b-d]'", 256);
+//! assert_eq!(NoRegex.visit_rule(&rule.unwrap()), Err(Span::new(6, 19)));
+//! ```
+
+use crate::{
+    Span,
+    exprs::{self, Rule, Stmt, test::TestCase},
+};
+
+/// The kind of node whose children are visited between a call to
+/// [`RuleVisitor::down`] and [`RuleVisitor::up`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NestingKind {
+    Group,
+    Alternation,
+    Intersection,
+    Repetition,
+    Lookaround,
+    /// The expression following a statement
+    StmtExpr,
+    /// The right side of a `let` statement
+    Let,
+    /// The cases of a `test` block
+    Test,
+    Negation,
+}
+
+/// Visits the rule and all its descendants in source order
+pub fn visit<V: RuleVisitor<E>, E>(rule: &Rule, visitor: &mut V) -> Result<(), E> {
+    visitor.enter_rule(rule)?;
+    match rule {
+        Rule::Literal(l) => visitor.visit_literal(l),
+        Rule::CharClass(c) => visitor.visit_char_class(c),
+        Rule::Group(g) => {
+            visitor.visit_group(g)?;
+            visitor.down(NestingKind::Group);
+            for rule in &g.parts {
+                visit(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Group);
+            Ok(())
+        }
+        Rule::Alternation(a) => {
+            visitor.visit_alternation(a)?;
+            visitor.down(NestingKind::Alternation);
+            for rule in &a.rules {
+                visit(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Alternation);
+            Ok(())
+        }
+        Rule::Intersection(i) => {
+            visitor.visit_intersection(i)?;
+            visitor.down(NestingKind::Intersection);
+            for rule in &i.rules {
+                visit(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Intersection);
+            Ok(())
+        }
+        Rule::Repetition(r) => {
+            visitor.visit_repetition(r)?;
+            visitor.down(NestingKind::Repetition);
+            visit(&r.rule, visitor)?;
+            visitor.up(NestingKind::Repetition);
+            Ok(())
+        }
+        Rule::Boundary(b) => visitor.visit_boundary(b),
+        Rule::Lookaround(l) => {
+            visitor.visit_lookaround(l)?;
+            visitor.down(NestingKind::Lookaround);
+            visit(&l.rule, visitor)?;
+            visitor.up(NestingKind::Lookaround);
+            Ok(())
+        }
+        Rule::Variable(v) => visitor.visit_variable(v),
+        Rule::Reference(r) => visitor.visit_reference(r),
+        Rule::Range(r) => visitor.visit_range(r),
+        Rule::StmtExpr(s) => {
+            visitor.visit_statement(&s.stmt)?;
+            match &s.stmt {
+                Stmt::Let(l) => {
+                    visitor.down(NestingKind::Let);
+                    visit(&l.rule, visitor)?;
+                    visitor.up(NestingKind::Let);
+                }
+                Stmt::Test(t) => {
+                    visitor.down(NestingKind::Test);
+                    for case in &t.cases {
+                        visitor.visit_test_case(case)?;
+                    }
+                    visitor.up(NestingKind::Test);
+                }
+                Stmt::Enable(..) | Stmt::Disable(..) | Stmt::Allow(_) => {}
+            }
+            visitor.down(NestingKind::StmtExpr);
+            visit(&s.rule, visitor)?;
+            visitor.up(NestingKind::StmtExpr);
+            Ok(())
+        }
+        Rule::Negation(n) => {
+            visitor.visit_negation(n)?;
+            visitor.down(NestingKind::Negation);
+            visit(&n.rule, visitor)?;
+            visitor.up(NestingKind::Negation);
+            Ok(())
+        }
+        Rule::Regex(r) => visitor.visit_regex(r),
+        Rule::Recursion(r) => visitor.visit_recursion(r),
+        Rule::Grapheme => visitor.visit_grapheme(),
+        Rule::Codepoint => visitor.visit_codepoint(),
+        Rule::Dot(_) => visitor.visit_dot(),
+        &Rule::Error(span) => visitor.visit_error(span),
+    }
+}
+
+/// Visits the rule and all its descendants in source order, allowing them to
+/// be modified
+pub fn visit_mut<V: RuleVisitorMut<E>, E>(rule: &mut Rule, visitor: &mut V) -> Result<(), E> {
+    visitor.enter_rule(rule)?;
+    match rule {
+        Rule::Literal(l) => visitor.visit_literal(l),
+        Rule::CharClass(c) => visitor.visit_char_class(c),
+        Rule::Group(g) => {
+            visitor.visit_group(g)?;
+            visitor.down(NestingKind::Group);
+            for rule in &mut g.parts {
+                visit_mut(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Group);
+            Ok(())
+        }
+        Rule::Alternation(a) => {
+            visitor.visit_alternation(a)?;
+            visitor.down(NestingKind::Alternation);
+            for rule in &mut a.rules {
+                visit_mut(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Alternation);
+            Ok(())
+        }
+        Rule::Intersection(i) => {
+            visitor.visit_intersection(i)?;
+            visitor.down(NestingKind::Intersection);
+            for rule in &mut i.rules {
+                visit_mut(rule, visitor)?;
+            }
+            visitor.up(NestingKind::Intersection);
+            Ok(())
+        }
+        Rule::Repetition(r) => {
+            visitor.visit_repetition(r)?;
+            visitor.down(NestingKind::Repetition);
+            visit_mut(&mut r.rule, visitor)?;
+            visitor.up(NestingKind::Repetition);
+            Ok(())
+        }
+        Rule::Boundary(b) => visitor.visit_boundary(b),
+        Rule::Lookaround(l) => {
+            visitor.visit_lookaround(l)?;
+            visitor.down(NestingKind::Lookaround);
+            visit_mut(&mut l.rule, visitor)?;
+            visitor.up(NestingKind::Lookaround);
+            Ok(())
+        }
+        Rule::Variable(v) => visitor.visit_variable(v),
+        Rule::Reference(r) => visitor.visit_reference(r),
+        Rule::Range(r) => visitor.visit_range(r),
+        Rule::StmtExpr(s) => {
+            visitor.visit_statement(&mut s.stmt)?;
+            match &mut s.stmt {
+                Stmt::Let(l) => {
+                    visitor.down(NestingKind::Let);
+                    visit_mut(&mut l.rule, visitor)?;
+                    visitor.up(NestingKind::Let);
+                }
+                Stmt::Test(t) => {
+                    visitor.down(NestingKind::Test);
+                    for case in &mut t.cases {
+                        visitor.visit_test_case(case)?;
+                    }
+                    visitor.up(NestingKind::Test);
+                }
+                Stmt::Enable(..) | Stmt::Disable(..) | Stmt::Allow(_) => {}
+            }
+            visitor.down(NestingKind::StmtExpr);
+            visit_mut(&mut s.rule, visitor)?;
+            visitor.up(NestingKind::StmtExpr);
+            Ok(())
+        }
+        Rule::Negation(n) => {
+            visitor.visit_negation(n)?;
+            visitor.down(NestingKind::Negation);
+            visit_mut(&mut n.rule, visitor)?;
+            visitor.up(NestingKind::Negation);
+            Ok(())
+        }
+        Rule::Regex(r) => visitor.visit_regex(r),
+        Rule::Recursion(r) => visitor.visit_recursion(r),
+        Rule::Grapheme => visitor.visit_grapheme(),
+        Rule::Codepoint => visitor.visit_codepoint(),
+        Rule::Dot(_) => visitor.visit_dot(),
+        &mut Rule::Error(span) => visitor.visit_error(span),
+    }
+}
+
+/// A visitor for the syntax tree; see the [module documentation](self).
+///
+/// For every rule, [`enter_rule`](RuleVisitor::enter_rule) is called first,
+/// then the hook specific to the rule's kind. If the rule has children, they
+/// are visited between a call to [`down`](RuleVisitor::down) and
+/// [`up`](RuleVisitor::up).
+#[allow(unused_variables)]
+pub trait RuleVisitor<E> {
+    /// Visits the rule and all its descendants
+    fn visit_rule(&mut self, rule: &Rule) -> Result<(), E>
+    where
+        Self: Sized,
+    {
+        visit(rule, self)
+    }
+
+    fn down(&mut self, kind: NestingKind) {}
+
+    fn up(&mut self, kind: NestingKind) {}
+
+    /// Called for every rule before the hook specific to its kind
+    fn enter_rule(&mut self, rule: &Rule) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_literal(&mut self, literal: &exprs::Literal) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_char_class(&mut self, char_class: &exprs::CharClass) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_group(&mut self, group: &exprs::Group) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_alternation(&mut self, alt: &exprs::Alternation) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_intersection(&mut self, int: &exprs::Intersection) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_repetition(&mut self, repetition: &exprs::Repetition) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_boundary(&mut self, boundary: &exprs::Boundary) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_lookaround(&mut self, lookaround: &exprs::Lookaround) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_variable(&mut self, variable: &exprs::Variable) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_reference(&mut self, reference: &exprs::Reference) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_range(&mut self, range: &exprs::Range) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_statement(&mut self, statement: &exprs::Stmt) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Called for every case in a `test` block
+    fn visit_test_case(&mut self, test_case: &TestCase) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_negation(&mut self, negation: &exprs::Negation) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_regex(&mut self, regex: &exprs::Regex) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_recursion(&mut self, recursion: &exprs::Recursion) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_grapheme(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_codepoint(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_dot(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Called for parts of the expression that contain a syntax error
+    fn visit_error(&mut self, span: Span) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// A visitor that can modify the syntax tree; see the
+/// [module documentation](self).
+///
+/// The hooks are called in the same order as in [`RuleVisitor`]. A rule can be
+/// replaced entirely in [`enter_rule`](RuleVisitorMut::enter_rule); its
+/// children are visited after it was replaced.
+///
+/// ```
+/// use pomsky_syntax::{exprs::Rule, visitor::RuleVisitorMut};
+///
+/// struct DotToGrapheme;
+///
+/// impl RuleVisitorMut<()> for DotToGrapheme {
+///     fn enter_rule(&mut self, rule: &mut Rule) -> Result<(), ()> {
+///         if let Rule::Dot(_) = rule {
+///             *rule = Rule::Grapheme;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let (rule, _) = pomsky_syntax::parse("let x = .; x+", 256);
+/// let mut rule = rule.unwrap();
+/// DotToGrapheme.visit_rule(&mut rule).unwrap();
+///
+/// let Rule::StmtExpr(s) = &rule else { panic!() };
+/// let pomsky_syntax::exprs::Stmt::Let(l) = &s.stmt else { panic!() };
+/// assert!(matches!(l.rule, Rule::Grapheme));
+/// ```
+#[allow(unused_variables)]
+pub trait RuleVisitorMut<E> {
+    /// Visits the rule and all its descendants
+    fn visit_rule(&mut self, rule: &mut Rule) -> Result<(), E>
+    where
+        Self: Sized,
+    {
+        visit_mut(rule, self)
+    }
+
+    fn down(&mut self, kind: NestingKind) {}
+
+    fn up(&mut self, kind: NestingKind) {}
+
+    /// Called for every rule before the hook specific to its kind
+    fn enter_rule(&mut self, rule: &mut Rule) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_literal(&mut self, literal: &mut exprs::Literal) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_char_class(&mut self, char_class: &mut exprs::CharClass) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_group(&mut self, group: &mut exprs::Group) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_alternation(&mut self, alt: &mut exprs::Alternation) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_intersection(&mut self, int: &mut exprs::Intersection) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_repetition(&mut self, repetition: &mut exprs::Repetition) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_boundary(&mut self, boundary: &mut exprs::Boundary) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_lookaround(&mut self, lookaround: &mut exprs::Lookaround) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_variable(&mut self, variable: &mut exprs::Variable) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_reference(&mut self, reference: &mut exprs::Reference) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_range(&mut self, range: &mut exprs::Range) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_statement(&mut self, statement: &mut exprs::Stmt) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Called for every case in a `test` block
+    fn visit_test_case(&mut self, test_case: &mut TestCase) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_negation(&mut self, negation: &mut exprs::Negation) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_regex(&mut self, regex: &mut exprs::Regex) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_recursion(&mut self, recursion: &mut exprs::Recursion) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_grapheme(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_codepoint(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_dot(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    /// Called for parts of the expression that contain a syntax error
+    fn visit_error(&mut self, span: Span) -> Result<(), E> {
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Provides warnings that are shown to the user (in addition to the output)
 
-use std::fmt;
+use alloc::string::String;
+use core::fmt;
 
 use crate::span::Span;
 
@@ -29,7 +30,7 @@ impl ParseWarningKind {
 impl fmt::Display for ParseWarning {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let ParseWarningKind::Deprecation(warning) = &self.kind;
-        if let Some(std::ops::Range { start, end }) = self.span.range() {
+        if let Some(core::ops::Range { start, end }) = self.span.range() {
             write!(f, "{warning}\n  at {start}..{end}")
         } else {
             write!(f, "{warning}")
@@ -38,3 +38,42 @@ export class PomskyResult {
     this.tests = tests
   }
 }
+
+export class PomskyToken {
+  /**
+   * @param {string} kind
+   * @param {[number, number]} range
+   */
+  constructor(kind, range) {
+    this.kind = kind
+    this.range = [range[0], range[1]]
+  }
+}
+
+export class PomskyExplanation {
+  /**
+   * @param {string} description
+   * @param {[number, number]} range
+   * @param {string | null} variable
+   * @param {string[]} notes
+   * @param {PomskyExplanation[]} children
+   */
+  constructor(description, range, variable, notes, children) {
+    this.description = description
+    this.range = [range[0], range[1]]
+    this.variable = variable
+    this.notes = notes
+    this.children = children
+  }
+}
+
+export class PomskyExplainResult {
+  /**
+   * @param {PomskyExplanation | null} explanation
+   * @param {PomskyDiagnostic[]} diagnostics
+   */
+  constructor(explanation, diagnostics) {
+    this.explanation = explanation
+    this.diagnostics = diagnostics
+  }
+}
@@ -3,6 +3,8 @@ use std::ops::Range;
 use js_sys::{Array, Object, Reflect};
 use pomsky::{
     diagnose::Diagnostic,
+    explain::Explanation,
+    line_index::TextUnit,
     options::{CompileOptions, RegexFlavor},
     test::{Test, TestCapture, TestCase, TestCaseMatch, TestCaseMatchAll, TestCaseReject},
     Expr, Span,
@@ -41,6 +43,33 @@ extern "C" {
         warnings: Vec<PomskyDiagnostic>,
         tests: Option<Array>,
     ) -> PomskyResult;
+
+    #[wasm_bindgen(typescript_type = "PomskyExplanation")]
+    pub type PomskyExplanation;
+
+    #[wasm_bindgen(constructor)]
+    fn new(
+        description: String,
+        range: &[usize],
+        variable: Option<String>,
+        notes: Vec<String>,
+        children: Vec<PomskyExplanation>,
+    ) -> PomskyExplanation;
+
+    #[wasm_bindgen(typescript_type = "PomskyExplainResult")]
+    pub type PomskyExplainResult;
+
+    #[wasm_bindgen(constructor)]
+    fn new(
+        explanation: Option<PomskyExplanation>,
+        diagnostics: Vec<PomskyDiagnostic>,
+    ) -> PomskyExplainResult;
+
+    #[wasm_bindgen(typescript_type = "PomskyToken")]
+    pub type PomskyToken;
+
+    #[wasm_bindgen(constructor)]
+    fn new(kind: &str, range: &[usize]) -> PomskyToken;
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -56,6 +85,34 @@ interface PomskyDiagnostic {
 
 interface PomskyError extends Error {}
 
+interface PomskyExplanation {
+    description: string;
+    range: [number, number];
+    variable: string | null;
+    notes: string[];
+    children: PomskyExplanation[];
+}
+
+interface PomskyExplainResult {
+    explanation: PomskyExplanation | null;
+    diagnostics: PomskyDiagnostic[];
+}
+
+interface PomskyToken {
+    kind:
+        | "keyword"
+        | "variable"
+        | "string"
+        | "number"
+        | "char-class-name"
+        | "unicode-property"
+        | "group-name"
+        | "operator"
+        | "comment"
+        | "invalid";
+    range: [number, number];
+}
+
 interface PomskyResult {
     output: string | null;
     diagnostics: PomskyDiagnostic[];
@@ -95,7 +152,8 @@ interface PomskyTestCapture {
 
 #[wasm_bindgen]
 /// Compile a pomsky expression. It returns a `PomskyResult`, or throws
-/// a `PomskyError` if the input is invalid.
+/// a `PomskyError` if the input is invalid. The `range`s of diagnostics and
+/// tests are in UTF-16 code units, so they can be used to index `input`.
 ///
 /// `flavor` must be one of the following values:
 ///  - "javascript" or "js"
@@ -103,8 +161,10 @@ interface PomskyTestCapture {
 ///  - "dotnet" or ".net"
 ///  - "pcre"
 ///  - "python"
+///  - "python-regex"
 ///  - "ruby"
 ///  - "rust"
+///  - "postgres" or "postgresql"
 pub fn compile(input: &str, flavor: &str) -> Result<PomskyResult, PomskyError> {
     utils::set_panic_hook();
 
@@ -113,54 +173,93 @@ pub fn compile(input: &str, flavor: &str) -> Result<PomskyResult, PomskyError> {
 
     let (result, diagnostics, tests) = Expr::parse_and_compile(
         input,
-        CompileOptions { flavor, max_range_size: 12, ..Default::default() },
+        CompileOptions::untrusted().with_flavor(flavor).with_max_range_size(12),
     );
 
     Ok(PomskyResult::new(
         result,
         diagnostics.into_iter().map(|d| convert_diagnostic(input, d)).collect(),
-        tests_to_js(tests),
+        tests_to_js(input, tests),
     ))
 }
 
-fn tests_to_js(tests: Vec<Test>) -> Option<Array> {
-    fn range(span: Span) -> Array {
-        let range = span.range().unwrap_or(0..0);
-        Array::from_iter([
-            JsValue::from_f64(range.start as f64),
-            JsValue::from_f64(range.end as f64),
-        ])
+#[wasm_bindgen]
+/// Explain a pomsky expression in English. It returns a `PomskyExplainResult`,
+/// or throws a `PomskyError` if the flavor is invalid. The explanation is a
+/// tree of `PomskyExplanation`s, whose ranges are in UTF-16 code units.
+///
+/// `flavor` accepts the same values as in `compile`.
+pub fn explain(input: &str, flavor: &str) -> Result<PomskyExplainResult, PomskyError> {
+    utils::set_panic_hook();
+
+    let flavor = parse_flavor(flavor)
+        .ok_or_else(|| PomskyError::new(format!("Unknown regex flavor `{flavor}`")))?;
+
+    let (explanation, diagnostics) = pomsky::explain::explain(input, flavor);
+
+    Ok(PomskyExplainResult::new(
+        explanation.map(|e| convert_explanation(input, e)),
+        diagnostics.into_iter().map(|d| convert_diagnostic(input, d)).collect(),
+    ))
+}
+
+#[wasm_bindgen]
+/// Classify the tokens of a pomsky expression for syntax highlighting. It
+/// returns an array of `PomskyToken`s, whose ranges are in UTF-16 code units.
+/// Whitespace is omitted.
+pub fn highlight(input: &str) -> Vec<PomskyToken> {
+    utils::set_panic_hook();
+
+    let mut offset = 0;
+    let mut offset16 = 0;
+    pomsky::highlight::highlight(input)
+        .into_iter()
+        .map(|(class, span)| {
+            let Range { start, end } = span.range_unchecked();
+            let start16 = offset16 + input[offset..start].encode_utf16().count();
+            let end16 = start16 + input[start..end].encode_utf16().count();
+            (offset, offset16) = (end, end16);
+
+            PomskyToken::new(class.as_str(), &[start16, end16])
+        })
+        .collect()
+}
+
+fn tests_to_js(input: &str, tests: Vec<Test>) -> Option<Array> {
+    fn range(input: &str, span: Span) -> Array {
+        let [start, end] = utf16_range(input, span);
+        Array::from_iter([JsValue::from_f64(start as f64), JsValue::from_f64(end as f64)])
     }
 
-    fn match_to_js(match_: TestCaseMatch) -> Object {
+    fn match_to_js(input: &str, match_: TestCaseMatch) -> Object {
         let obj = Object::new();
         Reflect::set(&obj, &"literal".into(), &match_.literal.content.into()).unwrap();
-        Reflect::set(&obj, &"range".into(), &range(match_.literal.span)).unwrap();
+        Reflect::set(&obj, &"range".into(), &range(input, match_.literal.span)).unwrap();
 
-        let captures = match_.captures.into_iter().map(capture_to_js);
+        let captures = match_.captures.into_iter().map(|c| capture_to_js(input, c));
         Reflect::set(&obj, &"captures".into(), &Array::from_iter(captures)).unwrap();
         obj
     }
 
-    fn match_all_to_js(match_all: TestCaseMatchAll) -> Object {
+    fn match_all_to_js(input: &str, match_all: TestCaseMatchAll) -> Object {
         let obj = Object::new();
         Reflect::set(&obj, &"literal".into(), &match_all.literal.content.into()).unwrap();
-        Reflect::set(&obj, &"range".into(), &range(match_all.literal.span)).unwrap();
+        Reflect::set(&obj, &"range".into(), &range(input, match_all.literal.span)).unwrap();
 
-        let matches = match_all.matches.into_iter().map(match_to_js);
+        let matches = match_all.matches.into_iter().map(|m| match_to_js(input, m));
         Reflect::set(&obj, &"matches".into(), &Array::from_iter(matches)).unwrap();
         obj
     }
 
-    fn reject_to_js(reject: TestCaseReject) -> Object {
+    fn reject_to_js(input: &str, reject: TestCaseReject) -> Object {
         let obj = Object::new();
         Reflect::set(&obj, &"literal".into(), &reject.literal.content.into()).unwrap();
-        Reflect::set(&obj, &"range".into(), &range(reject.literal.span)).unwrap();
+        Reflect::set(&obj, &"range".into(), &range(input, reject.literal.span)).unwrap();
         Reflect::set(&obj, &"asSubstring".into(), &reject.as_substring.into()).unwrap();
         obj
     }
 
-    fn capture_to_js(capture: TestCapture) -> Object {
+    fn capture_to_js(input: &str, capture: TestCapture) -> Object {
         let obj = Object::new();
 
         let ident = match capture.ident {
@@ -168,10 +267,10 @@ fn tests_to_js(tests: Vec<Test>) -> Option<Array> {
             pomsky::test::CaptureIdent::Index(idx) => idx.into(),
         };
         Reflect::set(&obj, &"ident".into(), &ident).unwrap();
-        Reflect::set(&obj, &"identRange".into(), &range(capture.ident_span)).unwrap();
+        Reflect::set(&obj, &"identRange".into(), &range(input, capture.ident_span)).unwrap();
 
         Reflect::set(&obj, &"literal".into(), &capture.literal.content.into()).unwrap();
-        Reflect::set(&obj, &"range".into(), &range(capture.literal.span)).unwrap();
+        Reflect::set(&obj, &"range".into(), &range(input, capture.literal.span)).unwrap();
         obj
     }
 
@@ -185,13 +284,14 @@ fn tests_to_js(tests: Vec<Test>) -> Option<Array> {
             let case_obj = Object::new();
             match case {
                 TestCase::Match(m) => {
-                    Reflect::set(&case_obj, &"match".into(), &match_to_js(m)).unwrap();
+                    Reflect::set(&case_obj, &"match".into(), &match_to_js(input, m)).unwrap();
                 }
                 TestCase::MatchAll(m) => {
-                    Reflect::set(&case_obj, &"matchAll".into(), &match_all_to_js(m)).unwrap();
+                    Reflect::set(&case_obj, &"matchAll".into(), &match_all_to_js(input, m))
+                        .unwrap();
                 }
                 TestCase::Reject(r) => {
-                    Reflect::set(&case_obj, &"reject".into(), &reject_to_js(r)).unwrap();
+                    Reflect::set(&case_obj, &"reject".into(), &reject_to_js(input, r)).unwrap();
                 }
             }
             tests_obj.push(&case_obj);
@@ -207,30 +307,37 @@ fn parse_flavor(flavor: &str) -> Option<RegexFlavor> {
         "dotnet" | ".net" => RegexFlavor::DotNet,
         "pcre" => RegexFlavor::Pcre,
         "python" => RegexFlavor::Python,
+        "python-regex" => RegexFlavor::PythonRegex,
         "ruby" => RegexFlavor::Ruby,
         "rust" => RegexFlavor::Rust,
+        "postgres" | "postgresql" => RegexFlavor::Postgres,
         _ => return None,
     })
 }
 
 fn convert_diagnostic(input: &str, d: Diagnostic) -> PomskyDiagnostic {
-    let Range { start, end } = d.span.range().unwrap_or_default();
-    let (prefix, content, _) = split_in_three(input, start, end);
-    let start16 = prefix.encode_utf16().count();
-    let end16 = start16 + content.encode_utf16().count();
-
     PomskyDiagnostic::new(
         d.severity.into(),
         d.kind.into(),
         d.code.map(|c| c.to_string()),
         d.msg,
         d.help,
-        &[start16, end16],
+        &utf16_range(input, d.span),
+    )
+}
+
+fn convert_explanation(input: &str, e: Explanation) -> PomskyExplanation {
+    PomskyExplanation::new(
+        e.description,
+        &utf16_range(input, e.span),
+        e.variable,
+        e.notes,
+        e.children.into_iter().map(|c| convert_explanation(input, c)).collect(),
     )
 }
 
-fn split_in_three(input: &str, cut1: usize, cut2: usize) -> (&str, &str, &str) {
-    let (rest, suffix) = input.split_at(cut2);
-    let (prefix, middle) = rest.split_at(cut1);
-    (prefix, middle, suffix)
+/// Converts a span to a range in UTF-16 code units, as used by JavaScript
+fn utf16_range(input: &str, span: Span) -> [usize; 2] {
+    let Range { start, end } = span.range().unwrap_or_default();
+    [TextUnit::Utf16.offset(input, start), TextUnit::Utf16.offset(input, end)]
 }
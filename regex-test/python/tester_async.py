@@ -1,14 +1,17 @@
 import sys
-import re
+import importlib
 import fileinput
 
+# either `re` or the third-party `regex` module
+re = importlib.import_module(sys.argv[1] if len(sys.argv) > 1 else "re")
+
 def substituteLf(s):
     return s.replace("\\", "\\\\").replace("\n", "\\n")
 
 regex = None
 
 try:
-    for line in fileinput.input():
+    for line in fileinput.input(files=("-",)):
         if line.endswith("\r\n"):
             line = line[:-2]
         elif line.endswith("\n"):
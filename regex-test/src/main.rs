@@ -9,6 +9,7 @@ fn main() {
     test.java.kill().unwrap();
     test.js.kill().unwrap();
     test.py.kill().unwrap();
+    test.py_regex.kill().unwrap();
     test.dotnet.kill().unwrap();
 
     if let Err(e) = res {
@@ -27,6 +28,7 @@ fn main_impl(test: &RegexTest) -> Result<(), String> {
         Flavor::Js => test.test_js_with(&args.input, &args.test),
         Flavor::Java => test.test_java_with(&args.input, &args.test),
         Flavor::Python => test.test_python_with(&args.input, &args.test),
+        Flavor::PythonRegex => test.test_python_regex_with(&args.input, &args.test),
         #[cfg(feature = "re2")]
         Flavor::RE2 => test.test_re2_with(&args.input, &args.test),
     };
@@ -47,6 +49,7 @@ enum Flavor {
     Js,
     Java,
     Python,
+    PythonRegex,
     DotNet,
     #[cfg(feature = "re2")]
     RE2,
@@ -64,6 +67,7 @@ impl FromStr for Flavor {
             "js" | "javascript" => Flavor::Js,
             "java" => Flavor::Java,
             "py" | "python" => Flavor::Python,
+            "py-regex" | "python-regex" => Flavor::PythonRegex,
             ".net" | "dotnet" => Flavor::DotNet,
             #[cfg(feature = "re2")]
             "re2" => Flavor::RE2,
@@ -164,7 +168,7 @@ USAGE:
     regex-test -f <FLAVOR> INPUT [-t TEST]...
 
 FLAVORS:
-    pcre, rust, ruby, js, java, python"
+    pcre, rust, ruby, js, java, python, python-regex"
     );
     exit(0);
 }
@@ -12,6 +12,7 @@ pub struct RegexTest {
     pub js: Process,
     pub java: Process,
     pub py: Process,
+    pub py_regex: Process,
     pub dotnet: Process,
     pub rust: Count,
     pub pcre: Count,
@@ -30,17 +31,20 @@ impl RegexTest {
             scope.spawn(|| self.test_js("x"));
             scope.spawn(|| self.test_java("x"));
             scope.spawn(|| self.test_python("x"));
+            scope.spawn(|| self.test_python_regex("x"));
             scope.spawn(|| self.test_dotnet("x"));
         });
         self.js.reset_count();
         self.java.reset_count();
         self.py.reset_count();
+        self.py_regex.reset_count();
         self.dotnet.reset_count();
     }
 
     pub fn kill_processes(&self) -> io::Result<()> {
         self.js.kill()?;
         self.py.kill()?;
+        self.py_regex.kill()?;
         self.java.kill()?;
         self.dotnet.kill()?;
         Ok(())
@@ -94,6 +98,19 @@ impl RegexTest {
         self.py.test(regex, tests)
     }
 
+    pub fn test_python_regex(&self, regex: impl AsRef<str>) -> Outcome {
+        self.test_python_regex_with(regex, &[] as &[&str])
+    }
+
+    pub fn test_python_regex_with(
+        &self,
+        regex: impl AsRef<str>,
+        tests: &[impl AsRef<str>],
+    ) -> Outcome {
+        self.py_regex.start("python", "python", &["tester_async.py", "regex"]);
+        self.py_regex.test(regex, tests)
+    }
+
     pub fn test_java(&self, regex: impl AsRef<str>) -> Outcome {
         self.test_java_with(regex, &[] as &[&str])
     }